@@ -0,0 +1,317 @@
+//! Command-line interface of the `chute` binary.
+//! - Without a subcommand the TUI starts; with one, it runs headless (see `headless`).
+//! - Global options may appear before or after the subcommand.
+//! - Usage errors exit with code 2, matching `headless::ErrorKind::Usage`.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use chute_kun::config;
+use chute_kun::date;
+use chute_kun::headless::{Command, ExportFormat, ImportFrom, ListWhich, TaskRef};
+use chute_kun::task::{self, EstimateArg};
+use chute_kun::{ics, timelog};
+
+#[derive(Debug, Parser)]
+#[command(name = "chute", version, about = "TaskChute-style day planner for the terminal")]
+pub struct Cli {
+    /// Snapshot file (default: CHUTE_KUN_STATE or the XDG data dir).
+    /// `state_path` in the config file takes precedence over this option.
+    #[arg(long, value_name = "PATH", global = true)]
+    pub state: Option<PathBuf>,
+
+    /// Config file (default: CHUTE_KUN_CONFIG or $XDG_CONFIG_HOME/chute_kun/config.toml)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Named profile: overlay config `profiles/<NAME>.toml` and a separate snapshot
+    /// (default: CHUTE_KUN_PROFILE)
+    #[arg(long, value_name = "NAME", value_parser = parse_profile, global = true)]
+    pub profile: Option<String>,
+
+    /// Treat DATE (YYYY-MM-DD) as today; same as CHUTE_KUN_TODAY
+    #[arg(long, value_name = "DATE", value_parser = parse_today, global = true)]
+    pub today: Option<String>,
+
+    /// Log filter such as `warn` or `chute_kun=debug` (default: RUST_LOG)
+    #[arg(long, value_name = "LEVEL", value_parser = parse_log_level, global = true)]
+    pub log_level: Option<String>,
+
+    /// Print headless command results and errors as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Write the default config file (if missing) and exit
+    #[arg(long, conflicts_with = "set_day_start")]
+    pub init_config: bool,
+
+    /// Set `day_start` in the config file and exit
+    #[arg(long, value_name = "HH:MM", value_parser = parse_day_start)]
+    pub set_day_start: Option<(u16, u16)>,
+
+    #[command(subcommand)]
+    pub command: Option<Sub>,
+}
+
+/// Headless subcommands. TASK is a 1-based Today position or a title match.
+#[derive(Debug, Subcommand)]
+pub enum Sub {
+    /// Add a task to Today (or Future with a later --date).
+    /// TITLE may carry quick-add words: 45m @work ^14:00 >tomorrow #tag
+    Add {
+        #[arg(required = true, value_name = "TITLE")]
+        title: Vec<String>,
+        /// Estimate in minutes (e.g. 30 or 30m)
+        #[arg(short, long, value_name = "MIN", value_parser = parse_minutes)]
+        estimate: Option<u16>,
+        /// today, tomorrow or YYYY-MM-DD
+        #[arg(short, long, value_name = "DATE", value_parser = parse_date)]
+        date: Option<String>,
+    },
+    /// List tasks of a view
+    #[command(visible_alias = "ls")]
+    List {
+        #[arg(value_enum, default_value_t = ListArg::Today)]
+        which: ListArg,
+    },
+    /// Start or resume a task
+    Start {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+    },
+    /// Pause the active task
+    Pause,
+    /// Finish a task (default: the active one)
+    Finish {
+        #[arg(value_parser = parse_task)]
+        task: Option<TaskRef>,
+    },
+    /// Move a task to tomorrow
+    Postpone {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+    },
+    /// Delete a task
+    #[command(visible_alias = "rm")]
+    Delete {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+    },
+    /// Move a task to a 1-based position
+    #[command(visible_alias = "mv")]
+    Move {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+        #[arg(value_parser = parse_position)]
+        to: usize,
+    },
+    /// Set (30, 30m) or adjust (+15m, -5) the estimate
+    #[command(visible_alias = "est")]
+    Estimate {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+        #[arg(value_name = "MIN", allow_hyphen_values = true, value_parser = parse_estimate)]
+        value: EstimateArg,
+    },
+    /// Pin the start time (HH:MM) or clear it (none)
+    At {
+        #[arg(value_parser = parse_task)]
+        task: TaskRef,
+        #[arg(value_name = "HH:MM", value_parser = parse_at)]
+        time: FixedStart,
+    },
+    /// Import tasks from another tool (todoist: `[todoist] filter`, token in TODOIST_API_TOKEN;
+    /// ics: today's events of FILE or of `[ics] paths`)
+    Import {
+        #[arg(value_enum)]
+        from: ImportArg,
+        /// Calendar file for `ics`
+        #[arg(value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+    /// Export the plan and recent sessions as iCalendar, or the time log as CSV/JSON
+    Export {
+        #[arg(long, value_enum, default_value_t = FormatArg::Ics)]
+        format: FormatArg,
+        /// ics: only planned blocks or only recorded sessions
+        #[arg(long, value_enum, default_value_t = PartArg::All)]
+        only: PartArg,
+        /// csv/json: first date (default: today, or --to when earlier)
+        #[arg(long, value_name = "DATE", value_parser = parse_date)]
+        from: Option<String>,
+        /// csv/json: last date (default: today, or --from when later)
+        #[arg(long, value_name = "DATE", value_parser = parse_date)]
+        to: Option<String>,
+        /// csv/json: one row per session or per task
+        #[arg(long, value_enum, default_value_t = RowsArg::Sessions)]
+        rows: RowsArg,
+        /// csv/json: round durations to the nearest N minutes
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+        round: Option<u16>,
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Markdown report of a day: plan vs actual, category totals, interruptions
+    Report {
+        #[arg(value_enum)]
+        kind: ReportArg,
+        /// Day to report (default: today)
+        #[arg(value_name = "DATE", value_parser = parse_date)]
+        date: Option<String>,
+        /// Markdown template with {{placeholders}} (default: `[report] template`)
+        #[arg(long, value_name = "FILE")]
+        template: Option<PathBuf>,
+        /// Write to a file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ListArg {
+    Today,
+    Future,
+    Past,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ImportArg {
+    Todoist,
+    Ics,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportArg {
+    Day,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FormatArg {
+    Ics,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RowsArg {
+    Sessions,
+    Tasks,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PartArg {
+    All,
+    Plan,
+    Actual,
+}
+
+/// Parsed `at` argument; `None` clears the fixed start.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedStart(Option<u16>);
+
+impl Sub {
+    pub fn into_command(self) -> Command {
+        match self {
+            // Resolved here rather than in the value parser so `--today` is already applied.
+            Sub::Add { title, estimate, date } => Command::Add {
+                title: title.join(" ").trim().to_string(),
+                estimate_min: estimate,
+                ymd: date.as_deref().and_then(date::parse_date_arg),
+            },
+            Sub::List { which } => Command::List(match which {
+                ListArg::Today => ListWhich::Today,
+                ListArg::Future => ListWhich::Future,
+                ListArg::Past => ListWhich::Past,
+            }),
+            Sub::Start { task } => Command::Start(task),
+            Sub::Pause => Command::Pause,
+            Sub::Finish { task } => Command::Finish(task),
+            Sub::Postpone { task } => Command::Postpone(task),
+            Sub::Delete { task } => Command::Delete(task),
+            Sub::Move { task, to } => Command::Move { task, to },
+            Sub::Estimate { task, value } => Command::Estimate { task, value },
+            Sub::At { task, time } => Command::At { task, minutes: time.0 },
+            Sub::Import { from, file } => Command::Import(match from {
+                ImportArg::Todoist => ImportFrom::Todoist,
+                ImportArg::Ics => ImportFrom::Ics(file),
+            }),
+            Sub::Export { format, only, from, to, rows, round, output } => {
+                let query = timelog::Query {
+                    from: from.as_deref().and_then(date::parse_date_arg),
+                    to: to.as_deref().and_then(date::parse_date_arg),
+                    rows: match rows {
+                        RowsArg::Sessions => timelog::Rows::Sessions,
+                        RowsArg::Tasks => timelog::Rows::Tasks,
+                    },
+                    round_min: round,
+                };
+                let format = match format {
+                    FormatArg::Ics => ExportFormat::Ics(match only {
+                        PartArg::All => ics::Part::All,
+                        PartArg::Plan => ics::Part::Plan,
+                        PartArg::Actual => ics::Part::Actual,
+                    }),
+                    FormatArg::Csv => ExportFormat::Csv(query),
+                    FormatArg::Json => ExportFormat::Json(query),
+                };
+                Command::Export { format, output }
+            }
+            Sub::Report { kind: ReportArg::Day, date, template, output } => Command::Report {
+                ymd: date.as_deref().and_then(date::parse_date_arg),
+                template,
+                output,
+            },
+        }
+    }
+}
+
+fn parse_today(s: &str) -> Result<String, String> {
+    date::parse_ymd(s).map(|_| s.to_string()).ok_or_else(|| "expected YYYY-MM-DD".to_string())
+}
+
+fn parse_profile(s: &str) -> Result<String, String> {
+    if config::is_valid_profile_name(s) {
+        Ok(s.to_string())
+    } else {
+        Err("use letters, digits, '-' or '_'".into())
+    }
+}
+
+fn parse_log_level(s: &str) -> Result<String, String> {
+    tracing_subscriber::EnvFilter::try_new(s).map(|_| s.to_string()).map_err(|e| e.to_string())
+}
+
+fn parse_day_start(s: &str) -> Result<(u16, u16), String> {
+    config::parse_hhmm_or_compact(s).map_err(|e| e.to_string())
+}
+
+fn parse_minutes(s: &str) -> Result<u16, String> {
+    s.trim_end_matches('m').parse::<u16>().map_err(|_| "expected minutes like 30 or 30m".into())
+}
+
+fn parse_date(s: &str) -> Result<String, String> {
+    date::parse_date_arg(s)
+        .map(|_| s.to_string())
+        .ok_or_else(|| "expected today, tomorrow or YYYY-MM-DD".into())
+}
+
+fn parse_task(s: &str) -> Result<TaskRef, String> {
+    if s.trim().is_empty() {
+        return Err("expected a position or a title".into());
+    }
+    Ok(TaskRef::parse(s))
+}
+
+fn parse_position(s: &str) -> Result<usize, String> {
+    s.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(|| "expected a 1-based position".into())
+}
+
+fn parse_estimate(s: &str) -> Result<EstimateArg, String> {
+    EstimateArg::parse(s).ok_or_else(|| "expected minutes like 30, 30m, +15m or -5".into())
+}
+
+fn parse_at(s: &str) -> Result<FixedStart, String> {
+    task::parse_at_arg(s).map(FixedStart).map_err(|e| e.to_string())
+}
@@ -1,7 +1,11 @@
 use std::io::stdout;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::event::{
     KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
@@ -9,9 +13,17 @@ use crossterm::event::{
 use crossterm::{cursor, event, execute, terminal};
 use ratatui::{backend::CrosstermBackend, Terminal};
 
+use chute_kun::app::App;
 use chute_kun::config::{self, Config};
+use chute_kun::headless;
+use chute_kun::journal::{self, Journal};
 use chute_kun::storage;
-use chute_kun::{app::App, ui};
+use chute_kun::todoist::WriteBack;
+use chute_kun::toggl::TogglSync;
+use chute_kun::ui;
+
+mod args;
+use args::Cli;
 
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<std::io::Stdout>>> {
     terminal::enable_raw_mode()?;
@@ -45,79 +57,178 @@ fn restore_terminal(mut terminal: Terminal<CrosstermBackend<std::io::Stdout>>) -
     Ok(())
 }
 
-// ---- Helpers for CLI-only features ----
-
-// shared helpers moved to chute_kun::config
-
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.command.is_some() && (cli.init_config || cli.set_day_start.is_some()) {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, "--init-config/--set-day-start take no subcommand")
+            .exit();
+    }
+    // Config, profile and date overrides go through the same env vars the library already reads,
+    // so every code path (load, --init-config, --set-day-start) sees them.
+    if let Some(path) = &cli.config {
+        std::env::set_var("CHUTE_KUN_CONFIG", path);
+    }
+    if let Some(profile) = &cli.profile {
+        std::env::set_var("CHUTE_KUN_PROFILE", profile);
+    }
+    if let Some(today) = &cli.today {
+        std::env::set_var("CHUTE_KUN_TODAY", today);
+    }
+
     color_eyre::install().ok();
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .with_target(false)
-        .compact()
-        .init();
-
-    // Simple flag handling to initialize config and exit.
-    if std::env::args().any(|a| a == "--init-config") {
+    let filter = match &cli.log_level {
+        Some(level) => tracing_subscriber::EnvFilter::new(level),
+        None => tracing_subscriber::EnvFilter::from_default_env(),
+    };
+    tracing_subscriber::fmt().with_env_filter(filter).with_target(false).compact().init();
+
+    if cli.init_config {
         let path = Config::write_default_file()?;
         println!("wrote config to {}", path.display());
         return Ok(());
     }
 
-    // Optional: set day_start value and exit (persistent).
-    // Usage: --set-day-start HH:MM | HHMM
-    // This updates the `day_start = "HH:MM"` line in the config TOML,
+    // Persistently update the `day_start = "HH:MM"` line in the config TOML,
     // creating the config file if it does not exist.
-    {
-        let mut args = std::env::args().skip(1);
-        while let Some(a) = args.next() {
-            if a == "--set-day-start" {
-                let Some(val) = args.next() else {
-                    eprintln!("--set-day-start requires a value like HH:MM");
-                    std::process::exit(2);
-                };
-                // Validate and normalize value (accept HH:MM or compact HHMM)
-                let (hh, mm) = config::parse_hhmm_or_compact(&val)?;
-                let normalized = format!("{:02}:{:02}", hh, mm);
-                let path = config::write_day_start(hh, mm)?;
-                println!("updated day_start to {} at {}", normalized, path.display());
-                return Ok(());
-            }
-        }
+    if let Some((hh, mm)) = cli.set_day_start {
+        let path = config::write_day_start(hh, mm)?;
+        println!("updated day_start to {:02}:{:02} at {}", hh, mm, path.display());
+        return Ok(());
     }
 
-    // Optional state path override: --state <path>
-    let mut args = std::env::args().skip(1);
-    let mut state_path_override: Option<std::path::PathBuf> = None;
-    while let Some(a) = args.next() {
-        if a == "--state" {
-            if let Some(p) = args.next() {
-                state_path_override = Some(std::path::PathBuf::from(p));
-            } else {
-                eprintln!("--state requires a file path");
-                std::process::exit(2);
-            }
-        }
+    let state_path_override = cli.state;
+    if let Some(sub) = cli.command {
+        std::process::exit(run_headless(sub.into_command(), state_path_override, cli.json));
     }
 
-    let mut terminal = setup_terminal()?;
-
     // Load config and state snapshot (if found) from XDG data path or override.
     let cfg = Config::load();
     let chosen_path = storage::resolve_state_path(&cfg, state_path_override)
         .expect("could not resolve default state path");
 
+    // Held until exit; headless changes wait for it instead of racing the autosave
+    let Some(_lock) = storage::StateLock::try_acquire(&chosen_path)? else {
+        eprintln!("chute: {} is already open in another chute", chosen_path.display());
+        std::process::exit(1);
+    };
     let mut app = match storage::load_from_path(&chosen_path, cfg.clone())? {
         Some(a) => a,
-        None => App::with_config(cfg),
+        None => storage::new_app_at_path(&chosen_path, cfg),
+    };
+    let mut journal = Journal::open(journal::journal_path_for(&chosen_path), &app);
+    // After the journal opens, so the imported appointments are journaled and saved
+    app.import_appointments();
+    // Todoist write-back runs on its own thread; an unreadable queue only disables it
+    let mut write_back = match WriteBack::start(&app, &chosen_path) {
+        Ok(w) => Some(w),
+        Err(e) => {
+            tracing::error!("todoist write-back disabled: {e:#}");
+            app.set_sync_notice("todoist", Some(format!("todoist: {e:#}")));
+            None
+        }
+    };
+    // Toggl push sync, also on its own thread; bad `TOGGL_*` settings only disable it
+    let mut toggl = match TogglSync::start(&chosen_path) {
+        Ok(t) => t,
+        Err(e) => {
+            tracing::error!("toggl sync disabled: {e:#}");
+            app.set_sync_notice("toggl", Some(format!("toggl: {e:#}")));
+            None
+        }
     };
 
+    // SIGTERM/SIGHUP/SIGINT only raise a flag; the loop exits and saves like a normal quit.
+    let terminate = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for sig in
+        [signal_hook::consts::SIGTERM, signal_hook::consts::SIGHUP, signal_hook::consts::SIGINT]
+    {
+        signal_hook::flag::register(sig, Arc::clone(&terminate))?;
+    }
+
+    let mut terminal = setup_terminal()?;
+
+    // A panic inside the loop still falls through to the final checkpoint below.
+    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        run_loop(
+            &mut terminal,
+            &mut app,
+            &mut journal,
+            write_back.as_mut(),
+            toggl.as_mut(),
+            &chosen_path,
+            &terminate,
+        )
+    }));
+    // Queue write-backs for the last changes (e.g. finish, then quit right away)
+    if let Some(w) = write_back.as_mut() {
+        w.update(&mut app);
+    }
+    if let Some(t) = toggl.as_mut() {
+        t.update(&mut app);
+    }
+
+    // Final save on exit, signal or panic.
+    if let Err(e) = journal.checkpoint(&mut app, &chosen_path) {
+        // Avoid crashing; report to stderr after restoring terminal.
+        tracing::error!("failed to save snapshot: {e}");
+    }
+    restore_terminal(terminal)?;
+    match outcome {
+        Ok(res) => res,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+/// Run one headless subcommand and return the process exit code.
+fn run_headless(
+    cmd: headless::Command,
+    state_override: Option<std::path::PathBuf>,
+    json: bool,
+) -> i32 {
+    let cfg = Config::load();
+    let result = storage::resolve_state_path(&cfg, state_override)
+        .ok_or_else(|| {
+            headless::CliError::new(headless::ErrorKind::Io, "could not resolve state path")
+        })
+        .and_then(|path| headless::run_at_path(&path, cfg, &cmd));
+    match result {
+        Ok(out) => {
+            if json {
+                println!("{}", out.json);
+            } else if !out.text.is_empty() {
+                println!("{}", out.text);
+            }
+            0
+        }
+        Err(e) => {
+            if json {
+                println!("{}", e.to_json());
+            } else {
+                eprintln!("chute: {e}");
+            }
+            e.kind.exit_code()
+        }
+    }
+}
+
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    app: &mut App,
+    journal: &mut Journal,
+    mut write_back: Option<&mut WriteBack>,
+    mut toggl: Option<&mut TogglSync>,
+    state_path: &std::path::Path,
+    terminate: &AtomicBool,
+) -> Result<()> {
     // Real-time ticking (seconds) — accumulate elapsed millis and convert to seconds.
     let mut last_instant = Instant::now();
     let mut carry_millis: u64 = 0;
+    let mut autosave = storage::Autosave::default();
 
     loop {
-        terminal.draw(|f| ui::draw(f, &app))?;
+        terminal.draw(|f| ui::draw(f, app))?;
 
         // Measure elapsed time and tick the app in whole seconds.
         let now = Instant::now();
@@ -128,28 +239,51 @@ fn main() -> Result<()> {
             app.tick(1);
             carry_millis -= 1000;
         }
+        let mut touched = false;
         if event::poll(Duration::from_millis(100))? {
             match event::read()? {
-                event::Event::Key(k) => app.handle_key_event(k),
-                event::Event::Paste(s) => app.handle_paste(&s),
+                event::Event::Key(k) => {
+                    app.handle_key_event(k);
+                    touched = true;
+                }
+                event::Event::Paste(s) => {
+                    app.handle_paste(&s);
+                    touched = true;
+                }
                 event::Event::Mouse(m) => {
                     let sz = terminal.size()?; // ratatui::prelude::Size
                     let area = ratatui::layout::Rect::new(0, 0, sz.width, sz.height);
                     app.handle_mouse_event(m, area);
+                    touched = !matches!(m.kind, event::MouseEventKind::Moved);
                 }
                 _ => {}
             }
         }
-        if app.should_quit {
+        // Journal list changes right away; snapshot writes are debounced.
+        match journal.record(app) {
+            Ok(true) => touched = true,
+            Ok(false) => {}
+            Err(e) => tracing::error!("failed to append journal: {e}"),
+        }
+        if let Some(w) = write_back.as_deref_mut() {
+            w.update(app);
+        }
+        if let Some(t) = toggl.as_deref_mut() {
+            t.update(app);
+        }
+        let now = Instant::now();
+        if touched {
+            autosave.touch(now);
+        }
+        if autosave.is_due(now) {
+            match journal.checkpoint(app, state_path) {
+                Ok(()) => autosave.saved(),
+                Err(e) => tracing::error!("autosave failed: {e}"),
+            }
+        }
+        if app.should_quit || terminate.load(Ordering::Relaxed) {
             break;
         }
     }
-
-    // Auto-save snapshot on exit.
-    if let Err(e) = storage::save_to_path(&app, &chosen_path) {
-        // Avoid crashing; report to stderr after restoring terminal.
-        tracing::error!("failed to save snapshot: {e}");
-    }
-    restore_terminal(terminal)?;
     Ok(())
 }
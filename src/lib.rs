@@ -2,13 +2,47 @@
 pub mod app;
 #[path = "lib/clock.rs"]
 pub mod clock;
+#[path = "lib/command.rs"]
+pub mod command;
 #[path = "lib/config.rs"]
 pub mod config;
 #[path = "lib/date.rs"]
 pub mod date;
+#[path = "lib/headless.rs"]
+pub mod headless;
+#[path = "lib/history.rs"]
+pub mod history;
+#[path = "lib/ics.rs"]
+pub mod ics;
+#[path = "lib/journal.rs"]
+pub mod journal;
+#[path = "lib/line_edit.rs"]
+pub mod line_edit;
+#[path = "lib/outbox.rs"]
+pub mod outbox;
+#[path = "lib/quick_add.rs"]
+pub mod quick_add;
+#[path = "lib/report.rs"]
+pub mod report;
+#[path = "lib/routine.rs"]
+pub mod routine;
+#[path = "lib/search.rs"]
+pub mod search;
+#[path = "lib/session_edit.rs"]
+pub mod session_edit;
+#[path = "lib/source.rs"]
+pub mod source;
 #[path = "lib/storage.rs"]
 pub mod storage;
+#[path = "lib/tags.rs"]
+pub mod tags;
 #[path = "lib/task.rs"]
 pub mod task;
+#[path = "lib/timelog.rs"]
+pub mod timelog;
+#[path = "lib/todoist.rs"]
+pub mod todoist;
+#[path = "lib/toggl.rs"]
+pub mod toggl;
 #[path = "lib/ui.rs"]
 pub mod ui;
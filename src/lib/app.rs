@@ -1,8 +1,16 @@
 use crate::config::Config;
 use crate::date::today_ymd;
+use crate::history::{day_key, HistoryStore};
+use crate::line_edit::{push_history, LineEditor};
+use crate::quick_add::{QuickAdd, DEFAULT_ESTIMATE_MIN, DEFAULT_INTERRUPT_ESTIMATE_MIN};
+use crate::routine::{Recurrence, Routine};
+use crate::session_edit::{SessionEditor, SessionField};
 use crate::task::{DayPlan, Task};
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::layout::Rect;
+use std::collections::BTreeSet;
 use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -35,6 +43,13 @@ impl View {
             View::Future => View::Today,
         }
     }
+    fn slot(self) -> usize {
+        match self {
+            View::Past => 0,
+            View::Today => 1,
+            View::Future => 2,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -45,6 +60,18 @@ pub struct App {
     selected: usize,
     tomorrow: Vec<Task>,
     history: Vec<Task>,
+    // Optional per-date history files; when set, `history` holds only the days loaded so far
+    history_store: Option<HistoryStore>,
+    history_days: BTreeSet<u32>,
+    history_all_loaded: bool,
+    // Days whose in-memory Past tasks differ from their history file
+    history_dirty: BTreeSet<u32>,
+    // Past edits `(before, after)` not yet written to the journal
+    history_edits: Vec<(Task, Task)>,
+    // Last journal entry reflected in the current state (see `journal`)
+    journal_seq: u64,
+    // Recurring task templates instantiated into Today once per date
+    routines: Vec<Routine>,
     view: View,
     // Current content rendering mode (table vs. time blocks)
     display: DisplayMode,
@@ -66,6 +93,47 @@ pub struct App {
     hovered_header_btn: Option<HeaderButton>,
     // Category picker selection index when open
     cat_pick_idx: usize,
+    // Working copy of the selected task's sessions while the session editor is open
+    session_edit: Option<SessionEditor>,
+    // Detail pane (title/meta/sessions/notes of the selected task) visibility
+    detail_open: bool,
+    // Per-view tag filter (indexed by `View::slot`); only matching tasks are listed
+    tag_filters: [Option<String>; 3],
+    // Per-view `/` search query (indexed by `View::slot`); narrows the list like tag filters
+    searches: [Option<String>; 3],
+    // Submitted lines per text field, recalled with Up/Down (in memory only)
+    input_history: InputHistory,
+    // Error or Tab candidates shown under the command prompt; cleared by the next edit
+    command_feedback: Option<CommandFeedback>,
+    // Tasks parsed from a multi-line paste, waiting for confirmation
+    paste_batch: Option<PasteBatch>,
+    // Current problem per external sync (`todoist`, `toggl`), shown in the header
+    sync_notices: Vec<(&'static str, String)>,
+    // Undo/redo history of task-list states (most recent last)
+    undo_stack: Vec<UndoEntry>,
+    redo_stack: Vec<UndoEntry>,
+    // Nesting guard so inner handlers don't record separate steps
+    undo_depth: u8,
+    // Set by undo/redo so the surrounding handler doesn't record the restore itself
+    undo_restored: bool,
+    // Past edits `(before, after)` made by the step being recorded
+    undo_past_edits: Vec<(Task, Task)>,
+}
+
+/// Maximum number of undo steps kept.
+const UNDO_LIMIT: usize = 100;
+
+/// State restored by undo/redo. `DayPlan` is cloned whole so the active pointer is exact.
+#[derive(Debug, Clone)]
+struct UndoEntry {
+    day: DayPlan,
+    tomorrow: Vec<Task>,
+    // Past is only edited in place, so it is kept as `(current, restored)` replacements
+    // (applied in order) instead of a copy of the whole history
+    past: Vec<(Task, Task)>,
+    routines: Vec<Routine>,
+    selected: usize,
+    view: View,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -78,6 +146,41 @@ enum InputKind {
     ConfirmDelete,
     CategoryPicker,
     StartTimeEdit,
+    SessionEdit,
+    NoteEdit,
+    Search,
+    TitleEdit,
+    ConfirmPaste,
+}
+
+#[derive(Debug, Default)]
+struct InputHistory {
+    title: Vec<String>,
+    rename: Vec<String>,
+    command: Vec<String>,
+    search: Vec<String>,
+}
+
+impl InputHistory {
+    fn for_kind(&mut self, kind: InputKind) -> &mut Vec<String> {
+        match kind {
+            InputKind::TitleEdit => &mut self.rename,
+            InputKind::Command => &mut self.command,
+            InputKind::Search => &mut self.search,
+            _ => &mut self.title,
+        }
+    }
+}
+
+/// Line shown under the command prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandFeedback {
+    /// The last Enter failed; the command stays in the prompt for fixing.
+    Error(String),
+    /// Tab found several completions.
+    Candidates(Vec<String>),
+    /// What a command that has no visible effect did (e.g. where `export` wrote).
+    Done(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,17 +195,27 @@ pub enum HeaderButton {
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Input {
     kind: InputKind,
-    buffer: String,
+    editor: LineEditor,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct NewTaskDraft {
     source: InputKind, // Normal or Interrupt
-    title: String,
+    // Title plus the quick-add fields typed with it (`@work ^14:00 #tag`)
+    spec: QuickAdd,
     default_estimate: u16,
     planned_ymd: u32,
 }
 
+/// One task per pasted line, created together once confirmed.
+#[derive(Debug, Clone)]
+struct PasteBatch {
+    tasks: Vec<QuickAdd>,
+    default_estimate: u16,
+    // Title input the paste went into; reopened on cancel
+    restore: Option<Input>,
+}
+
 impl App {
     fn handle_mouse_in_popup(&mut self, ev: MouseEvent, area: Rect) {
         // Delete confirmation
@@ -209,7 +322,7 @@ impl App {
                         if point_in_rect(pos.0, pos.1, track) {
                             let m = crate::ui::minutes_from_slider_x(track, 0, 240, 5, pos.0);
                             if let Some(inp) = self.input.as_mut() {
-                                inp.buffer = m.to_string();
+                                inp.editor.set_text(m.to_string());
                             }
                         } else if point_in_rect(pos.0, pos.1, prev_btn) {
                             if let Some(d) = self.new_task.as_mut() {
@@ -304,14 +417,14 @@ impl App {
                             let m =
                                 crate::ui::minutes_from_slider_x(track, 0, 23 * 60 + 59, 5, pos.0);
                             if let Some(inp) = self.input.as_mut() {
-                                inp.buffer = m.to_string();
+                                inp.editor.set_text(m.to_string());
                             }
                         } else if crate::app::point_in_rect(pos.0, pos.1, ok) {
                             // Apply and close
                             let m = self
                                 .input
                                 .as_ref()
-                                .and_then(|i| i.buffer.parse::<u16>().ok())
+                                .and_then(|i| i.editor.text().parse::<u16>().ok())
                                 .unwrap_or(self.config.day_start_minutes);
                             if let Some(t) = self.day.tasks.get_mut(self.selected) {
                                 t.fixed_start_min = Some(m.min(23 * 60 + 59));
@@ -324,6 +437,129 @@ impl App {
                     _ => {}
                 }
             }
+        } else if self.is_note_edit() {
+            if let Some(popup) = crate::ui::compute_note_popup_rect(self, area) {
+                let (ok, cancel) = crate::ui::note_popup_button_hitboxes(self, popup);
+                let pos = (ev.column, ev.row);
+                match ev.kind {
+                    MouseEventKind::Moved => {
+                        self.popup_hover = if point_in_rect(pos.0, pos.1, ok) {
+                            Some(PopupButton::EstOk)
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            Some(PopupButton::EstCancel)
+                        } else {
+                            None
+                        };
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if point_in_rect(pos.0, pos.1, ok) {
+                            self.handle_key(KeyCode::Enter);
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            self.input = None;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        } else if self.is_session_edit() {
+            if let Some(popup) = crate::ui::compute_session_popup_rect(self, area) {
+                let boxes = crate::ui::session_editor_hitboxes(self, popup);
+                let pos = (ev.column, ev.row);
+                match ev.kind {
+                    MouseEventKind::Moved => {
+                        self.popup_hover = if point_in_rect(pos.0, pos.1, boxes.ok) {
+                            Some(PopupButton::EstOk)
+                        } else if point_in_rect(pos.0, pos.1, boxes.cancel) {
+                            Some(PopupButton::EstCancel)
+                        } else {
+                            None
+                        };
+                    }
+                    MouseEventKind::Down(MouseButton::Left)
+                    | MouseEventKind::Drag(MouseButton::Left) => {
+                        if point_in_rect(pos.0, pos.1, boxes.track) {
+                            let m = crate::ui::minutes_from_slider_x(
+                                boxes.track,
+                                0,
+                                crate::session_edit::LAST_MINUTE,
+                                5,
+                                pos.0,
+                            );
+                            if let Some(ed) = self.session_edit.as_mut() {
+                                ed.set_minutes(m);
+                            }
+                        } else if matches!(ev.kind, MouseEventKind::Drag(_)) {
+                            // Dragging only moves the slider
+                        } else if point_in_rect(pos.0, pos.1, boxes.ok) {
+                            self.close_session_editor(true);
+                        } else if point_in_rect(pos.0, pos.1, boxes.cancel) {
+                            self.close_session_editor(false);
+                        } else if let Some(ed) = self.session_edit.as_mut() {
+                            for (i, start, end) in &boxes.rows {
+                                if pos.1 != start.y {
+                                    continue;
+                                }
+                                ed.cursor = *i;
+                                if point_in_rect(pos.0, pos.1, *end) {
+                                    ed.field = SessionField::End;
+                                } else if point_in_rect(pos.0, pos.1, *start) {
+                                    ed.field = SessionField::Start;
+                                }
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        } else if self.is_confirm_paste() {
+            if let Some(popup) = crate::ui::compute_paste_popup_rect(self, area) {
+                let (ok, cancel) = crate::ui::paste_popup_button_hitboxes(self, popup);
+                let pos = (ev.column, ev.row);
+                match ev.kind {
+                    MouseEventKind::Moved => {
+                        self.popup_hover = if point_in_rect(pos.0, pos.1, ok) {
+                            Some(PopupButton::EstOk)
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            Some(PopupButton::EstCancel)
+                        } else {
+                            None
+                        };
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if point_in_rect(pos.0, pos.1, ok) {
+                            self.handle_key(KeyCode::Enter);
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            self.handle_key(KeyCode::Esc);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        } else if self.is_title_edit() {
+            if let Some(popup) = crate::ui::compute_title_popup_rect(self, area) {
+                let (ok, cancel) = crate::ui::title_popup_button_hitboxes(self, popup);
+                let pos = (ev.column, ev.row);
+                match ev.kind {
+                    MouseEventKind::Moved => {
+                        self.popup_hover = if point_in_rect(pos.0, pos.1, ok) {
+                            Some(PopupButton::EstOk)
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            Some(PopupButton::EstCancel)
+                        } else {
+                            None
+                        };
+                    }
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if point_in_rect(pos.0, pos.1, ok) {
+                            self.handle_key(KeyCode::Enter);
+                        } else if point_in_rect(pos.0, pos.1, cancel) {
+                            self.input = None;
+                        }
+                    }
+                    _ => {}
+                }
+            }
         } else if self.in_input_mode() && !self.is_command_mode() {
             // Task name input (Normal/Interrupt)
             if let Some(popup) = crate::ui::compute_input_popup_rect(self, area) {
@@ -370,6 +606,13 @@ impl App {
             selected: 0,
             tomorrow: vec![],
             history: vec![],
+            history_store: None,
+            history_days: BTreeSet::new(),
+            history_all_loaded: true,
+            history_dirty: BTreeSet::new(),
+            history_edits: Vec::new(),
+            journal_seq: 0,
+            routines: vec![],
             view: View::default(),
             display: DisplayMode::List,
             input: None,
@@ -384,75 +627,50 @@ impl App {
             new_task: None,
             hovered_header_btn: None,
             cat_pick_idx: 0,
+            session_edit: None,
+            detail_open: false,
+            tag_filters: Default::default(),
+            searches: Default::default(),
+            input_history: InputHistory::default(),
+            command_feedback: None,
+            paste_batch: None,
+            sync_notices: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_depth: 0,
+            undo_restored: false,
+            undo_past_edits: Vec::new(),
         }
     }
 
     pub fn handle_key(&mut self, code: KeyCode) {
+        self.with_undo(|app| app.handle_key_inner(code));
+        self.snap_selection_to_filter();
+    }
+
+    fn handle_key_inner(&mut self, code: KeyCode) {
         // If we are in input mode, interpret keys as text editing/submit/cancel
         if let Some(input) = self.input.as_mut() {
             // Pre-capture default estimate for new-task flow to avoid borrowing self during edits
-            let new_task_default = self.new_task.as_ref().map(|d| d.default_estimate).unwrap_or(25);
+            let new_task_default =
+                self.new_task.as_ref().map_or(DEFAULT_ESTIMATE_MIN, |d| d.default_estimate);
             match input.kind {
-                InputKind::Normal | InputKind::Interrupt => match code {
-                    KeyCode::Enter => {
-                        let (default_title, est) = match input.kind {
-                            InputKind::Normal => ("New Task", 25u16),
-                            InputKind::Interrupt => ("Interrupt", 15u16),
-                            _ => unreachable!(),
-                        };
-                        let title = if input.buffer.trim().is_empty() {
-                            default_title.to_string()
-                        } else {
-                            input.buffer.trim().to_string()
-                        };
-                        // Move to estimate entry step with default prefilled
-                        self.new_task = Some(NewTaskDraft {
-                            source: input.kind,
-                            title,
-                            default_estimate: est,
-                            planned_ymd: today_ymd(),
-                        });
-                        self.input =
-                            Some(Input { kind: InputKind::NewTaskEstimate, buffer: String::new() });
-                    }
-                    KeyCode::Esc => {
-                        self.input = None;
-                    }
-                    KeyCode::Backspace => {
-                        input.buffer.pop();
-                    }
-                    KeyCode::Char(c) => {
-                        input.buffer.push(c);
-                    }
-                    _ => {}
-                },
+                InputKind::Normal
+                | InputKind::Interrupt
+                | InputKind::Command
+                | InputKind::Search
+                | InputKind::TitleEdit => self.handle_line_input_key(code, KeyModifiers::NONE),
                 InputKind::NewTaskEstimate => match code {
                     KeyCode::Enter => {
                         if let Some(draft) = self.new_task.take() {
                             let cur = self
                                 .input
                                 .as_ref()
-                                .and_then(|i| i.buffer.trim().parse::<u16>().ok())
+                                .and_then(|i| i.editor.text().trim().parse::<u16>().ok())
                                 .unwrap_or(draft.default_estimate);
-                            let today = today_ymd();
-                            if draft.planned_ymd == today {
-                                let idx = self.add_task(&draft.title, cur);
-                                if let Some(t) = self.day.tasks.get_mut(idx) {
-                                    t.planned_ymd = draft.planned_ymd;
-                                }
-                                self.selected = idx;
-                            } else if draft.planned_ymd > today {
-                                let mut t = Task::new(&draft.title, cur);
-                                t.planned_ymd = draft.planned_ymd;
-                                self.tomorrow.push(t);
-                            } else {
-                                // Clamp to today if past (defensive)
-                                let idx = self.add_task(&draft.title, cur);
-                                if let Some(t) = self.day.tasks.get_mut(idx) {
-                                    t.planned_ymd = today;
-                                }
-                                self.selected = idx;
-                            }
+                            let mut spec = draft.spec;
+                            spec.planned_ymd = Some(draft.planned_ymd);
+                            self.add_quick_task(spec, cur, draft.source == InputKind::Interrupt);
                         }
                         self.input = None;
                     }
@@ -462,19 +680,29 @@ impl App {
                         self.input = None;
                     }
                     KeyCode::Backspace => {
-                        input.buffer.pop();
+                        input.editor.backspace();
                     }
                     KeyCode::Up | KeyCode::Right | KeyCode::Char('k') => {
-                        let base =
-                            input.buffer.trim().parse::<u16>().ok().unwrap_or(new_task_default);
+                        let base = input
+                            .editor
+                            .text()
+                            .trim()
+                            .parse::<u16>()
+                            .ok()
+                            .unwrap_or(new_task_default);
                         let next = base.saturating_add(5).min(240);
-                        input.buffer = next.to_string();
+                        input.editor.set_text(next.to_string());
                     }
                     KeyCode::Down | KeyCode::Left | KeyCode::Char('j') => {
-                        let base =
-                            input.buffer.trim().parse::<u16>().ok().unwrap_or(new_task_default);
+                        let base = input
+                            .editor
+                            .text()
+                            .trim()
+                            .parse::<u16>()
+                            .ok()
+                            .unwrap_or(new_task_default);
                         let next = base.saturating_sub(5);
-                        input.buffer = next.to_string();
+                        input.editor.set_text(next.to_string());
                     }
                     KeyCode::Char('.') => {
                         if let Some(d) = self.new_task.as_mut() {
@@ -491,31 +719,14 @@ impl App {
                     KeyCode::Char(_c) => {}
                     _ => {}
                 },
-                InputKind::Command => match code {
-                    KeyCode::Enter => {
-                        let cmd = input.buffer.trim().to_string();
-                        self.apply_command(&cmd);
-                        self.input = None;
-                    }
-                    KeyCode::Esc => {
-                        self.input = None;
-                    }
-                    KeyCode::Backspace => {
-                        input.buffer.pop();
-                    }
-                    KeyCode::Char(c) => input.buffer.push(c),
-                    _ => {}
-                },
                 InputKind::CategoryPicker => match code {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if self.cat_pick_idx > 0 {
-                            self.cat_pick_idx -= 1;
-                        }
+                    KeyCode::Up | KeyCode::Char('k') if self.cat_pick_idx > 0 => {
+                        self.cat_pick_idx -= 1;
                     }
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        if self.cat_pick_idx < 3 {
-                            self.cat_pick_idx += 1;
-                        }
+                    KeyCode::Down | KeyCode::Char('j')
+                        if self.cat_pick_idx + 1 < self.config.categories.selectable().count() =>
+                    {
+                        self.cat_pick_idx += 1;
                     }
                     KeyCode::Enter => {
                         self.apply_selected_category();
@@ -573,6 +784,15 @@ impl App {
                     }
                     _ => {}
                 },
+                InputKind::ConfirmPaste => match code {
+                    KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        self.close_paste_batch(true)
+                    }
+                    KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                        self.close_paste_batch(false)
+                    }
+                    _ => {}
+                },
                 InputKind::ConfirmDelete => match code {
                     // Confirm via Enter or 'y'; cancel via Esc or 'n'
                     KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -588,7 +808,9 @@ impl App {
                     KeyCode::Enter | KeyCode::Esc => {
                         // Apply on Enter, discard on Esc
                         if matches!(code, KeyCode::Enter) {
-                            if let Some(buf) = self.input.as_ref().map(|i| i.buffer.clone()) {
+                            if let Some(buf) =
+                                self.input.as_ref().map(|i| i.editor.text().to_string())
+                            {
                                 if let Ok(mins) = buf.trim().parse::<u16>() {
                                     if let Some(t) = self.day.tasks.get_mut(self.selected) {
                                         let clamped = mins.min(23 * 60 + 59);
@@ -601,20 +823,36 @@ impl App {
                     }
                     KeyCode::Up | KeyCode::Right | KeyCode::Char('k') => {
                         if let Some(input) = self.input.as_mut() {
-                            let base = input.buffer.trim().parse::<u16>().ok().unwrap_or(0);
+                            let base = input.editor.text().trim().parse::<u16>().ok().unwrap_or(0);
                             let next = (base + 5).min(23 * 60 + 59);
-                            input.buffer = next.to_string();
+                            input.editor.set_text(next.to_string());
                         }
                     }
                     KeyCode::Down | KeyCode::Left | KeyCode::Char('j') => {
                         if let Some(input) = self.input.as_mut() {
-                            let base = input.buffer.trim().parse::<u16>().ok().unwrap_or(0);
+                            let base = input.editor.text().trim().parse::<u16>().ok().unwrap_or(0);
                             let next = base.saturating_sub(5);
-                            input.buffer = next.to_string();
+                            input.editor.set_text(next.to_string());
                         }
                     }
                     _ => {}
                 },
+                InputKind::SessionEdit => self.handle_session_editor_key(code),
+                InputKind::NoteEdit => match code {
+                    KeyCode::Enter => {
+                        let note = input.editor.text().trim_end().to_string();
+                        self.update_selected_current(|t| t.notes = note);
+                        self.input = None;
+                    }
+                    KeyCode::Esc => {
+                        self.input = None;
+                    }
+                    KeyCode::Backspace => {
+                        input.editor.backspace();
+                    }
+                    KeyCode::Char(c) => input.editor.insert_char(c),
+                    _ => {}
+                },
             }
             return;
         }
@@ -638,39 +876,52 @@ impl App {
                     .get(self.selected)
                     .and_then(|t| t.fixed_start_min)
                     .unwrap_or(self.config.day_start_minutes);
-                self.input =
-                    Some(Input { kind: InputKind::StartTimeEdit, buffer: initial.to_string() });
+                self.input = Some(Input {
+                    kind: InputKind::StartTimeEdit,
+                    editor: LineEditor::new(initial.to_string()),
+                });
+            }
+            KeyCode::Char('s') => {
+                self.open_session_editor();
+            }
+            KeyCode::Char('d') => {
+                self.toggle_detail();
+            }
+            KeyCode::Char('/') => {
+                self.open_search();
+            }
+            KeyCode::Char('r') => {
+                self.open_title_editor();
+            }
+            KeyCode::Char('n') if self.search_query().is_some() => self.search_step(true),
+            KeyCode::Char('N') => self.search_step(false),
+            KeyCode::Esc if self.search_query().is_some() => self.set_search(None),
+            KeyCode::Char('n') => {
+                self.open_note_editor();
             }
             KeyCode::Char('c') => {
                 // Cycle category of the selected task (all views)
-                if let Some(t) = self.selected_task_mut_current() {
-                    use crate::task::Category as C;
-                    t.category = match t.category {
-                        C::General => C::Work,
-                        C::Work => C::Home,
-                        C::Home => C::Hobby,
-                        C::Hobby => C::General,
-                    };
-                }
+                self.cycle_selected_category();
             }
             KeyCode::Char(':') => {
                 // Open command palette
-                self.input = Some(Input { kind: InputKind::Command, buffer: String::new() });
+                self.command_feedback = None;
+                self.input =
+                    Some(Input { kind: InputKind::Command, editor: LineEditor::default() });
             }
-            KeyCode::Char('E') => {
+            KeyCode::Char('E') if !self.day.tasks.is_empty() => {
                 // Enter estimate edit mode if a task is available
-                if !self.day.tasks.is_empty() {
-                    self.input =
-                        Some(Input { kind: InputKind::EstimateEdit, buffer: String::new() });
-                }
+                self.input =
+                    Some(Input { kind: InputKind::EstimateEdit, editor: LineEditor::default() });
             }
             KeyCode::Char('i') => {
                 // Enter input mode for a normal task
-                self.input = Some(Input { kind: InputKind::Normal, buffer: String::new() });
+                self.input = Some(Input { kind: InputKind::Normal, editor: LineEditor::default() });
             }
             KeyCode::Char('I') => {
                 // Enter input mode for an interrupt task
-                self.input = Some(Input { kind: InputKind::Interrupt, buffer: String::new() });
+                self.input =
+                    Some(Input { kind: InputKind::Interrupt, editor: LineEditor::default() });
             }
             KeyCode::Enter => {
                 // Toggle: if active -> pause; else start/resume selected or first eligible.
@@ -723,28 +974,24 @@ impl App {
                 let new = self.day.reorder_up(self.selected);
                 self.selected = new;
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char('e') if !self.day.tasks.is_empty() => {
                 // Open estimate edit mode
-                if !self.day.tasks.is_empty() {
-                    // Backfill missing planned date on legacy tasks to avoid panics in date ops
-                    if let Some(t) = self.day.tasks.get_mut(self.selected) {
-                        if !crate::date::is_valid_ymd(t.planned_ymd) {
-                            t.planned_ymd = today_ymd();
-                        }
+                // Backfill missing planned date on legacy tasks to avoid panics in date ops
+                if let Some(t) = self.day.tasks.get_mut(self.selected) {
+                    if !crate::date::is_valid_ymd(t.planned_ymd) {
+                        t.planned_ymd = today_ymd();
                     }
-                    self.input =
-                        Some(Input { kind: InputKind::EstimateEdit, buffer: String::new() });
                 }
+                self.input =
+                    Some(Input { kind: InputKind::EstimateEdit, editor: LineEditor::default() });
             }
             KeyCode::Char('p') => {
                 self.postpone_selected();
             }
-            KeyCode::Char('x') => {
+            KeyCode::Char('x') if self.view == View::Today && !self.day.tasks.is_empty() => {
                 // Open delete confirmation on Today view with an existing task
-                if self.view == View::Today && !self.day.tasks.is_empty() {
-                    self.input =
-                        Some(Input { kind: InputKind::ConfirmDelete, buffer: String::new() });
-                }
+                self.input =
+                    Some(Input { kind: InputKind::ConfirmDelete, editor: LineEditor::default() });
             }
             KeyCode::Char('b') => {
                 self.bring_selected_from_future();
@@ -769,11 +1016,43 @@ impl App {
         if matches!(ev.kind, KeyEventKind::Release) {
             return;
         }
+        self.with_undo(|app| app.handle_key_event_inner(ev));
+        self.snap_selection_to_filter();
+    }
+
+    fn handle_key_event_inner(&mut self, ev: KeyEvent) {
+        // Shift/Alt+Enter inserts a line break while editing a note (plain Enter saves)
+        if self.is_note_edit()
+            && ev.code == KeyCode::Enter
+            && ev.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT)
+        {
+            if let Some(input) = self.input.as_mut() {
+                input.editor.insert_char('\n');
+            }
+            return;
+        }
+        // Single-line text inputs see modifiers (Ctrl+W, Alt+b, ...)
+        if self.is_line_input() {
+            self.handle_line_input_key(ev.code, ev.modifiers);
+            return;
+        }
         // If in input mode, delegate to text edit handling
         if self.in_input_mode() {
             self.handle_key(ev.code);
             return;
         }
+        // While searching, hit navigation wins over other bindings of the same keys (e.g. `n`)
+        if self.search_query().is_some() {
+            let hit = |keys: &Vec<crate::config::KeySpec>| keys.iter().any(|k| k.matches(&ev));
+            if hit(&self.config.keys.search_next) {
+                self.search_step(true);
+                return;
+            }
+            if hit(&self.config.keys.search_prev) {
+                self.search_step(false);
+                return;
+            }
+        }
         // Try config-based keymap first
         if let Some(action) = self.config.keys.action_for(&ev) {
             self.apply_action(action);
@@ -790,7 +1069,18 @@ impl App {
     /// - Mouse move updates hover index.
     /// - Ignores clicks while in input/popup modes.
     pub fn handle_mouse_event(&mut self, ev: MouseEvent, area: Rect) {
-        if self.in_input_mode()
+        // Hover tracking never mutates tasks; skip the undo snapshot on every mouse move
+        if matches!(ev.kind, MouseEventKind::Moved) {
+            self.handle_mouse_event_inner(ev, area);
+        } else {
+            self.with_undo(|app| app.handle_mouse_event_inner(ev, area));
+            self.snap_selection_to_filter();
+        }
+    }
+
+    fn handle_mouse_event_inner(&mut self, ev: MouseEvent, area: Rect) {
+        // The search prompt lives in the tab line, so the list stays clickable while typing
+        if (self.in_input_mode() && !self.is_search_mode())
             || self.is_confirm_delete()
             || self.is_estimate_editing()
             || self.is_new_task_estimate()
@@ -860,8 +1150,10 @@ impl App {
                         match i {
                             0 => {
                                 // New task
-                                self.input =
-                                    Some(Input { kind: InputKind::Normal, buffer: String::new() });
+                                self.input = Some(Input {
+                                    kind: InputKind::Normal,
+                                    editor: LineEditor::default(),
+                                });
                             }
                             1 => {
                                 // Start/resume selected (pause other active if needed)
@@ -881,14 +1173,12 @@ impl App {
                                 // Finish selected
                                 self.finish_selected();
                             }
-                            4 => {
+                            4 if self.view == View::Today && !self.day.tasks.is_empty() => {
                                 // Delete (confirm)
-                                if self.view == View::Today && !self.day.tasks.is_empty() {
-                                    self.input = Some(Input {
-                                        kind: InputKind::ConfirmDelete,
-                                        buffer: String::new(),
-                                    });
-                                }
+                                self.input = Some(Input {
+                                    kind: InputKind::ConfirmDelete,
+                                    editor: LineEditor::default(),
+                                });
                             }
                             _ => {}
                         }
@@ -929,18 +1219,11 @@ impl App {
                 let task_x0 = list.x.saturating_add(5 + 1 + 4 + 1);
                 let dot_x_main = task_x0.saturating_add(2);
                 let dot_x_drag = task_x0.saturating_add(4);
-                let row_y = list.y.saturating_add(1).saturating_add(idx as u16);
+                let row = self.row_of_index(idx).unwrap_or(0) as u16;
+                let row_y = list.y.saturating_add(1).saturating_add(row);
                 if ev.row == row_y && (ev.column == dot_x_main || ev.column == dot_x_drag) {
                     self.selected = idx;
-                    use crate::task::Category as C;
-                    if let Some(t) = self.selected_task_mut_current() {
-                        t.category = match t.category {
-                            C::General => C::Work,
-                            C::Work => C::Home,
-                            C::Home => C::Hobby,
-                            C::Hobby => C::General,
-                        };
-                    }
+                    self.cycle_selected_category();
                     // Don't treat as part of a double-click
                     self.last_click = None;
                     let (_t2, _b2, list2, _h2) = crate::ui::compute_layout(self, area);
@@ -1013,7 +1296,8 @@ impl App {
                 let idx = self.index_from_list_row(ev.row, list);
                 // If right-click is on the category dot of that exact row, open picker
                 let task_x0 = list.x.saturating_add(5 + 1 + 4 + 1);
-                let row_y = list.y.saturating_add(1).saturating_add(idx as u16);
+                let row = self.row_of_index(idx).unwrap_or(0) as u16;
+                let row_y = list.y.saturating_add(1).saturating_add(row);
                 let dot_x_main = task_x0.saturating_add(2);
                 let dot_x_drag = task_x0.saturating_add(4);
                 if ev.row == row_y && (ev.column == dot_x_main || ev.column == dot_x_drag) {
@@ -1022,6 +1306,14 @@ impl App {
                     self.update_hover_from_coords(ev.column, ev.row, list2);
                     return;
                 }
+                // Ctrl/Alt+right click renames the row instead (any view)
+                if ev.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                    if idx < self.current_len() {
+                        self.selected = idx;
+                        self.open_title_editor();
+                    }
+                    return;
+                }
                 self.selected = idx;
                 if !self.day.tasks.is_empty() {
                     if let Some(t) = self.day.tasks.get_mut(self.selected) {
@@ -1029,8 +1321,10 @@ impl App {
                             t.planned_ymd = today_ymd();
                         }
                     }
-                    self.input =
-                        Some(Input { kind: InputKind::EstimateEdit, buffer: String::new() });
+                    self.input = Some(Input {
+                        kind: InputKind::EstimateEdit,
+                        editor: LineEditor::default(),
+                    });
                 }
                 // Popups don't affect list geometry, but realign hover defensively.
                 let (_t2, _b2, list2, _h2) = crate::ui::compute_layout(self, area);
@@ -1071,13 +1365,71 @@ impl App {
     }
 
     /// Handle pasted text from the terminal (bracketed/kitty paste etc.).
-    /// Appends to the input buffer only when in input mode.
+    /// Inserts at the cursor only when in input mode; single-line inputs get line breaks
+    /// as spaces.
     pub fn handle_paste(&mut self, s: &str) {
-        if let Some(input) = self.input.as_mut() {
-            input.buffer.push_str(s);
+        // Several lines pasted on the list or into the title input become one task each
+        let title_input = self.input.is_none() || self.is_text_input_mode();
+        if title_input && s.lines().filter(|l| !l.trim().is_empty()).count() > 1 {
+            self.open_paste_batch(s);
+            return;
+        }
+        let single_line = self.is_line_input();
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        if single_line {
+            let text: String =
+                s.chars().map(|c| if c == '\n' || c == '\r' { ' ' } else { c }).collect();
+            input.editor.insert_str(&text);
+            self.sync_search_input();
+        } else {
+            input.editor.insert_str(s);
+        }
+    }
+
+    fn open_paste_batch(&mut self, text: &str) {
+        let today = today_ymd();
+        let tasks: Vec<QuickAdd> = crate::quick_add::paste_lines(text)
+            .iter()
+            .map(|line| QuickAdd::parse(line, &self.config, today))
+            .collect();
+        if tasks.is_empty() {
+            return;
+        }
+        let restore = self.input.take();
+        let default_estimate = if restore.as_ref().is_some_and(|i| i.kind == InputKind::Interrupt) {
+            DEFAULT_INTERRUPT_ESTIMATE_MIN
+        } else {
+            DEFAULT_ESTIMATE_MIN
+        };
+        self.paste_batch = Some(PasteBatch { tasks, default_estimate, restore });
+        self.input = Some(Input { kind: InputKind::ConfirmPaste, editor: LineEditor::default() });
+    }
+
+    fn close_paste_batch(&mut self, create: bool) {
+        self.input = None;
+        let Some(batch) = self.paste_batch.take() else {
+            return;
+        };
+        if !create {
+            self.input = batch.restore;
+            return;
+        }
+        for mut spec in batch.tasks {
+            if spec.title.is_empty() {
+                spec.title = "New Task".to_string();
+            }
+            let interrupt = batch.restore.as_ref().is_some_and(|i| i.kind == InputKind::Interrupt);
+            self.add_quick_task(spec, batch.default_estimate, interrupt);
         }
     }
 
+    /// Tasks a pending multi-line paste would create.
+    pub fn paste_preview(&self) -> Option<&[QuickAdd]> {
+        self.paste_batch.as_ref().filter(|_| self.is_confirm_paste()).map(|b| b.tasks.as_slice())
+    }
+
     pub fn add_task(&mut self, title: &str, estimate_min: u16) -> usize {
         self.day.add_task(Task::new(title, estimate_min))
     }
@@ -1094,6 +1446,7 @@ impl App {
                 t.end_session(now);
             }
             self.day.finish_at(idx, ymd);
+            self.record_routine_actual(idx);
         }
     }
 
@@ -1106,11 +1459,34 @@ impl App {
         // Use current date at the moment of finishing for the same reason as above.
         let ymd = crate::date::today_ymd();
         let now = crate::clock::system_now_minutes();
+        let was_done = matches!(self.day.tasks[idx].state, crate::task::TaskState::Done);
         if let Some(t) = self.day.tasks.get_mut(idx) {
             t.finished_at_min = Some(now);
             t.end_session(now);
         }
         self.day.finish_at(idx, ymd);
+        if !was_done {
+            self.record_routine_actual(idx);
+        }
+    }
+
+    /// Feed the actual time of a just-finished routine instance into its routine's history,
+    /// updating the estimate used for the next generated instance.
+    fn record_routine_actual(&mut self, idx: usize) {
+        let Some(t) = self.day.tasks.get(idx) else {
+            return;
+        };
+        // Tasks finished without any tracked time would only drag the estimate to zero
+        let (Some(id), actual) = (t.routine_id, t.actual_min) else {
+            return;
+        };
+        if actual == 0 {
+            return;
+        }
+        let policy = self.config.routine_estimate;
+        if let Some(r) = self.routines.iter_mut().find(|r| r.id == id) {
+            r.record_actual(actual, policy);
+        }
     }
 
     fn apply_action(&mut self, action: crate::config::Action) {
@@ -1124,16 +1500,14 @@ impl App {
                     self.should_quit = true;
                 }
             }
+            A::Undo => {
+                self.undo();
+            }
+            A::Redo => {
+                self.redo();
+            }
             A::CategoryCycle => {
-                if let Some(t) = self.selected_task_mut_current() {
-                    use crate::task::Category as C;
-                    t.category = match t.category {
-                        C::General => C::Work,
-                        C::Work => C::Home,
-                        C::Home => C::Hobby,
-                        C::Hobby => C::General,
-                    };
-                }
+                self.cycle_selected_category();
             }
             A::CategoryPicker => {
                 // Open picker for the current view if it has any items
@@ -1143,11 +1517,33 @@ impl App {
                     self.open_category_picker_for(idx);
                 }
             }
+            A::SessionEditor => {
+                self.open_session_editor();
+            }
+            A::ToggleDetail => {
+                self.toggle_detail();
+            }
+            A::EditNote => {
+                self.open_note_editor();
+            }
+            A::Search => {
+                self.open_search();
+            }
+            A::Rename => {
+                self.open_title_editor();
+            }
+            A::SearchNext => {
+                self.search_step(true);
+            }
+            A::SearchPrev => {
+                self.search_step(false);
+            }
             A::AddTask => {
-                self.input = Some(Input { kind: InputKind::Normal, buffer: String::new() });
+                self.input = Some(Input { kind: InputKind::Normal, editor: LineEditor::default() });
             }
             A::AddInterrupt => {
-                self.input = Some(Input { kind: InputKind::Interrupt, buffer: String::new() });
+                self.input =
+                    Some(Input { kind: InputKind::Interrupt, editor: LineEditor::default() });
             }
             A::StartOrResume => {
                 // Toggle behavior for Enter-mapped action: pause if active, otherwise start/resume
@@ -1204,13 +1600,17 @@ impl App {
                     .get(self.selected)
                     .and_then(|t| t.fixed_start_min)
                     .unwrap_or(self.config.day_start_minutes);
-                self.input =
-                    Some(Input { kind: InputKind::StartTimeEdit, buffer: initial.to_string() });
+                self.input = Some(Input {
+                    kind: InputKind::StartTimeEdit,
+                    editor: LineEditor::new(initial.to_string()),
+                });
             }
             A::Delete => {
                 if self.view == View::Today && !self.day.tasks.is_empty() {
-                    self.input =
-                        Some(Input { kind: InputKind::ConfirmDelete, buffer: String::new() });
+                    self.input = Some(Input {
+                        kind: InputKind::ConfirmDelete,
+                        editor: LineEditor::default(),
+                    });
                 }
             }
             A::ReorderUp => {
@@ -1230,8 +1630,10 @@ impl App {
                             t.planned_ymd = today_ymd();
                         }
                     }
-                    self.input =
-                        Some(Input { kind: InputKind::EstimateEdit, buffer: String::new() });
+                    self.input = Some(Input {
+                        kind: InputKind::EstimateEdit,
+                        editor: LineEditor::default(),
+                    });
                 }
             }
             A::Postpone => {
@@ -1257,34 +1659,152 @@ impl App {
     pub fn selected_index(&self) -> usize {
         self.selected
     }
-    pub fn select_up(&mut self) {
-        let len = self.current_len();
-        if len == 0 {
-            return;
+    /// Select `idx` in the current view (clamped to the list length).
+    pub fn select_index(&mut self, idx: usize) {
+        self.selected = idx.min(self.current_len().saturating_sub(1));
+    }
+    /// Pause the running task, closing its session. Returns false if nothing was active.
+    pub fn pause_active(&mut self) -> bool {
+        let Some(active_idx) = self.day.active_index() else {
+            return false;
+        };
+        let now = crate::clock::system_now_minutes();
+        if let Some(t) = self.day.tasks.get_mut(active_idx) {
+            t.end_session(now);
         }
-        let new = self.selected.saturating_sub(1);
-        if new != self.selected {
-            self.selected = new;
+        self.day.pause_active();
+        true
+    }
+    /// Append a task planned for a later date to the Future list.
+    pub fn add_future_task(&mut self, task: Task) {
+        self.tomorrow.push(task);
+    }
+    /// Add `task` to Today, or to Future when it is planned after today.
+    /// Returns the list it went to and its index there.
+    pub fn add_planned_task(&mut self, task: Task) -> (View, usize) {
+        if task.planned_ymd > today_ymd() {
+            self.tomorrow.push(task);
+            (View::Future, self.tomorrow.len() - 1)
+        } else {
+            (View::Today, self.day.add_task(task))
         }
     }
-    pub fn select_down(&mut self) {
-        let len = self.current_len();
-        if len == 0 {
+    /// Set or adjust the estimate of Today task `idx`. Allowed in any state, like the
+    /// estimate editor: correcting a running or finished task's plan changes no state.
+    pub fn set_task_estimate(&mut self, idx: usize, est: crate::task::EstimateArg) {
+        match est {
+            crate::task::EstimateArg::Set(m) => {
+                if let Some(t) = self.day.tasks.get_mut(idx) {
+                    t.estimate_min = m;
+                }
+            }
+            crate::task::EstimateArg::Delta(d) => self.day.adjust_estimate(idx, d),
+        }
+    }
+    /// Pin (or with `None` unpin) Today task `idx` to a start time. Allowed in any state,
+    /// like the start-time editor: it only moves where the task sits on the timeline.
+    pub fn set_task_fixed_start(&mut self, idx: usize, minutes: Option<u16>) {
+        if let Some(t) = self.day.tasks.get_mut(idx) {
+            t.fixed_start_min = minutes;
+        }
+    }
+    // Create the task typed in the title input; Today tasks become selected
+    fn add_quick_task(&mut self, spec: QuickAdd, default_estimate: u16, interrupt: bool) {
+        let mut task = spec.into_task(default_estimate, today_ymd());
+        task.interrupt = interrupt;
+        if let (View::Today, idx) = self.add_planned_task(task) {
+            self.selected = idx;
+        }
+    }
+    pub fn select_up(&mut self) {
+        let visible = self.visible_indices();
+        if let Some(&prev) = visible.iter().rev().find(|&&i| i < self.selected) {
+            self.selected = prev;
+        }
+    }
+    pub fn select_down(&mut self) {
+        let visible = self.visible_indices();
+        if let Some(&next) = visible.iter().find(|&&i| i > self.selected) {
+            self.selected = next;
+        }
+    }
+
+    /// Tag filter of the current view, if any.
+    pub fn tag_filter(&self) -> Option<&str> {
+        self.tag_filters[self.view.slot()].as_deref()
+    }
+    /// Show only tasks tagged `tag` in the current view (`None` shows all).
+    pub fn set_tag_filter(&mut self, tag: Option<String>) {
+        self.tag_filters[self.view.slot()] = tag;
+        self.hovered = None;
+        self.snap_selection_to_filter();
+    }
+    /// Search query of the current view, if any.
+    pub fn search_query(&self) -> Option<&str> {
+        self.searches[self.view.slot()].as_deref()
+    }
+    /// Narrow the current view to tasks matching `query` (blank or `None` clears it).
+    pub fn set_search(&mut self, query: Option<String>) {
+        self.searches[self.view.slot()] = query.filter(|q| !q.trim().is_empty());
+        self.hovered = None;
+        self.snap_selection_to_filter();
+    }
+    fn open_search(&mut self) {
+        let query = self.search_query().unwrap_or_default().to_string();
+        self.input = Some(Input { kind: InputKind::Search, editor: LineEditor::new(query) });
+    }
+    /// Move the selection to the next (or previous) search hit, wrapping around.
+    pub fn search_step(&mut self, forward: bool) {
+        if self.search_query().is_none() {
+            return;
+        }
+        let hits = self.visible_indices();
+        let next = if forward {
+            hits.iter().find(|&&i| i > self.selected).or(hits.first())
+        } else {
+            hits.iter().rev().find(|&&i| i < self.selected).or(hits.last())
+        };
+        if let Some(&i) = next {
+            self.selected = i;
+        }
+    }
+    /// Indices (into the current view's list) of the tasks that are listed, in order.
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let tasks = self.current_tasks();
+        let tag = self.tag_filter();
+        let query = self.search_query();
+        (0..tasks.len())
+            .filter(|&i| tag.map_or(true, |tag| tasks[i].has_tag(tag)))
+            .filter(|&i| query.map_or(true, |q| crate::search::matches(&tasks[i], q, &self.config)))
+            .collect()
+    }
+    /// Row of task `idx` among the listed tasks.
+    pub fn row_of_index(&self, idx: usize) -> Option<usize> {
+        self.visible_indices().iter().position(|&i| i == idx)
+    }
+    // Keep the selection on a listed task: the next one below, else the last one.
+    fn snap_selection_to_filter(&mut self) {
+        if self.tag_filter().is_none() && self.search_query().is_none() {
             return;
         }
-        let last = len - 1;
-        let new = (self.selected + 1).min(last);
-        if new != self.selected {
-            self.selected = new;
+        let visible = self.visible_indices();
+        if visible.is_empty() || visible.contains(&self.selected) {
+            return;
         }
+        self.selected = visible
+            .iter()
+            .copied()
+            .find(|&i| i > self.selected)
+            .unwrap_or(visible[visible.len() - 1]);
     }
 
-    pub fn postpone_selected(&mut self) {
+    /// Move the selected Today task to tomorrow. Returns its index in Future.
+    pub fn postpone_selected(&mut self) -> Option<usize> {
         if self.day.tasks.is_empty() {
-            return;
+            return None;
         }
         let idx = self.selected.min(self.day.tasks.len() - 1);
-        if let Some(task) = self.day.remove(idx) {
+        let moved = self.day.remove(idx).map(|task| {
             // stay planned for tomorrow
             let next_day = crate::date::add_days_to_ymd(today_ymd(), 1);
             self.tomorrow.push(Task {
@@ -1292,12 +1812,14 @@ impl App {
                 planned_ymd: next_day,
                 ..task
             });
-        }
+            self.tomorrow.len() - 1
+        });
         if !self.day.tasks.is_empty() {
             self.selected = self.selected.min(self.day.tasks.len() - 1);
         } else {
             self.selected = 0;
         }
+        moved
     }
 
     /// Bring a task from Future to Today (mirror of postpone). No-op unless in Future view.
@@ -1326,6 +1848,9 @@ impl App {
     pub fn history_tasks(&self) -> &Vec<Task> {
         &self.history
     }
+    pub fn routines(&self) -> &Vec<Routine> {
+        &self.routines
+    }
 
     pub fn view(&self) -> View {
         self.view
@@ -1339,7 +1864,7 @@ impl App {
         self.input.is_some()
     }
     pub fn input_buffer(&self) -> Option<&str> {
-        self.input.as_ref().map(|i| i.buffer.as_str())
+        self.input.as_ref().map(|i| i.editor.text())
     }
     pub fn is_estimate_editing(&self) -> bool {
         matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::EstimateEdit))
@@ -1350,6 +1875,26 @@ impl App {
     pub fn is_command_mode(&self) -> bool {
         matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::Command))
     }
+    pub fn command_feedback(&self) -> Option<&CommandFeedback> {
+        self.command_feedback.as_ref().filter(|_| self.is_command_mode())
+    }
+    /// Problems reported by external syncs, joined for the header; `None` when all is well.
+    pub fn sync_notice(&self) -> Option<String> {
+        if self.sync_notices.is_empty() {
+            return None;
+        }
+        Some(self.sync_notices.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>().join("  "))
+    }
+    /// Set (`Some`) or clear (`None`) the notice of one sync `source`.
+    pub fn set_sync_notice(&mut self, source: &'static str, notice: Option<String>) {
+        self.sync_notices.retain(|(s, _)| *s != source);
+        if let Some(n) = notice {
+            self.sync_notices.push((source, n));
+        }
+    }
+    pub fn is_confirm_paste(&self) -> bool {
+        matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::ConfirmPaste))
+    }
     pub fn is_confirm_delete(&self) -> bool {
         matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::ConfirmDelete))
     }
@@ -1359,6 +1904,52 @@ impl App {
     pub fn is_start_time_edit(&self) -> bool {
         matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::StartTimeEdit))
     }
+    pub fn is_session_edit(&self) -> bool {
+        matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::SessionEdit))
+    }
+    pub fn session_editor(&self) -> Option<&SessionEditor> {
+        self.session_edit.as_ref()
+    }
+    pub fn is_note_edit(&self) -> bool {
+        matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::NoteEdit))
+    }
+    pub fn is_search_mode(&self) -> bool {
+        matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::Search))
+    }
+    pub fn is_title_edit(&self) -> bool {
+        matches!(self.input.as_ref().map(|i| i.kind), Some(InputKind::TitleEdit))
+    }
+    /// Title, rename, command and search inputs (edited with `LineEditor`).
+    pub fn is_line_input(&self) -> bool {
+        matches!(
+            self.input.as_ref().map(|i| i.kind),
+            Some(
+                InputKind::Normal
+                    | InputKind::Interrupt
+                    | InputKind::Command
+                    | InputKind::Search
+                    | InputKind::TitleEdit
+            )
+        )
+    }
+    /// Editor of the current single-line input (for cursor rendering).
+    pub fn line_editor(&self) -> Option<&LineEditor> {
+        self.input.as_ref().filter(|_| self.is_line_input()).map(|i| &i.editor)
+    }
+    pub fn detail_open(&self) -> bool {
+        self.detail_open
+    }
+    pub fn toggle_detail(&mut self) {
+        self.detail_open = !self.detail_open;
+    }
+    /// Selected task in the current view, if any.
+    pub fn selected_task(&self) -> Option<&Task> {
+        match self.view {
+            View::Past => self.history.get(self.selected),
+            View::Today => self.day.tasks.get(self.selected),
+            View::Future => self.tomorrow.get(self.selected),
+        }
+    }
     /// True only when typing a task title (Normal/Interrupt), not for estimate/confirm popups.
     pub fn is_text_input_mode(&self) -> bool {
         matches!(
@@ -1370,7 +1961,7 @@ impl App {
         self.day.tasks.get(self.selected).map(|t| t.estimate_min)
     }
     pub fn new_task_title(&self) -> Option<&str> {
-        self.new_task.as_ref().map(|d| d.title.as_str())
+        self.new_task.as_ref().map(|d| d.spec.title.as_str())
     }
     pub fn new_task_default_estimate(&self) -> Option<u16> {
         self.new_task.as_ref().map(|d| d.default_estimate)
@@ -1408,6 +1999,10 @@ impl App {
     }
 
     fn set_view(&mut self, v: View) {
+        if v == View::Past {
+            // Days that fail to load are left untouched on disk and simply not shown
+            let _ = self.ensure_history_loaded();
+        }
         self.view = v;
         // clamp selection to current view length
         let len = self.current_len();
@@ -1427,10 +2022,14 @@ impl App {
     }
 
     fn current_len(&self) -> usize {
+        self.current_tasks().len()
+    }
+
+    fn current_tasks(&self) -> &[Task] {
         match self.view {
-            View::Past => self.history.len(),
-            View::Today => self.day.tasks.len(),
-            View::Future => self.tomorrow.len(),
+            View::Past => &self.history,
+            View::Today => &self.day.tasks,
+            View::Future => &self.tomorrow,
         }
     }
 
@@ -1447,7 +2046,12 @@ impl App {
         let today = today_ymd();
         if today != self.last_seen_ymd {
             self.last_seen_ymd = today;
+            // Earlier states would resurrect tasks that are about to move to Past
+            self.undo_stack.clear();
+            self.redo_stack.clear();
             self.sweep_done_before(today);
+            self.generate_routine_tasks(today);
+            self.import_appointments();
         }
         if let Some(active) = self.day.active_index() {
             if let Some(t) = self.day.tasks.get_mut(active) {
@@ -1463,7 +2067,8 @@ impl App {
 
 impl App {
     /// Start or resume the selected task. If another task is active, pause it first.
-    fn start_selected(&mut self) {
+    /// Start (or resume) the selected Today task, pausing the running one.
+    pub fn start_selected(&mut self) {
         // Only meaningful on Today view
         if self.view != View::Today {
             return;
@@ -1496,7 +2101,8 @@ impl App {
             }
         }
     }
-    fn delete_selected(&mut self) {
+    /// Delete the selected Today task without confirmation.
+    pub fn delete_selected(&mut self) {
         if self.view != View::Today || self.day.tasks.is_empty() {
             return;
         }
@@ -1509,6 +2115,124 @@ impl App {
         }
     }
 
+    /// Run a user-driven handler and record one undo step if it changed any task state.
+    fn with_undo<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        if self.undo_depth > 0 {
+            return f(self);
+        }
+        let mut before = self.capture_undo();
+        self.undo_depth += 1;
+        let r = f(self);
+        self.undo_depth -= 1;
+        let past_edits = std::mem::take(&mut self.undo_past_edits);
+        if std::mem::take(&mut self.undo_restored) {
+            return r;
+        }
+        before.past = past_edits.into_iter().rev().map(|(b, a)| (a, b)).collect();
+        if !self.undo_matches(&before) {
+            self.undo_stack.push(before);
+            if self.undo_stack.len() > UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+            self.redo_stack.clear();
+        }
+        r
+    }
+
+    fn capture_undo(&self) -> UndoEntry {
+        UndoEntry {
+            day: self.day.clone(),
+            tomorrow: self.tomorrow.clone(),
+            past: Vec::new(),
+            routines: self.routines.clone(),
+            selected: self.selected,
+            view: self.view,
+        }
+    }
+
+    fn undo_matches(&self, e: &UndoEntry) -> bool {
+        e.day == self.day
+            && e.tomorrow == self.tomorrow
+            && e.routines == self.routines
+            && e.past.is_empty()
+    }
+
+    /// Swap the current state with `e`, returning the state that was replaced.
+    /// Time tracked since `e` was taken survives: the running task keeps its sessions and
+    /// actual time, and routines keep their recorded history.
+    fn restore_undo(&mut self, e: UndoEntry) -> UndoEntry {
+        let mut current = self.capture_undo();
+        let running = self.day.active_index().map(|i| self.day.tasks[i].clone());
+        let live_routines = std::mem::take(&mut self.routines);
+        self.day = e.day;
+        self.tomorrow = e.tomorrow;
+        self.routines = e.routines;
+        // The same run: a task whose open session started at the same minute
+        let open_start =
+            |t: &Task| t.sessions.last().filter(|s| s.end_min.is_none()).map(|s| s.start_min);
+        if let Some(live) = running.filter(|t| open_start(t).is_some()) {
+            if let Some(t) = self.day.tasks.iter_mut().find(|t| open_start(t) == open_start(&live))
+            {
+                t.sessions = live.sessions;
+                t.actual_min = live.actual_min;
+                t.actual_carry_sec = live.actual_carry_sec;
+            }
+        }
+        for r in self.routines.iter_mut() {
+            if let Some(live) = live_routines.iter().find(|l| l.id == r.id) {
+                if live.history != r.history {
+                    r.history = live.history.clone();
+                    r.estimate_min = live.estimate_min;
+                }
+                r.last_generated_ymd = live.last_generated_ymd;
+            }
+        }
+        for (cur, restored) in e.past {
+            if self.replace_history_task(&cur, restored.clone()) {
+                self.history_edits.push((cur.clone(), restored.clone()));
+                current.past.insert(0, (restored, cur));
+            }
+        }
+        self.input = None;
+        self.new_task = None;
+        self.session_edit = None;
+        self.drag_from = None;
+        self.set_view(e.view);
+        let len = self.current_len();
+        self.selected = if len == 0 { 0 } else { e.selected.min(len - 1) };
+        // Inside a handler, keep the surrounding `with_undo` from recording the restore
+        self.undo_restored = self.undo_depth > 0;
+        current
+    }
+
+    /// Revert the last recorded change. Returns false when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(e) = self.undo_stack.pop() else {
+            return false;
+        };
+        let current = self.restore_undo(e);
+        self.redo_stack.push(current);
+        true
+    }
+
+    /// Re-apply the last undone change. Returns false when there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(e) = self.redo_stack.pop() else {
+            return false;
+        };
+        let current = self.restore_undo(e);
+        self.undo_stack.push(current);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
     /// Replace task lists from an external snapshot.
     /// - Resets selection and carry seconds; keeps config.
     pub fn apply_snapshot(
@@ -1519,11 +2243,193 @@ impl App {
     ) {
         self.day = DayPlan::new(today);
         self.tomorrow = future;
-        self.history = past;
+        self.history = Vec::new();
+        self.history_days.clear();
+        self.history_dirty.clear();
+        self.history_all_loaded = self.history_store.is_none();
+        for t in past {
+            self.push_history(t);
+        }
         self.selected = 0;
         self.set_view(View::Today);
         // Ensure old done items are moved to Past on startup
         self.sweep_done_before(self.last_seen_ymd);
+        // Instantiate routines due today (no-op if already generated for this date)
+        self.generate_routine_tasks(self.last_seen_ymd);
+    }
+
+    /// Add today's events from `[ics] paths` (skipping ones already imported); a file that
+    /// cannot be read shows in the header. Runs at day rollover; the TUI also calls it on
+    /// startup.
+    pub fn import_appointments(&mut self) {
+        if self.config.ics.paths.is_empty() {
+            return;
+        }
+        let notice = crate::ics::import_configured(self).err().map(|e| format!("ics: {e:#}"));
+        self.set_sync_notice("ics", notice);
+    }
+
+    /// Back Past with per-date history files. Call before `apply_snapshot`; from then on
+    /// `history` is filled lazily (a day when something is swept into it, everything when
+    /// the Past view opens).
+    pub fn attach_history_store(&mut self, store: HistoryStore) {
+        self.history_store = Some(store);
+        self.history_all_loaded = false;
+    }
+
+    pub fn history_store(&self) -> Option<&HistoryStore> {
+        self.history_store.as_ref()
+    }
+
+    /// Dates whose Past tasks changed since load and need to be written back.
+    pub fn history_dirty_days(&self) -> &BTreeSet<u32> {
+        &self.history_dirty
+    }
+
+    /// Forget the dirty set after the changed days were written (`storage::save_history`).
+    pub fn mark_history_saved(&mut self) {
+        self.history_dirty.clear();
+    }
+
+    /// Past edits `(before, after)` made since the journal last recorded them.
+    pub fn history_edits(&self) -> &[(Task, Task)] {
+        &self.history_edits
+    }
+
+    pub fn mark_history_edits_journaled(&mut self) {
+        self.history_edits.clear();
+    }
+
+    /// Replace the Past task equal to `before` with `after` (journal replay, undo),
+    /// loading the days involved first. Returns false when no such task exists.
+    pub fn replace_history_task(&mut self, before: &Task, after: Task) -> bool {
+        self.load_history_day(day_key(before));
+        self.load_history_day(day_key(&after));
+        let Some(i) = self.history.iter().position(|t| t == before) else {
+            return false;
+        };
+        self.history_dirty.insert(day_key(before));
+        self.history_dirty.insert(day_key(&after));
+        self.history[i] = after;
+        true
+    }
+
+    pub fn journal_seq(&self) -> u64 {
+        self.journal_seq
+    }
+
+    pub fn set_journal_seq(&mut self, seq: u64) {
+        self.journal_seq = seq;
+    }
+
+    /// True when `history` holds the complete set of tasks for `ymd`.
+    pub fn history_day_loaded(&self, ymd: u32) -> bool {
+        self.history_all_loaded || self.history_days.contains(&ymd)
+    }
+
+    /// Past tasks filed under `ymd` (see `history::day_key`).
+    pub fn history_tasks_for_day(&self, ymd: u32) -> Vec<Task> {
+        self.history.iter().filter(|t| day_key(t) == ymd).cloned().collect()
+    }
+
+    /// Load every history file not yet in memory, keeping Past ordered by date.
+    pub fn ensure_history_loaded(&mut self) -> anyhow::Result<()> {
+        if self.history_all_loaded {
+            return Ok(());
+        }
+        let Some(store) = self.history_store.clone() else {
+            self.history_all_loaded = true;
+            return Ok(());
+        };
+        let mut first_err = None;
+        for ymd in store.list_days()? {
+            if self.history_days.contains(&ymd) {
+                continue;
+            }
+            match store.load_day(ymd) {
+                Ok(tasks) => {
+                    self.history.extend(tasks);
+                    self.history_days.insert(ymd);
+                }
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        self.history.sort_by_key(day_key);
+        match first_err {
+            Some(e) => Err(e),
+            None => {
+                self.history_all_loaded = true;
+                Ok(())
+            }
+        }
+    }
+
+    /// Merge the history file of `ymd` into memory unless it is already there; on failure
+    /// the day stays unloaded and saving refuses to overwrite its file.
+    fn load_history_day(&mut self, ymd: u32) {
+        if self.history_day_loaded(ymd) {
+            return;
+        }
+        if let Some(store) = &self.history_store {
+            if let Ok(tasks) = store.load_day(ymd) {
+                self.history.extend(tasks);
+                self.history_days.insert(ymd);
+            }
+        }
+    }
+
+    fn push_history(&mut self, task: Task) {
+        let ymd = day_key(&task);
+        if self.history_store.is_some() {
+            self.load_history_day(ymd);
+            // A replayed journal may archive a task that already reached its day file
+            if self.history.contains(&task) {
+                return;
+            }
+        }
+        self.history.push(task);
+        self.history_dirty.insert(ymd);
+    }
+
+    /// Replace routine definitions (e.g., from a snapshot). Call before `apply_snapshot`
+    /// so that startup generation sees them. An invalid `anchor_ymd` (hand-edited
+    /// snapshot) is reset to today with a warning instead of failing date math later.
+    pub fn set_routines(&mut self, mut routines: Vec<Routine>) {
+        for r in routines.iter_mut().filter(|r| !crate::date::is_valid_ymd(r.anchor_ymd)) {
+            tracing::warn!("routine {} ({}): invalid anchor date {}", r.id, r.title, r.anchor_ymd);
+            r.anchor_ymd = today_ymd();
+        }
+        self.routines = routines;
+    }
+
+    /// Register a new routine and return its assigned id.
+    pub fn add_routine(&mut self, mut routine: Routine) -> u32 {
+        let id = self.routines.iter().map(|r| r.id).max().map_or(1, |m| m + 1);
+        routine.id = id;
+        self.routines.push(routine);
+        id
+    }
+
+    /// Append one task per routine due on `ymd` to Today, at most once per date.
+    /// Returns the number of generated tasks.
+    pub fn generate_routine_tasks(&mut self, ymd: u32) -> usize {
+        let mut generated = 0;
+        for r in self.routines.iter_mut() {
+            if !r.is_due(ymd) {
+                continue;
+            }
+            r.last_generated_ymd = Some(ymd);
+            // Defensive: skip if an instance for this date is already present
+            let exists =
+                self.day.tasks.iter().any(|t| t.routine_id == Some(r.id) && t.planned_ymd == ymd);
+            if !exists {
+                self.day.add_task(r.instantiate(ymd));
+                generated += 1;
+            }
+        }
+        generated
     }
 }
 
@@ -1535,7 +2441,7 @@ impl App {
             let move_to_past = matches!(self.day.tasks[i].done_ymd, Some(d) if d < ymd);
             if move_to_past {
                 if let Some(task) = self.day.remove(i) {
-                    self.history.push(task);
+                    self.push_history(task);
                 }
                 // don't increment i; elements shifted left
                 continue;
@@ -1550,82 +2456,312 @@ impl App {
         }
     }
 
-    fn apply_command(&mut self, cmd: &str) {
-        // Supported:
-        // - "est +15m" / "est -5" / "est 90m" (estimate edit)
-        // - "base HH:MM" (予定の基準時刻 = day_start を変更)
-        // - "mode blocks|list" (表示モードを切替)
-        let mut it = cmd.split_whitespace();
-        let Some(head) = it.next() else {
-            return;
-        };
-        match head {
-            "est" => {
-                let Some(arg) = it.next() else {
-                    return;
-                };
-                let s = arg.trim();
-                if s.starts_with('+') || s.starts_with('-') {
-                    // relative delta
-                    let sign = if s.starts_with('+') { 1 } else { -1 };
-                    let num_part = s[1..].trim_end_matches('m');
-                    if let Ok(v) = num_part.parse::<i16>() {
-                        let delta = v.saturating_mul(sign);
-                        self.day.adjust_estimate(self.selected, delta);
-                    }
-                } else {
-                    // absolute minutes
-                    let num_part = s.trim_end_matches('m');
-                    if let Ok(v) = num_part.parse::<u16>() {
-                        if let Some(t) = self.day.tasks.get_mut(self.selected) {
-                            t.estimate_min = v;
+    /// Run a parsed `:` command. Errors are shown in the command popup.
+    fn run_command(&mut self, cmd: crate::command::PaletteCommand) -> anyhow::Result<()> {
+        use crate::command::{MoveTo, PaletteCommand as C, TagEdit};
+        match cmd {
+            C::Action(action) => {
+                self.check_command_action(action)?;
+                self.apply_action(action);
+            }
+            C::Estimate(est) => {
+                let idx = self.command_today_task()?;
+                self.set_task_estimate(idx, est);
+            }
+            C::At(minutes) => {
+                let idx = self.command_today_task()?;
+                self.set_task_fixed_start(idx, minutes);
+            }
+            C::Base { hour, minute } => {
+                // Update in-memory immediately
+                self.config.day_start_minutes = hour * 60 + minute;
+                // Persist by default
+                let _ = crate::config::write_day_start(hour, minute);
+            }
+            C::Routine(rule) => {
+                self.command_today_task()?;
+                self.apply_routine_command(rule);
+            }
+            C::Mode(mode) => self.display = mode,
+            C::Tag(edits) => {
+                let edited = self.update_selected_current(|t| {
+                    for edit in edits {
+                        match edit {
+                            TagEdit::Add(tag) => {
+                                crate::tags::add_tag(&mut t.tags, tag);
+                            }
+                            TagEdit::Remove(tag) => {
+                                crate::tags::remove_tag(&mut t.tags, &tag);
+                            }
+                            TagEdit::Clear => t.tags.clear(),
                         }
                     }
+                });
+                if edited.is_none() {
+                    anyhow::bail!("select a task first");
                 }
             }
-            "at" => {
-                if let Some(arg) = it.next() {
-                    if arg == "-"
-                        || arg.eq_ignore_ascii_case("none")
-                        || arg.eq_ignore_ascii_case("clear")
-                    {
-                        if let Some(t) = self.day.tasks.get_mut(self.selected) {
-                            t.fixed_start_min = None;
-                        }
-                    } else if let Ok((h, m)) = crate::config::parse_hhmm_or_compact(arg) {
-                        let minutes = h * 60 + m;
-                        if let Some(t) = self.day.tasks.get_mut(self.selected) {
-                            t.fixed_start_min = Some(minutes);
-                        }
-                    }
+            C::Filter(tag) => self.set_tag_filter(tag),
+            C::Rename(title) => {
+                if self.update_selected_current(|t| t.title = title).is_none() {
+                    anyhow::bail!("select a task first");
                 }
             }
-            "base" => {
-                if let Some(arg) = it.next() {
-                    if let Ok((h, m)) = crate::config::parse_hhmm_or_compact(arg) {
-                        // Update in-memory immediately
-                        self.config.day_start_minutes = h * 60 + m;
-                        // Persist by default
-                        let _ = crate::config::write_day_start(h, m);
-                    }
+            C::Add { interrupt, title } => {
+                let kind = if interrupt { InputKind::Interrupt } else { InputKind::Normal };
+                self.submit_line_input(kind, title);
+            }
+            C::Postpone { days } => {
+                self.command_today_task()?;
+                self.postpone_selected();
+                if let Some(t) = self.tomorrow.last_mut() {
+                    t.planned_ymd = crate::date::add_days_to_ymd(today_ymd(), days as i32);
                 }
             }
-            "mode" => {
-                if let Some(arg) = it.next() {
-                    match arg {
-                        // Backward-compatible aliases now map to Calendar
-                        "blocks" | "timeline" | "calendar" => self.display = DisplayMode::Calendar,
-                        "list" | "table" => self.display = DisplayMode::List,
-                        _ => {}
+            C::Move { task, to } => {
+                let len = self.current_len();
+                if self.view == View::Past {
+                    anyhow::bail!("past tasks cannot be reordered");
+                }
+                let from = task.unwrap_or(self.selected);
+                if from >= len {
+                    anyhow::bail!("no task #{} (the list has {len})", from + 1);
+                }
+                let dest = match to {
+                    MoveTo::Top => 0,
+                    MoveTo::Bottom => len - 1,
+                    MoveTo::Up => from.saturating_sub(1),
+                    MoveTo::Down => (from + 1).min(len - 1),
+                    MoveTo::Position(p) => p.min(len - 1),
+                };
+                self.selected = if self.view == View::Today {
+                    // `move_index` takes an insertion slot in the list before removal
+                    let slot = if dest > from { dest + 1 } else { dest };
+                    self.day.move_index(from, slot)
+                } else {
+                    let t = self.tomorrow.remove(from);
+                    self.tomorrow.insert(dest, t);
+                    dest
+                };
+            }
+            C::Category(name) => {
+                let Some(id) = self
+                    .config
+                    .categories
+                    .entries
+                    .iter()
+                    .find(|e| {
+                        e.id.id().eq_ignore_ascii_case(&name) || e.name.eq_ignore_ascii_case(&name)
+                    })
+                    .map(|e| e.id.clone())
+                else {
+                    anyhow::bail!("unknown category `{name}`");
+                };
+                if self.update_selected_current(|t| t.category = id).is_none() {
+                    anyhow::bail!("select a task first");
+                }
+            }
+            C::Date(ymd) => self.plan_selected_for(ymd)?,
+            C::Sort(key) => {
+                if self.view == View::Past {
+                    anyhow::bail!("past tasks cannot be reordered");
+                }
+                let order = crate::command::sorted_order(self.current_tasks(), key, &self.config);
+                if self.view == View::Today {
+                    self.day.apply_order(&order);
+                } else {
+                    self.tomorrow = order.iter().map(|&i| self.tomorrow[i].clone()).collect();
+                }
+                // Keep the same task selected
+                self.selected = order.iter().position(|&i| i == self.selected).unwrap_or(0);
+            }
+            C::View(view) => self.set_view(view),
+            C::Select(idx) => {
+                let len = self.current_len();
+                if idx >= len {
+                    anyhow::bail!("no task #{} (the list has {len})", idx + 1);
+                }
+                self.selected = idx;
+            }
+            C::Search(query) => self.set_search(Some(query)),
+            C::Export { part, path } => {
+                let path = match path {
+                    Some(p) => std::path::PathBuf::from(p),
+                    None => match self.history_store.as_ref().and_then(|h| h.root().parent()) {
+                        Some(dir) => dir.join("chute.ics"),
+                        None => anyhow::bail!("export: give a file path"),
+                    },
+                };
+                let today = today_ymd();
+                let past = crate::ics::recent_past(self, today)?;
+                let now = crate::clock::system_now_minutes();
+                let ics = crate::ics::export(self, &past, today, now, part);
+                crate::storage::write_atomic(&path, ics.text.as_bytes())?;
+                // Reopen the prompt to show where the file went
+                self.input =
+                    Some(Input { kind: InputKind::Command, editor: LineEditor::default() });
+                self.command_feedback = Some(CommandFeedback::Done(format!(
+                    "exported {} events to {}",
+                    ics.events,
+                    path.display()
+                )));
+            }
+            C::Report { ymd, path } => {
+                let today = today_ymd();
+                let ymd = ymd.unwrap_or(today);
+                let date = crate::date::format_ymd(ymd);
+                let path = match path {
+                    Some(p) => std::path::PathBuf::from(p),
+                    None => match self.history_store.as_ref().and_then(|h| h.root().parent()) {
+                        Some(dir) => dir.join("reports").join(format!("{date}.md")),
+                        None => anyhow::bail!("report: give a file path"),
+                    },
+                };
+                let template = crate::report::template(&self.config.report, None)?;
+                let tasks = crate::report::tasks_for_day(self, today, ymd)?;
+                let text = crate::report::render(self, ymd, &tasks, &template);
+                crate::storage::write_atomic(&path, text.as_bytes())?;
+                self.input =
+                    Some(Input { kind: InputKind::Command, editor: LineEditor::default() });
+                self.command_feedback = Some(CommandFeedback::Done(format!(
+                    "report {date} written to {}",
+                    path.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Selected Today task for commands that only apply to Today.
+    fn command_today_task(&self) -> anyhow::Result<usize> {
+        if self.view != View::Today {
+            anyhow::bail!("only works in the Today view");
+        }
+        if self.selected >= self.day.tasks.len() {
+            anyhow::bail!("select a task first");
+        }
+        Ok(self.selected)
+    }
+
+    // Reject palette actions that would silently do nothing in the current view
+    fn check_command_action(&self, action: crate::config::Action) -> anyhow::Result<()> {
+        use crate::config::Action as A;
+        match action {
+            A::StartOrResume
+            | A::FinishActive
+            | A::Delete
+            | A::ReorderUp
+            | A::ReorderDown
+            | A::EstimatePlus
+            | A::OpenPopup
+            | A::Postpone => self.command_today_task().map(|_| ()),
+            A::BringToToday if self.view != View::Future => {
+                anyhow::bail!("only works in the Future view")
+            }
+            A::BringToToday
+            | A::CategoryCycle
+            | A::CategoryPicker
+            | A::SessionEditor
+            | A::EditNote
+            | A::Rename
+                if self.selected_task().is_none() =>
+            {
+                anyhow::bail!("select a task first")
+            }
+            A::SearchNext | A::SearchPrev if self.search_query().is_none() => {
+                anyhow::bail!("no active search")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Plan the selected task for `ymd`: today moves it to Today, later days to Future.
+    fn plan_selected_for(&mut self, ymd: u32) -> anyhow::Result<()> {
+        let today = today_ymd();
+        if ymd < today {
+            anyhow::bail!("{} is in the past", crate::date::format_ymd(ymd));
+        }
+        match self.view {
+            View::Past => anyhow::bail!("past tasks cannot be planned"),
+            View::Today => {
+                self.command_today_task()?;
+                if ymd > today {
+                    self.postpone_selected();
+                    if let Some(t) = self.tomorrow.last_mut() {
+                        t.planned_ymd = ymd;
                     }
                 }
             }
-            _ => {}
+            View::Future => {
+                if self.selected >= self.tomorrow.len() {
+                    anyhow::bail!("select a task first");
+                }
+                if ymd == today {
+                    self.bring_selected_from_future();
+                } else {
+                    self.tomorrow[self.selected].planned_ymd = ymd;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tab in the command prompt: complete the word before the cursor.
+    fn complete_command(&mut self) {
+        let tasks = self.day.tasks.iter().chain(&self.tomorrow).chain(&self.history);
+        let sources = crate::command::Sources::collect(&self.config, tasks);
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        let text = input.editor.text();
+        let split = text.char_indices().nth(input.editor.cursor()).map_or(text.len(), |(b, _)| b);
+        let (before, after) = text.split_at(split);
+        let done = crate::command::complete(before, &sources);
+        let cursor = done.text.chars().count();
+        input.editor.set_text(format!("{}{}", done.text, after));
+        for _ in cursor..input.editor.text().chars().count() {
+            input.editor.move_left();
+        }
+        if !done.candidates.is_empty() {
+            self.command_feedback = Some(CommandFeedback::Candidates(done.candidates));
         }
     }
 }
 
 impl App {
+    /// Turn the selected Today task into a routine, or detach it with `None`.
+    /// The selected task becomes today's instance so it is not generated twice.
+    fn apply_routine_command(&mut self, rule: Option<Recurrence>) {
+        if self.view != View::Today {
+            return;
+        }
+        let idx = self.selected;
+        let Some(task) = self.day.tasks.get(idx) else {
+            return;
+        };
+        let Some(rec) = rule else {
+            if let Some(id) = task.routine_id {
+                self.routines.retain(|r| r.id != id);
+                self.day.tasks[idx].routine_id = None;
+            }
+            return;
+        };
+        let today = today_ymd();
+        if let Some(id) = task.routine_id {
+            // Already a routine instance: just update its rule
+            if let Some(r) = self.routines.iter_mut().find(|r| r.id == id) {
+                r.recurrence = rec;
+                return;
+            }
+        }
+        let mut routine = Routine::from_task(0, task, rec, today);
+        routine.last_generated_ymd = Some(today);
+        let id = self.add_routine(routine);
+        if let Some(t) = self.day.tasks.get_mut(idx) {
+            t.routine_id = Some(id);
+        }
+    }
+
     fn toggle_task_start_pause(&mut self, idx: usize) {
         if self.day.active_index() == Some(idx) {
             self.day.pause_active();
@@ -1635,60 +2771,249 @@ impl App {
         }
     }
 
+    /// Advance the selected task to the next selectable category (config order).
+    fn cycle_selected_category(&mut self) {
+        let Some(cur) = self.selected_task().map(|t| t.category.clone()) else {
+            return;
+        };
+        let next = self.config.categories.next_after(&cur);
+        self.update_selected_current(|t| t.category = next);
+    }
+
     fn apply_selected_category(&mut self) {
-        use crate::task::Category as C;
-        let pick = self.cat_pick_idx;
-        if let Some(t) = self.selected_task_mut_current() {
-            t.category = match pick {
-                0 => C::General,
-                1 => C::Work,
-                2 => C::Home,
-                3 => C::Hobby,
-                _ => C::General,
-            };
-        }
+        let Some(pick) =
+            self.config.categories.selectable().nth(self.cat_pick_idx).map(|e| e.id.clone())
+        else {
+            return;
+        };
+        self.update_selected_current(|t| t.category = pick);
     }
 
     fn open_category_picker_for(&mut self, idx: usize) {
         self.selected = idx;
         // Initialize picker index to current category position for the active list
-        use crate::task::Category as C;
         let cur = match self.view {
-            View::Past => self.history.get(idx).map(|t| t.category),
-            View::Today => self.day.tasks.get(idx).map(|t| t.category),
-            View::Future => self.tomorrow.get(idx).map(|t| t.category),
-        }
-        .unwrap_or(C::General);
-        self.cat_pick_idx = match cur {
-            C::General => 0,
-            C::Work => 1,
-            C::Home => 2,
-            C::Hobby => 3,
+            View::Past => self.history.get(idx).map(|t| &t.category),
+            View::Today => self.day.tasks.get(idx).map(|t| &t.category),
+            View::Future => self.tomorrow.get(idx).map(|t| &t.category),
+        };
+        self.cat_pick_idx = cur.and_then(|c| self.config.categories.position(c)).unwrap_or(0);
+        self.input = Some(Input { kind: InputKind::CategoryPicker, editor: LineEditor::default() });
+    }
+
+    /// Open the session editor for the selected Today/Past task.
+    fn open_session_editor(&mut self) {
+        if self.view == View::Future {
+            return;
+        }
+        let Some(task) = self.selected_task() else {
+            return;
         };
-        self.input = Some(Input { kind: InputKind::CategoryPicker, buffer: String::new() });
+        self.session_edit = Some(SessionEditor::new(&task.title, task.sessions.clone()));
+        self.input = Some(Input { kind: InputKind::SessionEdit, editor: LineEditor::default() });
     }
 
-    fn selected_task_mut_current(&mut self) -> Option<&mut Task> {
+    /// Open the title editor prefilled with the selected task's title, cursor at the end.
+    fn open_title_editor(&mut self) {
+        let Some(title) = self.selected_task().map(|t| t.title.clone()) else {
+            return;
+        };
+        self.input = Some(Input { kind: InputKind::TitleEdit, editor: LineEditor::new(title) });
+    }
+
+    /// Keys for the single-line inputs: editing goes to `LineEditor`, Up/Down recall history.
+    fn handle_line_input_key(&mut self, code: KeyCode, mods: KeyModifiers) {
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        let kind = input.kind;
+        let history = self.input_history.for_kind(kind);
+        if kind == InputKind::Command {
+            self.command_feedback = None;
+        }
+        match code {
+            KeyCode::Tab if kind == InputKind::Command => {
+                self.complete_command();
+                return;
+            }
+            KeyCode::Enter => {
+                let text = input.editor.text().to_string();
+                push_history(history, &text);
+                self.submit_line_input(kind, text);
+                return;
+            }
+            KeyCode::Esc => {
+                self.input = None;
+                if kind == InputKind::Search {
+                    self.set_search(None);
+                }
+                return;
+            }
+            KeyCode::Up => input.editor.recall_prev(history),
+            KeyCode::Down => input.editor.recall_next(history),
+            _ => {
+                input.editor.handle_key(code, mods);
+            }
+        }
+        self.sync_search_input();
+    }
+
+    fn submit_line_input(&mut self, kind: InputKind, text: String) {
+        match kind {
+            InputKind::Normal | InputKind::Interrupt => {
+                let (default_title, est) = match kind {
+                    InputKind::Normal => ("New Task", DEFAULT_ESTIMATE_MIN),
+                    _ => ("Interrupt", DEFAULT_INTERRUPT_ESTIMATE_MIN),
+                };
+                let today = today_ymd();
+                let mut spec = QuickAdd::parse(&text, &self.config, today);
+                if spec.title.is_empty() {
+                    spec.title = default_title.to_string();
+                }
+                // An estimate in the title skips the estimate step
+                if spec.estimate_min.is_some() {
+                    self.input = None;
+                    self.add_quick_task(spec, est, kind == InputKind::Interrupt);
+                    return;
+                }
+                // Move to estimate entry step with default prefilled
+                self.new_task = Some(NewTaskDraft {
+                    source: kind,
+                    planned_ymd: spec.planned_ymd.unwrap_or(today).max(today),
+                    spec,
+                    default_estimate: est,
+                });
+                self.input =
+                    Some(Input { kind: InputKind::NewTaskEstimate, editor: LineEditor::default() });
+            }
+            InputKind::Command => {
+                // Closed first so commands can open their own popup
+                self.input = None;
+                if text.trim().is_empty() {
+                    return;
+                }
+                let result = crate::command::parse(&text).and_then(|cmd| self.run_command(cmd));
+                if let Err(e) = result {
+                    self.input =
+                        Some(Input { kind: InputKind::Command, editor: LineEditor::new(text) });
+                    self.command_feedback = Some(CommandFeedback::Error(e.to_string()));
+                }
+            }
+            InputKind::TitleEdit => {
+                self.input = None;
+                let title = text.trim().to_string();
+                if !title.is_empty() {
+                    self.update_selected_current(|t| t.title = title);
+                }
+            }
+            // Search: the query is already applied as you type; keep it as a filter
+            _ => self.input = None,
+        }
+    }
+
+    // Filter as you type while the search prompt is open
+    fn sync_search_input(&mut self) {
+        if self.is_search_mode() {
+            let query = self.input_buffer().unwrap_or_default().to_string();
+            self.set_search(Some(query));
+        }
+    }
+
+    /// Open the note editor prefilled with the selected task's note.
+    fn open_note_editor(&mut self) {
+        let Some(note) = self.selected_task().map(|t| t.notes.clone()) else {
+            return;
+        };
+        self.input = Some(Input { kind: InputKind::NoteEdit, editor: LineEditor::new(note) });
+    }
+
+    fn close_session_editor(&mut self, apply: bool) {
+        let editor = self.session_edit.take();
+        self.input = None;
+        let Some(editor) = editor.filter(|_| apply) else {
+            return;
+        };
+        let sessions = editor.into_sessions();
+        let now = crate::clock::system_now_minutes();
+        // Only rewrite the task (and mark its history day dirty) when something changed
+        let changed = self.selected_task().is_some_and(|t| t.sessions != sessions);
+        if !changed {
+            return;
+        }
+        self.update_selected_current(|t| {
+            t.sessions = sessions;
+            t.recompute_from_sessions(now);
+        });
+    }
+
+    fn handle_session_editor_key(&mut self, code: KeyCode) {
+        let default_start = self.config.day_start_minutes;
+        let Some(ed) = self.session_edit.as_mut() else {
+            self.input = None;
+            return;
+        };
+        match code {
+            KeyCode::Enter => self.close_session_editor(true),
+            KeyCode::Esc => self.close_session_editor(false),
+            KeyCode::Up | KeyCode::Char('k') => ed.select_up(),
+            KeyCode::Down | KeyCode::Char('j') => ed.select_down(),
+            KeyCode::Tab | KeyCode::BackTab => ed.toggle_field(),
+            KeyCode::Right | KeyCode::Char('l') => ed.adjust(5),
+            KeyCode::Left | KeyCode::Char('h') => ed.adjust(-5),
+            KeyCode::Char('.') => ed.adjust(1),
+            KeyCode::Char(',') => ed.adjust(-1),
+            KeyCode::Char('a') => ed.insert_after(default_start),
+            KeyCode::Char('s') => {
+                ed.split_selected();
+            }
+            KeyCode::Char('d') | KeyCode::Char('x') => {
+                ed.delete_selected();
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply `f` to the selected task of the current view; `None` when nothing is selected.
+    /// A Past edit marks the task's history day dirty, before and after the edit, since
+    /// the edit may move it to another day.
+    fn update_selected_current<R>(&mut self, f: impl FnOnce(&mut Task) -> R) -> Option<R> {
         match self.view {
-            View::Past => self.history.get_mut(self.selected),
-            View::Today => self.day.tasks.get_mut(self.selected),
-            View::Future => self.tomorrow.get_mut(self.selected),
+            View::Past => {
+                let t = self.history.get_mut(self.selected)?;
+                let before = t.clone();
+                let r = f(t);
+                self.history_dirty.insert(day_key(&before));
+                self.history_dirty.insert(day_key(t));
+                if *t != before {
+                    if self.undo_depth > 0 {
+                        self.undo_past_edits.push((before.clone(), t.clone()));
+                    }
+                    self.history_edits.push((before, t.clone()));
+                }
+                Some(r)
+            }
+            View::Today => self.day.tasks.get_mut(self.selected).map(f),
+            View::Future => self.tomorrow.get_mut(self.selected).map(f),
         }
     }
 
     fn index_from_list_row(&self, row: u16, list: Rect) -> usize {
         // Table uses a header row at list.y; first data row starts at list.y + 1.
-        // Map mouse row to data index accordingly.
+        // Map mouse row to data index accordingly (rows list only tag-filtered tasks).
         let rel = row.saturating_sub(list.y.saturating_add(1)) as usize;
-        let len = self.current_len();
-        rel.min(len.saturating_sub(1))
+        let visible = self.visible_indices();
+        match visible.len() {
+            0 => 0,
+            n => visible[rel.min(n - 1)],
+        }
     }
 
     fn update_hover_from_coords(&mut self, col: u16, row: u16, list: Rect) {
         // Only treat rows strictly below the header as actual task rows in normal movement.
         // While dragging, allow hover to extend into the tail space (snaps to last row) so
         // users get a visible drop target at the end of the list.
-        let len = self.current_len() as u16;
+        let visible = self.visible_indices();
+        let len = visible.len() as u16;
         if len == 0 {
             self.hovered = None;
             return;
@@ -1706,10 +3031,10 @@ impl App {
                 self.hovered = Some(idx);
             } else if self.drag_from.is_some() && within_list_block {
                 // Dragging in tail space: snap hover to last row for visual target
-                self.hovered = Some((len - 1) as usize);
+                self.hovered = Some(visible[visible.len() - 1]);
             } else if self.drag_from.is_some() && within_header {
                 // Dragging into head space (header): snap hover to first row
-                self.hovered = Some(0);
+                self.hovered = Some(visible[0]);
             } else {
                 self.hovered = None;
             }
@@ -0,0 +1,545 @@
+//! `:` command palette language.
+//! - `parse` turns a line into a `PaletteCommand`; malformed input is an error whose
+//!   message is shown in the popup.
+//! - `complete` extends the last word (Tab) from command names or the command's arguments.
+//! - `help_for` is the usage line of the command being typed.
+//! - Task numbers (`move 2 top`, `select 3`) are 1-based positions in the current list.
+
+use anyhow::{bail, Result};
+
+use crate::app::{DisplayMode, View};
+use crate::config::{Action, Config};
+use crate::routine::Recurrence;
+use crate::task::EstimateArg;
+use crate::task::{Task, TaskState};
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+}
+
+const fn spec(name: &'static str, usage: &'static str, help: &'static str) -> CommandSpec {
+    CommandSpec { name, usage, help }
+}
+
+/// Every palette command, alphabetically.
+pub const COMMANDS: &[CommandSpec] = &[
+    spec("add", "add [title 45m @cat ^HH:MM >date #tag]", "new task (no title: input popup)"),
+    spec("at", "at [HH:MM|-]", "fix the start time, - clears (no arg: slider)"),
+    spec("base", "base HH:MM", "day start for the plan (saved to config)"),
+    spec("bring", "bring", "move the selected Future task to Today"),
+    spec("cat", "cat [id|next]", "set the category (no arg: picker)"),
+    spec("date", "date today|tomorrow|YYYY-MM-DD", "plan the selected task for a day"),
+    spec("delete", "delete", "delete the selected Today task (asks first)"),
+    spec("detail", "detail", "toggle the detail pane"),
+    spec("est", "est [30m|+15m|-5]", "set or adjust the estimate (no arg: editor)"),
+    spec(
+        "export",
+        "export ics [path] [plan|actual]",
+        "write the plan and sessions to an .ics file",
+    ),
+    spec("filter", "filter #tag|off", "show only tasks with the tag"),
+    spec("finish", "finish", "finish the selected Today task"),
+    spec("interrupt", "interrupt [title]", "new interrupt task (no title: input popup)"),
+    spec("mode", "mode [list|calendar]", "switch the display (no arg: toggle)"),
+    spec("move", "move [N] top|bottom|up|down|POS", "reorder the selected task (or task N)"),
+    spec("next", "next", "next search hit"),
+    spec("note", "note", "edit the note of the selected task"),
+    spec("postpone", "postpone [Nd]", "move to Future, N days ahead (default 1)"),
+    spec("prev", "prev", "previous search hit"),
+    spec("quit", "quit", "quit (leaves Calendar first)"),
+    spec("redo", "redo", "redo the last undone change"),
+    spec("rename", "rename [title]", "rename the selected task (no arg: editor)"),
+    spec("report", "report [date] [path]", "write the day's Markdown report (plan vs actual)"),
+    spec("routine", "routine daily|weekdays|mon,wed|every N|monthly D|off", "repeat the task"),
+    spec("search", "search [query]", "filter by text (no arg: prompt)"),
+    spec("select", "select N|up|down", "select a task in the current list"),
+    spec("sessions", "sessions", "edit the work sessions of the selected task"),
+    spec("sort", "sort by start|estimate|title|category|state", "reorder the current list"),
+    spec("start", "start", "start, resume or pause the selected Today task"),
+    spec("tag", "tag #a +b -c|clear", "add/remove tags on the selected task"),
+    spec("undo", "undo", "undo the last change"),
+    spec("view", "view past|today|future|next|prev", "switch the view"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteCommand {
+    /// Same as pressing the key bound to the action.
+    Action(Action),
+    Estimate(EstimateArg),
+    At(Option<u16>),
+    Base {
+        hour: u16,
+        minute: u16,
+    },
+    /// `None` detaches the task from its routine.
+    Routine(Option<Recurrence>),
+    Mode(DisplayMode),
+    Tag(Vec<TagEdit>),
+    Filter(Option<String>),
+    Rename(String),
+    Add {
+        interrupt: bool,
+        title: String,
+    },
+    Postpone {
+        days: u32,
+    },
+    /// `task` is a 0-based index; `None` means the selected task.
+    Move {
+        task: Option<usize>,
+        to: MoveTo,
+    },
+    Category(String),
+    Date(u32),
+    Sort(SortKey),
+    View(View),
+    Select(usize),
+    Search(String),
+    /// `path` defaults to `chute.ics` next to the snapshot.
+    Export {
+        part: crate::ics::Part,
+        path: Option<String>,
+    },
+    /// `ymd` defaults to today, `path` to `reports/YYYY-MM-DD.md` next to the snapshot.
+    Report {
+        ymd: Option<u32>,
+        path: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagEdit {
+    Add(String),
+    Remove(String),
+    Clear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveTo {
+    Top,
+    Bottom,
+    Up,
+    Down,
+    /// 0-based position.
+    Position(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Fixed start (`at`) time; tasks without one keep their order after the rest.
+    Start,
+    Estimate,
+    Title,
+    /// Config order of the categories.
+    Category,
+    /// Active, paused, planned, done.
+    State,
+}
+
+const SORT_KEYS: &[&str] = &["start", "estimate", "title", "category", "state"];
+const MOVE_TARGETS: &[&str] = &["top", "bottom", "up", "down"];
+
+fn usage(name: &str) -> &'static str {
+    COMMANDS.iter().find(|c| c.name == name).map_or("", |c| c.usage)
+}
+
+fn no_args(name: &str, args: &[&str], cmd: PaletteCommand) -> Result<PaletteCommand> {
+    if !args.is_empty() {
+        bail!("{name} takes no arguments");
+    }
+    Ok(cmd)
+}
+
+// 1-based number -> 0-based index
+fn task_number(s: &str) -> Option<usize> {
+    s.parse::<usize>().ok().filter(|&n| n > 0).map(|n| n - 1)
+}
+
+fn move_target(s: &str) -> Option<MoveTo> {
+    match s {
+        "top" => Some(MoveTo::Top),
+        "bottom" => Some(MoveTo::Bottom),
+        "up" => Some(MoveTo::Up),
+        "down" => Some(MoveTo::Down),
+        _ => task_number(s).map(MoveTo::Position),
+    }
+}
+
+fn sort_key(s: &str) -> Option<SortKey> {
+    match s {
+        "start" | "at" | "time" => Some(SortKey::Start),
+        "estimate" | "est" => Some(SortKey::Estimate),
+        "title" | "name" => Some(SortKey::Title),
+        "category" | "cat" => Some(SortKey::Category),
+        "state" | "status" => Some(SortKey::State),
+        _ => None,
+    }
+}
+
+/// Parse one palette line.
+pub fn parse(input: &str) -> Result<PaletteCommand> {
+    let input = input.trim();
+    let Some(name) = input.split_whitespace().next() else {
+        bail!("type a command (Tab lists them)");
+    };
+    let rest = input[name.len()..].trim();
+    let args: Vec<&str> = rest.split_whitespace().collect();
+    let one = |what: &str| -> Result<&str> {
+        match args.as_slice() {
+            [a] => Ok(*a),
+            [] => bail!("usage: {}", usage(name)),
+            _ => bail!("{name}: expected one {what}"),
+        }
+    };
+    use PaletteCommand as C;
+    Ok(match name {
+        "add" | "interrupt" => {
+            let interrupt = name == "interrupt";
+            if rest.is_empty() {
+                C::Action(if interrupt { Action::AddInterrupt } else { Action::AddTask })
+            } else {
+                C::Add { interrupt, title: rest.to_string() }
+            }
+        }
+        "at" => match args.as_slice() {
+            [] => C::Action(Action::OpenPopup),
+            ["clear"] => C::At(None),
+            [a] => match crate::task::parse_at_arg(a) {
+                Ok(min) => C::At(min),
+                Err(_) => bail!("at: expected HH:MM, HHMM or -, got `{a}`"),
+            },
+            _ => bail!("usage: {}", usage(name)),
+        },
+        "base" => {
+            let a = one("time")?;
+            let Ok((hour, minute)) = crate::config::parse_hhmm_or_compact(a) else {
+                bail!("base: expected HH:MM or HHMM, got `{a}`");
+            };
+            C::Base { hour, minute }
+        }
+        "bring" => no_args(name, &args, C::Action(Action::BringToToday))?,
+        "cat" => match args.as_slice() {
+            [] => C::Action(Action::CategoryPicker),
+            ["next"] => C::Action(Action::CategoryCycle),
+            [a] => C::Category(a.to_string()),
+            _ => bail!("usage: {}", usage(name)),
+        },
+        "date" => {
+            let a = one("date")?;
+            let Some(ymd) = crate::date::parse_date_arg(a) else {
+                bail!("date: expected today, tomorrow or YYYY-MM-DD, got `{a}`");
+            };
+            C::Date(ymd)
+        }
+        "delete" => no_args(name, &args, C::Action(Action::Delete))?,
+        "detail" => no_args(name, &args, C::Action(Action::ToggleDetail))?,
+        "est" => match args.as_slice() {
+            [] => C::Action(Action::EstimatePlus),
+            [a] => match EstimateArg::parse(a) {
+                Some(est) => C::Estimate(est),
+                None => bail!("est: expected minutes like 30m, +15m or -5, got `{a}`"),
+            },
+            _ => bail!("usage: {}", usage(name)),
+        },
+        "export" => {
+            let Some((&"ics", rest)) = args.split_first() else {
+                bail!("usage: {}", usage(name));
+            };
+            let (mut part, mut path) = (crate::ics::Part::All, None);
+            for a in rest {
+                match crate::ics::Part::parse(a) {
+                    Some(p) => part = p,
+                    None if path.is_none() => path = Some(a.to_string()),
+                    None => bail!("usage: {}", usage(name)),
+                }
+            }
+            C::Export { part, path }
+        }
+        "report" => {
+            let (mut ymd, mut path) = (None, None);
+            for a in &args {
+                match crate::date::parse_date_arg(a) {
+                    Some(d) if ymd.is_none() => ymd = Some(d),
+                    None if path.is_none() => path = Some(a.to_string()),
+                    _ => bail!("usage: {}", usage(name)),
+                }
+            }
+            C::Report { ymd, path }
+        }
+        "filter" => match args.as_slice() {
+            [] | ["off" | "-" | "none"] => C::Filter(None),
+            [a] => match crate::tags::normalize_tag(a) {
+                Some(tag) => C::Filter(Some(tag)),
+                None => bail!("filter: invalid tag `{a}`"),
+            },
+            _ => bail!("filter: one tag at a time"),
+        },
+        "finish" => no_args(name, &args, C::Action(Action::FinishActive))?,
+        "mode" => match args.as_slice() {
+            [] => C::Action(Action::ToggleBlocks),
+            ["list" | "table"] => C::Mode(DisplayMode::List),
+            // Backward-compatible aliases map to Calendar
+            ["calendar" | "blocks" | "timeline"] => C::Mode(DisplayMode::Calendar),
+            _ => bail!("usage: {}", usage(name)),
+        },
+        "move" => {
+            let (task, target) = match args.as_slice() {
+                [to] => (None, *to),
+                [n, to] => match task_number(n) {
+                    Some(i) => (Some(i), *to),
+                    None => bail!("move: `{n}` is not a task number"),
+                },
+                _ => bail!("usage: {}", usage(name)),
+            };
+            let Some(to) = move_target(target) else {
+                bail!("move: expected top, bottom, up, down or a position, got `{target}`");
+            };
+            C::Move { task, to }
+        }
+        "next" => no_args(name, &args, C::Action(Action::SearchNext))?,
+        "note" => no_args(name, &args, C::Action(Action::EditNote))?,
+        "postpone" => match args.as_slice() {
+            [] => C::Postpone { days: 1 },
+            [a] => match a.trim_end_matches('d').parse::<u32>() {
+                Ok(days) if days > 0 => C::Postpone { days },
+                _ => bail!("postpone: expected a number of days like 3d, got `{a}`"),
+            },
+            _ => bail!("usage: {}", usage(name)),
+        },
+        "prev" => no_args(name, &args, C::Action(Action::SearchPrev))?,
+        "quit" => no_args(name, &args, C::Action(Action::Quit))?,
+        "redo" => no_args(name, &args, C::Action(Action::Redo))?,
+        "rename" => {
+            if rest.is_empty() {
+                C::Action(Action::Rename)
+            } else {
+                C::Rename(rest.to_string())
+            }
+        }
+        "routine" => match rest {
+            "" => bail!("usage: {}", usage(name)),
+            "off" | "none" | "-" => C::Routine(None),
+            _ => match Recurrence::parse(rest) {
+                Some(rec) => C::Routine(Some(rec)),
+                None => bail!("routine: unknown rule `{rest}`"),
+            },
+        },
+        "search" => {
+            if rest.is_empty() {
+                C::Action(Action::Search)
+            } else {
+                C::Search(rest.to_string())
+            }
+        }
+        "select" => match one("task")? {
+            "up" => C::Action(Action::SelectUp),
+            "down" => C::Action(Action::SelectDown),
+            a => match task_number(a) {
+                Some(i) => C::Select(i),
+                None => bail!("select: expected a task number, up or down, got `{a}`"),
+            },
+        },
+        "sessions" => no_args(name, &args, C::Action(Action::SessionEditor))?,
+        "sort" => {
+            let key = match args.as_slice() {
+                ["by", k] | [k] => *k,
+                _ => bail!("usage: {}", usage(name)),
+            };
+            match sort_key(key) {
+                Some(k) => C::Sort(k),
+                None => bail!("sort: unknown key `{key}` ({})", SORT_KEYS.join(", ")),
+            }
+        }
+        "start" => no_args(name, &args, C::Action(Action::StartOrResume))?,
+        "tag" => {
+            if args.is_empty() {
+                bail!("usage: {}", usage(name));
+            }
+            let mut edits = Vec::new();
+            for arg in &args {
+                let edit = if matches!(*arg, "-" | "clear" | "none") {
+                    Some(TagEdit::Clear)
+                } else if let Some(t) = arg.strip_prefix('-') {
+                    crate::tags::normalize_tag(t).map(TagEdit::Remove)
+                } else {
+                    crate::tags::normalize_tag(arg.strip_prefix('+').unwrap_or(arg))
+                        .map(TagEdit::Add)
+                };
+                match edit {
+                    Some(e) => edits.push(e),
+                    None => bail!("tag: invalid tag `{arg}`"),
+                }
+            }
+            C::Tag(edits)
+        }
+        "undo" => no_args(name, &args, C::Action(Action::Undo))?,
+        "view" => match one("view")? {
+            "past" => C::View(View::Past),
+            "today" => C::View(View::Today),
+            "future" => C::View(View::Future),
+            "next" => C::Action(Action::ViewNext),
+            "prev" => C::Action(Action::ViewPrev),
+            a => bail!("view: expected past, today, future, next or prev, got `{a}`"),
+        },
+        _ => bail!("unknown command `{name}` (Tab lists commands)"),
+    })
+}
+
+/// Values offered for the command's arguments beyond the static lists.
+#[derive(Debug, Clone, Default)]
+pub struct Sources {
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl Sources {
+    /// Selectable category ids from `cfg` and every tag in `tasks`, sorted.
+    pub fn collect<'a>(cfg: &Config, tasks: impl IntoIterator<Item = &'a Task>) -> Self {
+        let categories = cfg.categories.selectable().map(|e| e.id.id().to_string()).collect();
+        let mut tags: Vec<String> =
+            tasks.into_iter().flat_map(|t| t.tags.iter().cloned()).collect();
+        tags.sort();
+        tags.dedup();
+        Sources { categories, tags }
+    }
+}
+
+fn strs(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+// Candidates for the argument at `pos` of `name`, given the arguments before it
+fn arg_candidates(name: &str, before: &[&str], word: &str, src: &Sources) -> Vec<String> {
+    let pos = before.len();
+    match (name, pos) {
+        ("at", 0) => strs(&["-"]),
+        ("cat", 0) => {
+            let mut v = src.categories.clone();
+            v.push("next".into());
+            v
+        }
+        ("date", 0) => strs(&["today", "tomorrow"]),
+        ("export", 0) => strs(&["ics"]),
+        ("export", 1 | 2) => strs(&["plan", "actual"]),
+        ("report", 0) => strs(&["today"]),
+        ("filter", 0) => {
+            let mut v: Vec<String> = src.tags.iter().map(|t| format!("#{t}")).collect();
+            v.push("off".into());
+            v
+        }
+        ("mode", 0) => strs(&["list", "calendar"]),
+        ("move", 0) => strs(MOVE_TARGETS),
+        ("move", 1) if task_number(before[0]).is_some() => strs(MOVE_TARGETS),
+        ("routine", 0) => strs(&["daily", "weekdays", "weekly", "every", "monthly", "off"]),
+        ("select", 0) => strs(&["up", "down"]),
+        ("sort", 0) => {
+            std::iter::once("by").chain(SORT_KEYS.iter().copied()).map(String::from).collect()
+        }
+        ("sort", 1) if before[0] == "by" => strs(SORT_KEYS),
+        ("tag", _) => match word.chars().next() {
+            Some(sigil @ ('+' | '-' | '#')) => {
+                src.tags.iter().map(|t| format!("{sigil}{t}")).collect()
+            }
+            _ => {
+                let mut v = src.tags.clone();
+                v.push("clear".into());
+                v
+            }
+        },
+        ("view", 0) => strs(&["past", "today", "future", "next", "prev"]),
+        _ => Vec::new(),
+    }
+}
+
+/// Result of Tab: the new input and, when still ambiguous, the remaining choices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub text: String,
+    pub candidates: Vec<String>,
+}
+
+/// Complete the last word of `input`. A single match is inserted with a trailing space;
+/// several matches extend the word to their common prefix and are listed.
+pub fn complete(input: &str, src: &Sources) -> Completion {
+    let start = input
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let (head, word) = input.split_at(start);
+    let before: Vec<&str> = head.split_whitespace().collect();
+    let pool: Vec<String> = match before.split_first() {
+        None => COMMANDS.iter().map(|c| c.name.to_string()).collect(),
+        Some((name, args)) => arg_candidates(name, args, word, src),
+    };
+    let lower = word.to_lowercase();
+    let matches: Vec<String> =
+        pool.into_iter().filter(|c| c.to_lowercase().starts_with(&lower)).collect();
+    match matches.as_slice() {
+        [] => Completion { text: input.to_string(), candidates: Vec::new() },
+        [only] => Completion { text: format!("{head}{only} "), candidates: Vec::new() },
+        _ => {
+            let prefix = common_prefix(&matches);
+            let word = if prefix.chars().count() > word.chars().count() { prefix } else { word };
+            Completion { text: format!("{head}{word}"), candidates: matches }
+        }
+    }
+}
+
+fn common_prefix(words: &[String]) -> &str {
+    let first = &words[0];
+    let mut end = first.len();
+    for w in &words[1..] {
+        end = first
+            .char_indices()
+            .zip(w.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(end.min(w.len()), |((i, _), _)| i.min(end));
+    }
+    &first[..end]
+}
+
+/// `usage — help` for the command being typed, or the matching command names while
+/// the first word is still a prefix of several.
+pub fn help_for(input: &str) -> Option<String> {
+    let name = input.split_whitespace().next()?;
+    if let Some(c) = COMMANDS.iter().find(|c| c.name == name) {
+        return Some(format!("{} — {}", c.usage, c.help));
+    }
+    let names: Vec<&str> =
+        COMMANDS.iter().map(|c| c.name).filter(|n| n.starts_with(name)).collect();
+    match names.as_slice() {
+        [] => None,
+        [one] => help_for(one),
+        _ => Some(names.join(" ")),
+    }
+}
+
+/// Order of `tasks` sorted stably by `key`, as indices into `tasks`.
+pub fn sorted_order(tasks: &[Task], key: SortKey, cfg: &Config) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..tasks.len()).collect();
+    let state_rank = |s: TaskState| match s {
+        TaskState::Active => 0,
+        TaskState::Paused => 1,
+        TaskState::Planned => 2,
+        TaskState::Done => 3,
+    };
+    match key {
+        SortKey::Start => {
+            order.sort_by_key(|&i| tasks[i].fixed_start_min.map_or((1, 0), |m| (0, m)))
+        }
+        SortKey::Estimate => order.sort_by_key(|&i| tasks[i].estimate_min),
+        SortKey::Title => order.sort_by_key(|&i| tasks[i].title.to_lowercase()),
+        SortKey::Category => order.sort_by_key(|&i| {
+            cfg.categories
+                .entries
+                .iter()
+                .position(|e| e.id == tasks[i].category)
+                .unwrap_or(usize::MAX)
+        }),
+        SortKey::State => order.sort_by_key(|&i| state_rank(tasks[i].state)),
+    }
+    order
+}
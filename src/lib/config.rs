@@ -2,6 +2,11 @@
 //! - Supports day start time (HH:MM) and key bindings.
 //! - Defaults: day start 09:00 and built-in keymap compatible with current tests.
 
+use crate::ics::IcsConfig;
+use crate::report::ReportConfig;
+use crate::routine::EstimatePolicy;
+use crate::task::Category;
+use crate::todoist::TodoistConfig;
 use anyhow::{anyhow, Context, Result};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::Color;
@@ -16,6 +21,16 @@ pub struct Config {
     pub categories: CategoryTheme,
     /// Optional snapshot save path. When set, this takes precedence over CLI flags and env.
     pub state_path: Option<PathBuf>,
+    /// Default policy for learning routine estimates from actuals.
+    pub routine_estimate: EstimatePolicy,
+    /// Active profile name (`--profile`/`CHUTE_KUN_PROFILE`); selects a separate snapshot.
+    pub profile: Option<String>,
+    /// `[todoist]` import settings (the token comes from the environment).
+    pub todoist: TodoistConfig,
+    /// `[ics]` calendar files whose appointments are imported into Today.
+    pub ics: IcsConfig,
+    /// `[report]` settings for the daily Markdown report.
+    pub report: ReportConfig,
 }
 
 impl Default for Config {
@@ -25,56 +40,92 @@ impl Default for Config {
             keys: KeyMap::default(),
             categories: CategoryTheme::default(),
             state_path: None,
+            routine_estimate: EstimatePolicy::default(),
+            profile: None,
+            todoist: TodoistConfig::default(),
+            ics: IcsConfig::default(),
+            report: ReportConfig::default(),
         }
     }
 }
 
 // ---- Category theme (names + colors) ----
 
+/// Categories in config order: the four built-ins first, then `[categories.<id>]` tables
+/// that do not override a built-in, in the order they are declared.
 #[derive(Debug, Clone)]
 pub struct CategoryTheme {
-    pub general: CategoryStyle,
-    pub work: CategoryStyle,
-    pub home: CategoryStyle,
-    pub hobby: CategoryStyle,
+    pub entries: Vec<CategoryStyle>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CategoryStyle {
+    pub id: Category,
     pub name: String,
     pub color: Color,
+    /// Hidden categories keep their name/color for existing tasks but are not offered
+    /// by the picker or the cycle action.
+    pub hidden: bool,
 }
 
+/// Color for category ids that are not (or no longer) declared in config.
+pub const UNKNOWN_CATEGORY_COLOR: Color = Color::Gray;
+
 impl Default for CategoryTheme {
     fn default() -> Self {
+        let style = |id: Category, color: Color| CategoryStyle {
+            name: id.id().to_string(),
+            id,
+            color,
+            hidden: false,
+        };
         CategoryTheme {
-            general: CategoryStyle { name: "General".into(), color: Color::White },
-            work: CategoryStyle { name: "Work".into(), color: Color::Blue },
-            home: CategoryStyle { name: "Home".into(), color: Color::Yellow },
-            hobby: CategoryStyle { name: "Hobby".into(), color: Color::Magenta },
+            entries: vec![
+                style(Category::GENERAL, Color::White),
+                style(Category::WORK, Color::Blue),
+                style(Category::HOME, Color::Yellow),
+                style(Category::HOBBY, Color::Magenta),
+            ],
         }
     }
 }
 
-impl Config {
-    pub fn category_color(&self, cat: crate::task::Category) -> Color {
-        match cat {
-            crate::task::Category::General => self.categories.general.color,
-            crate::task::Category::Work => self.categories.work.color,
-            crate::task::Category::Home => self.categories.home.color,
-            crate::task::Category::Hobby => self.categories.hobby.color,
-        }
+impl CategoryTheme {
+    pub fn get(&self, cat: &Category) -> Option<&CategoryStyle> {
+        self.entries.iter().find(|e| e.id == *cat)
+    }
+
+    /// Categories offered by the picker and the cycle action.
+    pub fn selectable(&self) -> impl Iterator<Item = &CategoryStyle> {
+        self.entries.iter().filter(|e| !e.hidden)
+    }
+
+    /// Position of `cat` among `selectable()`.
+    pub fn position(&self, cat: &Category) -> Option<usize> {
+        self.selectable().position(|e| e.id == *cat)
     }
-    pub fn category_name(&self, cat: crate::task::Category) -> String {
-        match cat {
-            crate::task::Category::General => self.categories.general.name.clone(),
-            crate::task::Category::Work => self.categories.work.name.clone(),
-            crate::task::Category::Home => self.categories.home.name.clone(),
-            crate::task::Category::Hobby => self.categories.hobby.name.clone(),
+
+    /// Next selectable category after `cat`, wrapping around. Unknown or hidden
+    /// categories continue with the first one.
+    pub fn next_after(&self, cat: &Category) -> Category {
+        let list: Vec<&CategoryStyle> = self.selectable().collect();
+        match self.position(cat) {
+            Some(i) if !list.is_empty() => list[(i + 1) % list.len()].id.clone(),
+            _ => list.first().map(|e| e.id.clone()).unwrap_or_default(),
         }
     }
 }
 
+impl Config {
+    pub fn category_color(&self, cat: &Category) -> Color {
+        self.categories.get(cat).map(|e| e.color).unwrap_or(UNKNOWN_CATEGORY_COLOR)
+    }
+    /// Display name; ids missing from config show as-is.
+    pub fn category_name(&self, cat: &Category) -> String {
+        self.categories.get(cat).map(|e| e.name.clone()).unwrap_or_else(|| cat.id().to_string())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyMap {
     pub quit: Vec<KeySpec>,
@@ -96,6 +147,16 @@ pub struct KeyMap {
     pub toggle_blocks: Vec<KeySpec>,
     pub category_cycle: Vec<KeySpec>,
     pub category_picker: Vec<KeySpec>,
+    pub session_editor: Vec<KeySpec>,
+    pub detail: Vec<KeySpec>,
+    pub edit_note: Vec<KeySpec>,
+    pub rename: Vec<KeySpec>,
+    pub search: Vec<KeySpec>,
+    /// Jump between search hits. While a search is active these win over other bindings.
+    pub search_next: Vec<KeySpec>,
+    pub search_prev: Vec<KeySpec>,
+    pub undo: Vec<KeySpec>,
+    pub redo: Vec<KeySpec>,
 }
 
 impl Default for KeyMap {
@@ -122,6 +183,15 @@ impl Default for KeyMap {
             toggle_blocks: vec![k("t")],
             category_cycle: vec![k("c")],
             category_picker: vec![k("Shift+c")],
+            session_editor: vec![k("s")],
+            detail: vec![k("d")],
+            edit_note: vec![k("n")],
+            rename: vec![k("r")],
+            search: vec![k("/")],
+            search_next: vec![k("n")],
+            search_prev: vec![k("N")],
+            undo: vec![k("u"), k("Ctrl+z")],
+            redo: vec![k("Ctrl+r"), k("Ctrl+y")],
         }
     }
 }
@@ -147,6 +217,15 @@ pub enum Action {
     ToggleBlocks,
     CategoryCycle,
     CategoryPicker,
+    SessionEditor,
+    ToggleDetail,
+    EditNote,
+    Rename,
+    Search,
+    SearchNext,
+    SearchPrev,
+    Undo,
+    Redo,
 }
 
 impl KeyMap {
@@ -190,6 +269,24 @@ impl KeyMap {
             Some(Action::CategoryCycle)
         } else if matches(&self.category_picker) {
             Some(Action::CategoryPicker)
+        } else if matches(&self.session_editor) {
+            Some(Action::SessionEditor)
+        } else if matches(&self.detail) {
+            Some(Action::ToggleDetail)
+        } else if matches(&self.edit_note) {
+            Some(Action::EditNote)
+        } else if matches(&self.rename) {
+            Some(Action::Rename)
+        } else if matches(&self.search) {
+            Some(Action::Search)
+        } else if matches(&self.search_next) {
+            Some(Action::SearchNext)
+        } else if matches(&self.search_prev) {
+            Some(Action::SearchPrev)
+        } else if matches(&self.undo) {
+            Some(Action::Undo)
+        } else if matches(&self.redo) {
+            Some(Action::Redo)
         } else {
             None
         }
@@ -336,10 +433,38 @@ struct RawConfig {
     day_start: Option<String>,
     #[serde(default)]
     keys: Option<RawKeys>,
+    /// Kept as a table so declaration order survives (`toml/preserve_order`).
     #[serde(default)]
-    categories: Option<RawCategories>,
+    categories: Option<toml::Table>,
     #[serde(default)]
     state_path: Option<String>,
+    #[serde(default)]
+    routine_estimate: Option<String>,
+    #[serde(default)]
+    todoist: Option<RawTodoist>,
+    #[serde(default)]
+    ics: Option<RawIcs>,
+    #[serde(default)]
+    report: Option<RawReport>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawIcs {
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawReport {
+    #[serde(default)]
+    template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RawTodoist {
+    base_url: Option<String>,
+    filter: Option<String>,
+    comment_on_finish: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -363,18 +488,15 @@ struct RawKeys {
     toggle_blocks: Option<OneOrMany>,
     category_cycle: Option<OneOrMany>,
     category_picker: Option<OneOrMany>,
-}
-
-#[derive(Debug, Deserialize, Default)]
-struct RawCategories {
-    #[serde(default)]
-    general: Option<RawCategoryStyle>,
-    #[serde(default)]
-    work: Option<RawCategoryStyle>,
-    #[serde(default)]
-    home: Option<RawCategoryStyle>,
-    #[serde(default)]
-    hobby: Option<RawCategoryStyle>,
+    session_editor: Option<OneOrMany>,
+    detail: Option<OneOrMany>,
+    edit_note: Option<OneOrMany>,
+    rename: Option<OneOrMany>,
+    search: Option<OneOrMany>,
+    search_next: Option<OneOrMany>,
+    search_prev: Option<OneOrMany>,
+    undo: Option<OneOrMany>,
+    redo: Option<OneOrMany>,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -383,6 +505,8 @@ struct RawCategoryStyle {
     name: Option<String>,
     #[serde(default)]
     color: Option<String>,
+    #[serde(default)]
+    hidden: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -439,9 +563,51 @@ fn parse_color(s: &str) -> Result<Color> {
     Err(anyhow!("unknown color: {}", s))
 }
 
+/// Overlay `top` onto `base`: nested tables merge key by key, anything else replaces.
+fn merge_tables(base: &mut toml::Table, top: toml::Table) {
+    for (k, v) in top {
+        match (base.get_mut(&k), v) {
+            (Some(toml::Value::Table(dst)), toml::Value::Table(src)) => merge_tables(dst, src),
+            (_, v) => {
+                base.insert(k, v);
+            }
+        }
+    }
+}
+
 impl Config {
     pub fn from_toml_str(s: &str) -> Result<Self> {
         let raw: RawConfig = toml::from_str(s).context("parse config toml")?;
+        Self::from_raw(raw)
+    }
+
+    /// Parse a base config with a profile overlay on top. The profile inherits every
+    /// unspecified value (single keys and category fields included) except `state_path`,
+    /// so two profiles never share a snapshot by accident.
+    pub fn from_toml_layers(base: &str, profile: &str) -> Result<Self> {
+        let mut merged: toml::Table = toml::from_str(base).context("parse config toml")?;
+        let overlay: toml::Table = toml::from_str(profile).context("parse profile toml")?;
+        merged.remove("state_path");
+        merge_tables(&mut merged, overlay);
+        let raw: RawConfig =
+            toml::Value::Table(merged).try_into().context("parse merged profile config")?;
+        Self::from_raw(raw)
+    }
+
+    /// Like `from_toml_layers`, but a profile overlay that does not parse is dropped: the
+    /// base (still without `state_path`) applies and the overlay's error is returned with it.
+    /// Fails only when the base itself is invalid.
+    pub fn from_toml_layers_or_base(
+        base: &str,
+        profile: &str,
+    ) -> Result<(Self, Option<anyhow::Error>)> {
+        match Self::from_toml_layers(base, profile) {
+            Ok(cfg) => Ok((cfg, None)),
+            Err(e) => Ok((Self::from_toml_layers(base, "")?, Some(e))),
+        }
+    }
+
+    fn from_raw(raw: RawConfig) -> Result<Self> {
         let mut cfg = Config::default();
         if let Some(ds) = raw.day_start {
             cfg.day_start_minutes = parse_hhmm_to_minutes(&ds)?;
@@ -513,24 +679,93 @@ impl Config {
             if let Some(v) = keys.category_picker {
                 apply(&mut km.category_picker, v)?;
             }
+            if let Some(v) = keys.session_editor {
+                apply(&mut km.session_editor, v)?;
+            }
+            if let Some(v) = keys.detail {
+                apply(&mut km.detail, v)?;
+            }
+            if let Some(v) = keys.edit_note {
+                apply(&mut km.edit_note, v)?;
+            }
+            if let Some(v) = keys.rename {
+                apply(&mut km.rename, v)?;
+            }
+            if let Some(v) = keys.search {
+                apply(&mut km.search, v)?;
+            }
+            if let Some(v) = keys.search_next {
+                apply(&mut km.search_next, v)?;
+            }
+            if let Some(v) = keys.search_prev {
+                apply(&mut km.search_prev, v)?;
+            }
+            if let Some(v) = keys.undo {
+                apply(&mut km.undo, v)?;
+            }
+            if let Some(v) = keys.redo {
+                apply(&mut km.redo, v)?;
+            }
             cfg.keys = km;
         }
         if let Some(cats) = raw.categories {
-            let apply = |dst: &mut CategoryStyle, ent: Option<RawCategoryStyle>| -> Result<()> {
-                if let Some(e) = ent {
-                    if let Some(n) = e.name {
-                        dst.name = n;
-                    }
-                    if let Some(c) = e.color {
-                        dst.color = parse_color(&c)?;
+            for (key, value) in cats {
+                let ent: RawCategoryStyle =
+                    value.try_into().with_context(|| format!("invalid [categories.{}]", key))?;
+                let id = Category::new(key.clone());
+                let idx = match cfg.categories.entries.iter().position(|e| e.id == id) {
+                    Some(i) => i,
+                    None => {
+                        cfg.categories.entries.push(CategoryStyle {
+                            id,
+                            name: key,
+                            color: Color::White,
+                            hidden: false,
+                        });
+                        cfg.categories.entries.len() - 1
                     }
+                };
+                let dst = &mut cfg.categories.entries[idx];
+                if let Some(n) = ent.name {
+                    dst.name = n;
                 }
-                Ok(())
-            };
-            apply(&mut cfg.categories.general, cats.general)?;
-            apply(&mut cfg.categories.work, cats.work)?;
-            apply(&mut cfg.categories.home, cats.home)?;
-            apply(&mut cfg.categories.hobby, cats.hobby)?;
+                if let Some(c) = ent.color {
+                    dst.color = parse_color(&c)?;
+                }
+                if let Some(h) = ent.hidden {
+                    dst.hidden = h;
+                }
+            }
+            if cfg.categories.selectable().next().is_none() {
+                return Err(anyhow!("at least one category must not be hidden"));
+            }
+        }
+        if let Some(p) = raw.routine_estimate {
+            cfg.routine_estimate = EstimatePolicy::parse(&p)
+                .ok_or_else(|| anyhow!("invalid routine_estimate: {}", p))?;
+        }
+        if let Some(td) = raw.todoist {
+            if let Some(url) = td.base_url {
+                cfg.todoist.base_url = url.trim_end_matches('/').to_string();
+            }
+            if let Some(f) = td.filter {
+                cfg.todoist.filter = f;
+            }
+            if let Some(c) = td.comment_on_finish {
+                cfg.todoist.comment_on_finish = c;
+            }
+        }
+        if let Some(ics) = raw.ics {
+            for p in ics.paths {
+                let path = expand_and_validate_state_path(&p)
+                    .ok_or_else(|| anyhow!("invalid ics path (must be absolute): {}", p))?;
+                cfg.ics.paths.push(path);
+            }
+        }
+        if let Some(t) = raw.report.and_then(|r| r.template) {
+            let path = expand_and_validate_state_path(&t)
+                .ok_or_else(|| anyhow!("invalid report template path (must be absolute): {}", t))?;
+            cfg.report.template = Some(path);
         }
         // Top-level state_path with ${VAR} and ~ expansion (safe rules)
         if let Some(sp) = raw.state_path {
@@ -545,13 +780,37 @@ impl Config {
     }
 
     pub fn load() -> Self {
+        let profile = active_profile();
         // In tests (integration/unit), avoid reading external user config for determinism.
         // Detect by env var set by Rust test harness.
         if std::env::var("RUST_TEST_THREADS").is_ok()
             || std::env::var("CHUTE_KUN_DISABLE_CONFIG").is_ok()
         {
-            return Config::default();
+            return Config { profile, ..Config::default() };
         }
+        let Some(name) = profile else {
+            return Self::load_base();
+        };
+        // A profile without its own file still gets its own snapshot.
+        let base = Self::read_base().unwrap_or_default();
+        let overlay =
+            profile_config_path(&name).and_then(|p| fs::read_to_string(p).ok()).unwrap_or_default();
+        let mut cfg = match Self::from_toml_layers_or_base(&base, &overlay) {
+            Ok((cfg, None)) => cfg,
+            Ok((cfg, Some(e))) => {
+                tracing::warn!("ignoring config for profile {name}: {e:#}");
+                cfg
+            }
+            Err(e) => {
+                tracing::warn!("ignoring config: {e:#}");
+                Config::default()
+            }
+        };
+        cfg.profile = Some(name);
+        cfg
+    }
+
+    fn load_base() -> Self {
         if let Ok(path) = std::env::var("CHUTE_KUN_CONFIG") {
             if let Ok(s) = fs::read_to_string(&path) {
                 if let Ok(cfg) = Self::from_toml_str(&s) {
@@ -572,6 +831,15 @@ impl Config {
         Config::default()
     }
 
+    fn read_base() -> Option<String> {
+        if let Ok(path) = std::env::var("CHUTE_KUN_CONFIG") {
+            if let Ok(s) = fs::read_to_string(path) {
+                return Some(s);
+            }
+        }
+        default_config_path().and_then(|p| fs::read_to_string(p).ok())
+    }
+
     /// Render a default TOML string users can customize.
     pub fn default_toml() -> String {
         // Keep keys aligned with KeyMap::default()
@@ -586,6 +854,28 @@ day_start = "09:00"
 # ${VAR} と ~ を展開します（例: "${XDG_DATA_HOME}/chute_kun/snapshot.toml"）。
 # state_path = "${XDG_DATA_HOME}/chute_kun/snapshot.toml"
 
+# ルーチンの見積を実績から更新する方法。
+# "fixed"（更新しない）/ "last"（前回実績）/ "mean:N" / "median:N" / "pXX:N"（例: "p80:10"）。
+# N は直近の実行回数（省略時 5）。既定は "mean:5"。
+routine_estimate = "mean:5"
+
+# Todoist からの取り込み（`chute import todoist`）と完了/翌日送りの書き戻し。API トークンは環境変数 TODOIST_API_TOKEN で渡します。
+# [todoist]
+# filter = "today | overdue"
+# base_url = "https://api.todoist.com/rest/v2"
+# 取り込んだタスクの完了時に実績コメント（tc-log 形式）を追記するか。
+# comment_on_finish = true
+
+# 予定（.ics ファイル）の取り込み。起動時と日付が変わったときに、今日の予定を開始時刻固定のタスクとして Today に追加します。
+# ~ と ${VAR} を展開します。`chute import ics <file>` でも取り込めます。
+# [ics]
+# paths = ["~/calendars/work.ics"]
+
+# 日報（`chute report day`、`:report`）の Markdown テンプレート。{{date}} {{tasks}} {{categories}} などを置き換えます。
+# 省略時は組み込みのテンプレートを使います。
+# [report]
+# template = "~/.config/chute_kun/report.md"
+
 [keys]
 # 既定のキーバインド。必要なものだけ上書きできます。
 quit = "q"
@@ -607,9 +897,23 @@ select_down = ["Down", "j"]
 toggle_blocks = "t"
 category_cycle = "c"
 category_picker = "Shift+c"
+session_editor = "s"
+detail = "d"
+edit_note = "n"
+rename = "r"
+# 検索中は search_next / search_prev が他の割り当てより優先されます
+search = "/"
+search_next = "n"
+search_prev = "Shift+n"
+undo = ["u", "Ctrl+z"]
+redo = ["Ctrl+r", "Ctrl+y"]
 
 [categories]
 # カテゴリ名と色（"white"/"blue"/"yellow"/"magenta"/"red"/"green"/"cyan"/"black"/"gray"/"darkgray" または "#RRGGBB"）
+# 新しいキーを足すとカテゴリが増えます（宣言順に並びます）。hidden = true で選択肢から外せます。
+# [categories.meetings]
+# name = "Meetings"
+# color = "cyan"
 [categories.general]
 name = "General"
 color = "white"
@@ -628,26 +932,89 @@ color = "magenta"
 "##.to_string()
     }
 
+    /// Render the starter file for a profile overlay.
+    pub fn profile_toml(name: &str) -> String {
+        format!(
+            r##"# Chute-kun profile "{name}"
+# config.toml の値を引き継ぎ、ここに書いた項目だけを上書きします。
+# state_path は引き継がず、未指定なら profiles/{name}/snapshot.toml（データディレクトリ内）を使います。
+
+# day_start = "09:00"
+# state_path = "~/work/chute/snapshot.toml"
+
+# [keys]
+# quit = "q"
+
+# [categories.work]
+# name = "Work"
+# color = "blue"
+"##
+        )
+    }
+
     /// Write a default config file to the resolved path.
     /// - If `CHUTE_KUN_CONFIG` is set, writes there; otherwise XDG default.
+    /// - With an active profile, writes the profile overlay (`profile_toml`) instead.
     /// - Creates parent directories when必要.
     /// - If file already exists, leaves it as-is and returns Ok(path).
     pub fn write_default_file() -> Result<std::path::PathBuf> {
-        let path = if let Ok(p) = std::env::var("CHUTE_KUN_CONFIG") {
-            std::path::PathBuf::from(p)
-        } else {
-            default_config_path().ok_or_else(|| anyhow!("could not resolve config path"))?
+        let (path, contents) = match active_profile() {
+            Some(name) => (
+                profile_config_path(&name)
+                    .ok_or_else(|| anyhow!("could not resolve config path"))?,
+                Self::profile_toml(&name),
+            ),
+            None => (
+                config_path().ok_or_else(|| anyhow!("could not resolve config path"))?,
+                Self::default_toml(),
+            ),
         };
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).ok();
         }
         if !path.exists() {
-            std::fs::write(&path, Self::default_toml()).context("write default config")?;
+            std::fs::write(&path, contents).context("write default config")?;
         }
         Ok(path)
     }
 }
 
+/// Base config file: `CHUTE_KUN_CONFIG` (set by `--config`) or `default_config_path()`.
+pub fn config_path() -> Option<PathBuf> {
+    match std::env::var_os("CHUTE_KUN_CONFIG") {
+        Some(p) => Some(PathBuf::from(p)),
+        None => default_config_path(),
+    }
+}
+
+/// Profile names double as file and directory names: ASCII letters, digits, `-` and `_`.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Profile selected by `CHUTE_KUN_PROFILE` (set by `--profile`). Invalid names are ignored.
+pub fn active_profile() -> Option<String> {
+    let name = std::env::var("CHUTE_KUN_PROFILE").ok()?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    if !is_valid_profile_name(name) {
+        tracing::warn!("ignoring invalid profile name: {name}");
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Overlay file of profile `name`: `profiles/<name>.toml` next to the base config.
+pub fn profile_config_path(name: &str) -> Option<PathBuf> {
+    let base = config_path()?;
+    let dir = base.parent().map(PathBuf::from).unwrap_or_default();
+    Some(dir.join("profiles").join(format!("{name}.toml")))
+}
+
 pub fn default_config_path() -> Option<PathBuf> {
     if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
         return Some(PathBuf::from(xdg).join("chute_kun").join("config.toml"));
@@ -712,7 +1079,8 @@ pub fn parse_hhmm_or_compact(s: &str) -> Result<(u16, u16)> {
     Err(anyhow!("invalid time format, expected HH:MM or HHMM"))
 }
 
-/// Ensure a config file exists (respecting `CHUTE_KUN_CONFIG`/default path),
+/// Ensure a config file exists (respecting `CHUTE_KUN_CONFIG`/default path, or the
+/// active profile's overlay),
 /// update its `day_start` to the provided hour/minute, and write it back.
 /// Returns the path written.
 pub fn write_day_start(h: u16, m: u16) -> Result<PathBuf> {
@@ -727,7 +1095,7 @@ pub fn write_day_start(h: u16, m: u16) -> Result<PathBuf> {
     Ok(path)
 }
 
-/// Expand and validate `state_path` safely for convenience:
+/// Expand and validate `state_path` (and `[ics] paths`) safely for convenience:
 ///   - Whitelist env vars: HOME, XDG_DATA_HOME, XDG_STATE_HOME, XDG_CONFIG_HOME
 ///   - Unknown ${VAR} ⇒ None (disable state_path instead of risky empty)
 ///   - Leading '~' expands to HOME if set
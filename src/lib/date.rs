@@ -9,7 +9,7 @@ use chrono::{Datelike, Local, NaiveDate};
 /// If `CHUTE_KUN_TODAY` is set, parse it instead (for tests).
 pub fn today_ymd() -> u32 {
     if let Ok(s) = std::env::var("CHUTE_KUN_TODAY") {
-        if let Some(v) = parse_ymd_override(&s) {
+        if let Some(v) = parse_ymd(&s) {
             return v;
         }
     }
@@ -17,7 +17,8 @@ pub fn today_ymd() -> u32 {
     ymd_to_u32(d.year(), d.month(), d.day())
 }
 
-fn parse_ymd_override(s: &str) -> Option<u32> {
+/// Parse `YYYY-MM-DD` or `YYYYMMDD` into `YYYYMMDD`; `None` if malformed or not a real date.
+pub fn parse_ymd(s: &str) -> Option<u32> {
     let s = s.trim();
     if s.len() == 8 && s.chars().all(|c| c.is_ascii_digit()) {
         let y: i32 = s[0..4].parse().ok()?;
@@ -46,14 +47,25 @@ fn ymd_to_u32(y: i32, m: u32, d: u32) -> u32 {
 /// Add `days` to a `YYYYMMDD` date, returning `YYYYMMDD`.
 /// Negative `days` subtracts. Panics if input is invalid.
 pub fn add_days_to_ymd(ymd: u32, days: i32) -> u32 {
-    let y = (ymd / 10000) as i32;
-    let m = ymd / 100 % 100;
-    let d = ymd % 100;
-    let date = NaiveDate::from_ymd_opt(y, m, d).expect("valid ymd");
-    let new = date
-        .checked_add_signed(chrono::Duration::days(days as i64))
-        .expect("date add within range");
-    ymd_to_u32(new.year(), new.month(), new.day())
+    checked_add_days_to_ymd(ymd, days).expect("valid ymd and date add within range")
+}
+
+/// Like `add_days_to_ymd`, but `None` for an invalid date or a result outside
+/// `0001-01-01..=9999-12-31` (user input such as `>99999d`).
+pub fn checked_add_days_to_ymd(ymd: u32, days: i32) -> Option<u32> {
+    let date = NaiveDate::from_ymd_opt((ymd / 10000) as i32, ymd / 100 % 100, ymd % 100)?;
+    let new = date.checked_add_signed(chrono::Duration::try_days(i64::from(days))?)?;
+    (1..=9999).contains(&new.year()).then(|| ymd_to_u32(new.year(), new.month(), new.day()))
+}
+
+/// `today`, `tomorrow` or a date (`YYYY-MM-DD`/`YYYYMMDD`) as `YYYYMMDD`, as accepted by
+/// the CLI and the command palette.
+pub fn parse_date_arg(s: &str) -> Option<u32> {
+    match s {
+        "today" => Some(today_ymd()),
+        "tomorrow" => Some(add_days_to_ymd(today_ymd(), 1)),
+        _ => parse_ymd(s),
+    }
 }
 
 /// Format `YYYYMMDD` to `YYYY-MM-DD`.
@@ -88,3 +100,25 @@ pub fn is_valid_ymd(ymd: u32) -> bool {
     let d = ymd % 100;
     NaiveDate::from_ymd_opt(y, m, d).is_some()
 }
+
+/// Weekday index for a `YYYYMMDD` date, Monday = 0 .. Sunday = 6.
+/// `None` if the date is invalid.
+pub fn weekday_index(ymd: u32) -> Option<u8> {
+    let date = NaiveDate::from_ymd_opt((ymd / 10000) as i32, ymd / 100 % 100, ymd % 100)?;
+    Some(date.weekday().num_days_from_monday() as u8)
+}
+
+/// Number of days in the month of a `YYYYMMDD` date.
+pub fn days_in_month(ymd: u32) -> u32 {
+    let first_of_month = ymd / 100 * 100 + 1;
+    let next_month = add_days_to_ymd(first_of_month, 31) / 100 * 100 + 1;
+    add_days_to_ymd(next_month, -1) % 100
+}
+
+/// Signed day difference `to - from` between two `YYYYMMDD` dates.
+/// `None` if either date is invalid.
+pub fn days_between(from: u32, to: u32) -> Option<i64> {
+    let parse =
+        |ymd: u32| NaiveDate::from_ymd_opt((ymd / 10000) as i32, ymd / 100 % 100, ymd % 100);
+    Some((parse(to)? - parse(from)?).num_days())
+}
@@ -0,0 +1,580 @@
+//! Headless subcommands (`chute add|list|start|...`) that edit the snapshot without the TUI.
+//! - Tasks are addressed by their 1-based position in Today (as printed by `list`) or by a
+//!   case-insensitive title match (an exact title wins over substring matches).
+//! - Failures carry an `ErrorKind` with a stable process exit code.
+//! - Every command returns both a human-readable line and a JSON value (`--json`).
+
+use crate::app::{App, View};
+use crate::config::Config;
+use crate::date::today_ymd;
+use crate::quick_add::QuickAdd;
+use crate::source::{Imported, TaskSource};
+use crate::task::{state_str, EstimateArg, Task, TaskState};
+use crate::todoist::TodoistSource;
+use serde_json::{json, Value};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Reading or writing the snapshot/config failed.
+    Io,
+    /// Unknown command or malformed arguments.
+    Usage,
+    /// No task matches the given index/title.
+    NotFound,
+    /// The title matches more than one task.
+    Ambiguous,
+    /// The task is in a state that does not allow the operation.
+    InvalidState,
+    /// Another chute (usually the TUI) holds the snapshot.
+    Locked,
+}
+
+impl ErrorKind {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Io => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::NotFound => 3,
+            ErrorKind::Ambiguous => 4,
+            ErrorKind::InvalidState => 5,
+            ErrorKind::Locked => 6,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io",
+            ErrorKind::Usage => "usage",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Ambiguous => "ambiguous",
+            ErrorKind::InvalidState => "invalid_state",
+            ErrorKind::Locked => "locked",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl CliError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into() }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "ok": false, "error": { "kind": self.kind.as_str(), "message": self.message } })
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<anyhow::Error> for CliError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(ErrorKind::Io, format!("{e:#}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListWhich {
+    Today,
+    Future,
+    Past,
+}
+
+impl ListWhich {
+    fn as_str(self) -> &'static str {
+        match self {
+            ListWhich::Today => "today",
+            ListWhich::Future => "future",
+            ListWhich::Past => "past",
+        }
+    }
+}
+
+/// How a command names a Today task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskRef {
+    /// 1-based position in Today.
+    Index(usize),
+    Title(String),
+}
+
+impl TaskRef {
+    /// All-digit arguments are positions; anything else is a title match.
+    pub fn parse(s: &str) -> Self {
+        match s.parse::<usize>() {
+            Ok(n) => TaskRef::Index(n),
+            Err(_) => TaskRef::Title(s.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Add a task; `ymd` later than today goes to Future.
+    Add {
+        title: String,
+        estimate_min: Option<u16>,
+        ymd: Option<u32>,
+    },
+    List(ListWhich),
+    Start(TaskRef),
+    Pause,
+    Finish(Option<TaskRef>),
+    Postpone(TaskRef),
+    Delete(TaskRef),
+    /// Move a task to a 1-based position.
+    Move {
+        task: TaskRef,
+        to: usize,
+    },
+    Estimate {
+        task: TaskRef,
+        value: EstimateArg,
+    },
+    /// Set (`Some`) or clear (`None`) the fixed start time in minutes.
+    At {
+        task: TaskRef,
+        minutes: Option<u16>,
+    },
+    /// Pull tasks from an external source into Today/Future.
+    Import(ImportFrom),
+    /// Write the plan or the time log to `output` (stdout when `None`).
+    Export {
+        format: ExportFormat,
+        output: Option<PathBuf>,
+    },
+    /// Write the Markdown report of a day (today when `ymd` is `None`) to `output`.
+    Report {
+        ymd: Option<u32>,
+        template: Option<PathBuf>,
+        output: Option<PathBuf>,
+    },
+}
+
+/// File format of `export`, with its options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Planned blocks and sessions as iCalendar.
+    Ics(crate::ics::Part),
+    /// Session or task rows as CSV.
+    Csv(crate::timelog::Query),
+    /// Session or task rows as a JSON array.
+    Json(crate::timelog::Query),
+}
+
+/// Where `import` pulls tasks from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportFrom {
+    /// Todoist tasks matching `[todoist] filter`.
+    Todoist,
+    /// Today's events of a calendar file, or of `[ics] paths` when `None`.
+    Ics(Option<PathBuf>),
+}
+
+impl Command {
+    /// Whether running the command changes the snapshot.
+    pub fn mutates(&self) -> bool {
+        !matches!(self, Command::List(_) | Command::Export { .. } | Command::Report { .. })
+    }
+}
+
+/// Result of a successful command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub text: String,
+    pub json: Value,
+}
+
+/// Index into Today for `r`.
+pub fn resolve(app: &App, r: &TaskRef) -> Result<usize, CliError> {
+    let tasks = &app.day.tasks;
+    match r {
+        TaskRef::Index(n) => {
+            if *n == 0 || *n > tasks.len() {
+                return Err(CliError::new(
+                    ErrorKind::NotFound,
+                    format!("no task #{n} (today has {} tasks)", tasks.len()),
+                ));
+            }
+            Ok(n - 1)
+        }
+        TaskRef::Title(q) => {
+            let needle = q.to_lowercase();
+            if let Some(i) = tasks.iter().position(|t| t.title.to_lowercase() == needle) {
+                return Ok(i);
+            }
+            let hits: Vec<usize> = tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| t.title.to_lowercase().contains(&needle))
+                .map(|(i, _)| i)
+                .collect();
+            match hits.as_slice() {
+                [] => Err(CliError::new(ErrorKind::NotFound, format!("no task matches '{q}'"))),
+                [i] => Ok(*i),
+                many => {
+                    let names: Vec<String> =
+                        many.iter().map(|i| format!("#{} {}", i + 1, tasks[*i].title)).collect();
+                    Err(CliError::new(
+                        ErrorKind::Ambiguous,
+                        format!("'{q}' matches several tasks: {}", names.join(", ")),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// One task as JSON: the snapshot fields plus its 1-based `index`.
+pub fn task_json(index: usize, task: &Task) -> Value {
+    let mut v = serde_json::to_value(task).unwrap_or(Value::Null);
+    if let Value::Object(map) = &mut v {
+        map.insert("index".to_string(), json!(index));
+    }
+    v
+}
+
+/// One task as a `list` line: `index. state est/act [@HH:MM] title [#tag...]`.
+pub fn task_line(index: usize, task: &Task) -> String {
+    let at =
+        task.fixed_start_min.map(|m| format!(" @{:02}:{:02}", m / 60, m % 60)).unwrap_or_default();
+    let tags: String = task.tags.iter().map(|t| format!(" #{t}")).collect();
+    format!(
+        "{:>2}. {:<7} {:>3}m/{:>3}m{} {}{}",
+        index,
+        state_str(task.state),
+        task.estimate_min,
+        task.actual_min,
+        at,
+        task.title,
+        tags
+    )
+}
+
+fn done(action: &str, index: usize, task: &Task) -> Output {
+    Output {
+        text: format!("{action}: {}", task_line(index, task)),
+        json: json!({ "ok": true, "action": action, "task": task_json(index, task) }),
+    }
+}
+
+/// `export --format csv|json`: the time log rows of `query` (see `timelog`).
+fn export_log(
+    app: &App,
+    format: &str,
+    query: &crate::timelog::Query,
+    output: Option<&Path>,
+) -> Result<Output, CliError> {
+    let today = today_ymd();
+    let (from, to) = query.range(today);
+    if from > to {
+        return Err(CliError::new(ErrorKind::Usage, "export: --from is after --to"));
+    }
+    let days = crate::timelog::collect(app, today, from, to)?;
+    let now = crate::clock::system_now_minutes();
+    let table = crate::timelog::table(app, &days, now, query);
+    let rows = table.rows.len();
+    let (text, content) = if format == "csv" {
+        let csv = table.to_csv();
+        (csv.clone(), Value::String(csv))
+    } else {
+        let v = table.to_json();
+        (serde_json::to_string_pretty(&v).unwrap_or_default() + "\n", v)
+    };
+    let Some(path) = output else {
+        return Ok(Output {
+            text: text.trim_end().to_string(),
+            json: json!({ "ok": true, "action": "export", "format": format, "rows": rows, "content": content }),
+        });
+    };
+    crate::storage::write_atomic(path, text.as_bytes())?;
+    Ok(Output {
+        text: format!("export {format}: {rows} rows to {}", path.display()),
+        json: json!({ "ok": true, "action": "export", "format": format, "rows": rows, "path": path }),
+    })
+}
+
+fn imported_output(source: &str, imported: &Imported) -> Output {
+    let mut lines = vec![format!(
+        "import {source}: {} added, {} skipped",
+        imported.added.len(),
+        imported.skipped
+    )];
+    let mut added = Vec::new();
+    for (view, t) in &imported.added {
+        let list = match view {
+            View::Future => format!("future {}", crate::date::format_ymd(t.planned_ymd)),
+            _ => "today".to_string(),
+        };
+        lines.push(format!("  + {} ({list}, {}m)", t.title, t.estimate_min));
+        let list = if *view == View::Future { "future" } else { "today" };
+        added.push(json!({ "list": list, "task": t }));
+    }
+    Output {
+        text: lines.join("\n"),
+        json: json!({
+            "ok": true,
+            "action": "import",
+            "source": source,
+            "added": added,
+            "skipped": imported.skipped,
+        }),
+    }
+}
+
+/// Apply `cmd` to `app`.
+pub fn run(app: &mut App, cmd: &Command) -> Result<Output, CliError> {
+    match cmd {
+        Command::Add { title, estimate_min, ymd } => {
+            let today = today_ymd();
+            // Same quick-add words as the TUI title input; `--estimate`/`--date` win
+            let mut spec = QuickAdd::parse(title, &app.config, today);
+            if spec.title.is_empty() {
+                return Err(CliError::new(ErrorKind::Usage, "add: missing title"));
+            }
+            spec.estimate_min = estimate_min.or(spec.estimate_min);
+            spec.planned_ymd = ymd.or(spec.planned_ymd);
+            let t = spec.into_task(crate::quick_add::DEFAULT_ESTIMATE_MIN, today);
+            match app.add_planned_task(t.clone()) {
+                (View::Future, idx) => Ok(Output {
+                    text: format!(
+                        "add (future {}): {}",
+                        crate::date::format_ymd(t.planned_ymd),
+                        t.title
+                    ),
+                    json: json!({
+                        "ok": true,
+                        "action": "add",
+                        "list": "future",
+                        "task": task_json(idx + 1, &t),
+                    }),
+                }),
+                (_, idx) => Ok(done("add", idx + 1, &app.day.tasks[idx])),
+            }
+        }
+        Command::List(which) => {
+            let tasks: Vec<Task> = match which {
+                ListWhich::Today => app.day.tasks.clone(),
+                ListWhich::Future => app.tomorrow_tasks().clone(),
+                ListWhich::Past => {
+                    app.ensure_history_loaded()?;
+                    app.history_tasks().clone()
+                }
+            };
+            let text = tasks
+                .iter()
+                .enumerate()
+                .map(|(i, t)| task_line(i + 1, t))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let items: Vec<Value> =
+                tasks.iter().enumerate().map(|(i, t)| task_json(i + 1, t)).collect();
+            Ok(Output { text, json: json!({ "ok": true, "list": which.as_str(), "tasks": items }) })
+        }
+        Command::Start(r) => {
+            let idx = resolve(app, r)?;
+            if app.day.tasks[idx].state == TaskState::Done {
+                return Err(CliError::new(
+                    ErrorKind::InvalidState,
+                    format!("'{}' is already done", app.day.tasks[idx].title),
+                ));
+            }
+            app.select_index(idx);
+            app.start_selected();
+            Ok(done("start", idx + 1, &app.day.tasks[idx]))
+        }
+        Command::Pause => {
+            let Some(idx) = app.day.active_index() else {
+                return Err(CliError::new(ErrorKind::InvalidState, "no task is running"));
+            };
+            app.pause_active();
+            Ok(done("pause", idx + 1, &app.day.tasks[idx]))
+        }
+        Command::Finish(r) => {
+            let idx = match r {
+                Some(r) => resolve(app, r)?,
+                None => app.day.active_index().ok_or_else(|| {
+                    CliError::new(ErrorKind::InvalidState, "no task is running; name one to finish")
+                })?,
+            };
+            if app.day.tasks[idx].state == TaskState::Done {
+                return Err(CliError::new(
+                    ErrorKind::InvalidState,
+                    format!("'{}' is already done", app.day.tasks[idx].title),
+                ));
+            }
+            app.select_index(idx);
+            app.finish_selected();
+            Ok(done("finish", idx + 1, &app.day.tasks[idx]))
+        }
+        Command::Postpone(r) => {
+            let idx = resolve(app, r)?;
+            let t = &app.day.tasks[idx];
+            let why = match t.state {
+                TaskState::Done => Some("is already done"),
+                TaskState::Active => Some("is running; pause it first"),
+                _ => None,
+            };
+            if let Some(why) = why {
+                return Err(CliError::new(ErrorKind::InvalidState, format!("'{}' {why}", t.title)));
+            }
+            app.select_index(idx);
+            let moved = app.postpone_selected().expect("a resolved task was postponed");
+            let (index, t) = (moved + 1, app.tomorrow_tasks()[moved].clone());
+            Ok(Output {
+                text: format!("postpone: {}", t.title),
+                json: json!({ "ok": true, "action": "postpone", "list": "future", "task": task_json(index, &t) }),
+            })
+        }
+        Command::Delete(r) => {
+            let idx = resolve(app, r)?;
+            let t = app.day.tasks[idx].clone();
+            app.select_index(idx);
+            app.delete_selected();
+            Ok(Output {
+                text: format!("delete: {}", t.title),
+                json: json!({ "ok": true, "action": "delete", "task": task_json(idx + 1, &t) }),
+            })
+        }
+        Command::Move { task, to } => {
+            let from = resolve(app, task)?;
+            let dest = (*to - 1).min(app.day.tasks.len() - 1);
+            // `move_index` takes an insertion slot in the list before removal
+            let slot = if dest > from { dest + 1 } else { dest };
+            let new = app.day.move_index(from, slot);
+            Ok(done("move", new + 1, &app.day.tasks[new]))
+        }
+        // Unlike postpone, estimate/at only edit the plan, so any state is accepted
+        Command::Estimate { task, value } => {
+            let idx = resolve(app, task)?;
+            app.set_task_estimate(idx, *value);
+            Ok(done("estimate", idx + 1, &app.day.tasks[idx]))
+        }
+        Command::At { task, minutes } => {
+            let idx = resolve(app, task)?;
+            app.set_task_fixed_start(idx, *minutes);
+            Ok(done("at", idx + 1, &app.day.tasks[idx]))
+        }
+        Command::Import(ImportFrom::Todoist) => {
+            let source = TodoistSource::from_env(&app.config.todoist)
+                .map_err(|e| CliError::new(ErrorKind::Usage, format!("import: {e}")))?;
+            let imported = crate::source::import_from(app, &source)?;
+            Ok(imported_output(source.name(), &imported))
+        }
+        Command::Import(ImportFrom::Ics(file)) => {
+            let imported = match file {
+                Some(path) => {
+                    let source = crate::ics::IcsSource { path: path.clone(), day: today_ymd() };
+                    crate::ics::import_file(app, &source)?
+                }
+                None if app.config.ics.paths.is_empty() => {
+                    return Err(CliError::new(
+                        ErrorKind::Usage,
+                        "import ics: give a file or set [ics] paths in the config",
+                    ))
+                }
+                None => crate::ics::import_configured(app)?,
+            };
+            Ok(imported_output("ics", &imported))
+        }
+        Command::Export { format: ExportFormat::Ics(part), output } => {
+            let today = today_ymd();
+            let past = crate::ics::recent_past(app, today)?;
+            let now = crate::clock::system_now_minutes();
+            let ics = crate::ics::export(app, &past, today, now, *part);
+            let Some(path) = output else {
+                return Ok(Output {
+                    text: ics.text.trim_end().to_string(),
+                    json: json!({ "ok": true, "action": "export", "format": "ics", "events": ics.events, "content": ics.text }),
+                });
+            };
+            crate::storage::write_atomic(path, ics.text.as_bytes())?;
+            Ok(Output {
+                text: format!("export ics: {} events to {}", ics.events, path.display()),
+                json: json!({ "ok": true, "action": "export", "format": "ics", "events": ics.events, "path": path }),
+            })
+        }
+        Command::Export { format: ExportFormat::Csv(query), output } => {
+            export_log(app, "csv", query, output.as_deref())
+        }
+        Command::Export { format: ExportFormat::Json(query), output } => {
+            export_log(app, "json", query, output.as_deref())
+        }
+        Command::Report { ymd, template, output } => {
+            let today = today_ymd();
+            let ymd = ymd.unwrap_or(today);
+            let template = crate::report::template(&app.config.report, template.as_deref())?;
+            let tasks = crate::report::tasks_for_day(app, today, ymd)?;
+            let text = crate::report::render(app, ymd, &tasks, &template);
+            let date = crate::date::format_ymd(ymd);
+            let Some(path) = output else {
+                return Ok(Output {
+                    text: text.trim_end().to_string(),
+                    json: json!({ "ok": true, "action": "report", "date": date, "content": text }),
+                });
+            };
+            crate::storage::write_atomic(path, text.as_bytes())?;
+            Ok(Output {
+                text: format!("report {date}: written to {}", path.display()),
+                json: json!({ "ok": true, "action": "report", "date": date, "path": path }),
+            })
+        }
+    }
+}
+
+/// Load the snapshot at `state_path`, run `cmd` and save it back when it changed anything.
+/// Changing commands are refused while another chute holds the snapshot (`StateLock`);
+/// read-only ones run against the last saved state.
+pub fn run_at_path(state_path: &Path, config: Config, cmd: &Command) -> Result<Output, CliError> {
+    let _lock = if cmd.mutates() {
+        let lock = crate::storage::StateLock::try_acquire(state_path)?;
+        Some(lock.ok_or_else(|| {
+            CliError::new(
+                ErrorKind::Locked,
+                format!("{} is open in another chute; close it and retry", state_path.display()),
+            )
+        })?)
+    } else {
+        None
+    };
+    let mut app = match crate::storage::load_from_path(state_path, config.clone())? {
+        Some(a) => a,
+        None => crate::storage::new_app_at_path(state_path, config),
+    };
+    let mut tracker = crate::todoist::Tracker::open(&app);
+    let mut out = run(&mut app, cmd)?;
+    if cmd.mutates() {
+        let mut journal =
+            crate::journal::Journal::open(crate::journal::journal_path_for(state_path), &app);
+        journal.checkpoint(&mut app, state_path)?;
+        // Todoist write-back; a failure leaves the calls queued for the next run
+        let ops = tracker.observe(&app);
+        let status = crate::todoist::sync_now(state_path, ops, &app.config.todoist)?;
+        let mut notices: Vec<String> = status.and_then(|s| s.notice()).into_iter().collect();
+        // Toggl push; unsynced sessions are picked up again by the next run
+        match crate::toggl::sync_now(state_path, &app) {
+            Ok(status) => notices.extend(status.and_then(|s| s.notice())),
+            Err(e) => notices.push(format!("toggl: {e:#}")),
+        }
+        if !notices.is_empty() {
+            let notice = notices.join("  ");
+            out.text = format!("{}\n{}", out.text, notices.join("\n")).trim_start().to_string();
+            if let Value::Object(map) = &mut out.json {
+                map.insert("sync".to_string(), json!(notice));
+            }
+        }
+    }
+    Ok(out)
+}
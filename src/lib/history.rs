@@ -0,0 +1,142 @@
+//! Multi-day history store: Past tasks persisted as one TOML file per date.
+//! - Layout: `<root>/YYYY/YYYY-MM-DD.toml` (root defaults to `history/` next to the snapshot).
+//! - A task belongs to the date it was finished (`done_ymd`, falling back to `planned_ymd`),
+//!   which gives its minute-of-day session times a calendar date.
+//! - Files are read lazily; `App` only rewrites the dates it touched in a session.
+
+use crate::task::Task;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Contents of a single day file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayFileV1 {
+    pub version: u8,
+    /// Date of this file (YYYYMMDD).
+    pub ymd: u32,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+}
+
+/// Date a Past task is filed under.
+pub fn day_key(task: &Task) -> u32 {
+    task.done_ymd.unwrap_or(task.planned_ymd)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store living next to a snapshot file: `<snapshot dir>/history/`.
+    pub fn for_state_path(state_path: &Path) -> Self {
+        let dir = state_path.parent().unwrap_or_else(|| Path::new("."));
+        Self::new(dir.join("history"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn day_path(&self, ymd: u32) -> PathBuf {
+        let name = format!("{}.toml", crate::date::format_ymd(ymd));
+        self.root.join(format!("{:04}", ymd / 10000)).join(name)
+    }
+
+    /// Dates with a day file, ascending.
+    pub fn list_days(&self) -> Result<Vec<u32>> {
+        let mut days = Vec::new();
+        let years = match fs::read_dir(&self.root) {
+            Ok(rd) => rd,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(days),
+            Err(e) => return Err(e).context("read history dir"),
+        };
+        for year in years.flatten() {
+            if !year.path().is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(year.path()).context("read history year dir")?.flatten() {
+                let name = entry.file_name();
+                let Some(stem) = name.to_str().and_then(|n| n.strip_suffix(".toml")) else {
+                    continue;
+                };
+                if let Some(ymd) = crate::date::parse_ymd(stem) {
+                    days.push(ymd);
+                }
+            }
+        }
+        days.sort_unstable();
+        Ok(days)
+    }
+
+    /// Tasks filed under `ymd`; empty when the day has no file.
+    pub fn load_day(&self, ymd: u32) -> Result<Vec<Task>> {
+        let path = self.day_path(ymd);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let s = fs::read_to_string(&path).context("read history day file")?;
+        let file: DayFileV1 = toml::from_str(&s)
+            .with_context(|| format!("parse history day file {}", path.display()))?;
+        Ok(file.tasks)
+    }
+
+    /// Replace the tasks filed under `ymd`. An empty list removes the file.
+    pub fn save_day(&self, ymd: u32, tasks: &[Task]) -> Result<()> {
+        let path = self.day_path(ymd);
+        if tasks.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path).context("remove empty history day file")?;
+            }
+            return Ok(());
+        }
+        let file = DayFileV1 { version: 1, ymd, tasks: tasks.to_vec() };
+        let s = toml::to_string_pretty(&file).context("serialize history day file")?;
+        crate::storage::write_atomic(&path, s.as_bytes()).context("write history day file")
+    }
+
+    /// Days within `from..=to` (YYYYMMDD) with their tasks, ascending by date.
+    /// Intended for analytics/exports that should not load the whole history.
+    pub fn load_range(&self, from: u32, to: u32) -> Result<Vec<(u32, Vec<Task>)>> {
+        let mut out = Vec::new();
+        for ymd in self.list_days()?.into_iter().filter(|d| (from..=to).contains(d)) {
+            out.push((ymd, self.load_day(ymd)?));
+        }
+        Ok(out)
+    }
+
+    /// Move a legacy flat `past` list into day files. Each legacy task is matched
+    /// against at most one task already in the target file, so an interrupted
+    /// migration can safely run again while identical legacy entries (the same
+    /// task done twice in a day) all survive. Returns the number of tasks written.
+    pub fn migrate(&self, past: Vec<Task>) -> Result<usize> {
+        let mut by_day: std::collections::BTreeMap<u32, Vec<Task>> = Default::default();
+        for t in past {
+            by_day.entry(day_key(&t)).or_default().push(t);
+        }
+        let mut written = 0;
+        for (ymd, tasks) in by_day {
+            let mut existing = self.load_day(ymd)?;
+            let before = existing.len();
+            let mut matched = vec![false; before];
+            for t in tasks {
+                match (0..before).find(|&i| !matched[i] && existing[i] == t) {
+                    Some(i) => matched[i] = true,
+                    None => existing.push(t),
+                }
+            }
+            if existing.len() > before {
+                written += existing.len() - before;
+                self.save_day(ymd, &existing)?;
+            }
+        }
+        Ok(written)
+    }
+}
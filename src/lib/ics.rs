@@ -0,0 +1,673 @@
+//! iCalendar (RFC 5545) export of the plan and the recorded sessions, and import of
+//! appointments.
+//!
+//! Export:
+//! - Planned blocks use the list/Calendar schedule (`ui::compute_planned_starts` from
+//!   `day_start_minutes`, honouring `fixed_start_min`): Today, and each Future date.
+//! - Every `Session` of Today and of the last `PAST_DAYS` Past days is an Actual event;
+//!   a running session ends now.
+//! - `SUMMARY` is the title, `CATEGORIES` the category name plus `Plan`/`Actual`.
+//! - UIDs hash the date, the task (its `remote_id`, else its title) and, for sessions, the
+//!   session number, so re-importing the file updates events instead of adding copies.
+//!   Reordering keeps UIDs; renaming a task gives its events new ones.
+//! - Times are floating local times (no `TZID`), like the rest of chute.
+//!
+//! Import (`chute import ics <file>`, and `[ics] paths` on startup and at day rollover):
+//! - Each timed event occurring today becomes a Today task with the event start as
+//!   `fixed_start_min` and its length as the estimate. `remote_id = "ics:<UID>/<date>"`
+//!   marks it as an appointment and keeps re-imports from duplicating it.
+//! - `RRULE` with `FREQ=DAILY|WEEKLY` (`INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`), `EXDATE` and
+//!   edited occurrences (`RECURRENCE-ID`) are honoured; other rules count only their first
+//!   date. All-day and cancelled events are skipped. `TZID` times are read as local time.
+
+use crate::app::{App, View};
+use crate::source::{Imported, RemoteTask, TaskSource};
+use crate::task::{Task, TaskState};
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local, NaiveDate, TimeZone, Timelike};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Past days whose sessions are exported besides Today.
+pub const PAST_DAYS: i32 = 7;
+const PRODID: &str = "-//chute-kun//chute-kun//EN";
+
+/// Which events to write. `Plan` and `Actual` get their own calendar name, so they can be
+/// imported as two calendars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Part {
+    #[default]
+    All,
+    Plan,
+    Actual,
+}
+
+impl Part {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "all" => Some(Part::All),
+            "plan" => Some(Part::Plan),
+            "actual" => Some(Part::Actual),
+            _ => None,
+        }
+    }
+
+    fn calendar_name(self) -> &'static str {
+        match self {
+            Part::All => "chute-kun",
+            Part::Plan => "chute-kun Plan",
+            Part::Actual => "chute-kun Actual",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Plan,
+    Actual,
+}
+
+/// One VEVENT. Minutes count from midnight of `ymd` and may pass 24:00.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    uid: String,
+    kind: Kind,
+    ymd: u32,
+    start_min: u32,
+    end_min: u32,
+    summary: String,
+    category: String,
+    description: String,
+}
+
+/// The exported calendar and how many events it holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    pub text: String,
+    pub events: usize,
+}
+
+/// Past tasks by day for the `PAST_DAYS` days before `today`, from memory when the day is
+/// loaded and from the history store otherwise.
+pub fn recent_past(app: &App, today: u32) -> Result<Vec<(u32, Vec<Task>)>> {
+    let mut out = Vec::new();
+    for back in (1..=PAST_DAYS).rev() {
+        let ymd = crate::date::add_days_to_ymd(today, -back);
+        let tasks = match app.history_store() {
+            Some(store) if !app.history_day_loaded(ymd) => store.load_day(ymd)?,
+            _ => app.history_tasks_for_day(ymd),
+        };
+        if !tasks.is_empty() {
+            out.push((ymd, tasks));
+        }
+    }
+    Ok(out)
+}
+
+/// Build the calendar for `app` on `today` at `now_min`, with `past` from `recent_past`.
+pub fn export(
+    app: &App,
+    past: &[(u32, Vec<Task>)],
+    today: u32,
+    now_min: u16,
+    part: Part,
+) -> Export {
+    let mut events = Vec::new();
+    if part != Part::Actual {
+        events.extend(plan_events(app, &app.day.tasks, today));
+        // Future dates are scheduled separately, each from day start
+        let mut dates: Vec<u32> = app.tomorrow_tasks().iter().map(|t| t.planned_ymd).collect();
+        dates.sort_unstable();
+        dates.dedup();
+        for ymd in dates {
+            let tasks: Vec<Task> =
+                app.tomorrow_tasks().iter().filter(|t| t.planned_ymd == ymd).cloned().collect();
+            events.extend(plan_events(app, &tasks, ymd));
+        }
+    }
+    if part != Part::Plan {
+        for (ymd, tasks) in past {
+            events.extend(session_events(app, tasks, *ymd, None));
+        }
+        events.extend(session_events(app, &app.day.tasks, today, Some(now_min)));
+    }
+    Export { text: render(&events, part), events: events.len() }
+}
+
+fn plan_events(app: &App, tasks: &[Task], ymd: u32) -> Vec<Event> {
+    let starts = crate::ui::compute_planned_starts(app.config.day_start_minutes, tasks);
+    let keys = task_keys(tasks);
+    tasks
+        .iter()
+        .zip(starts)
+        .zip(keys)
+        .filter(|((t, _), _)| t.estimate_min > 0)
+        .map(|((t, start), key)| Event {
+            uid: uid("plan", ymd, &key),
+            kind: Kind::Plan,
+            ymd,
+            start_min: u32::from(start),
+            end_min: u32::from(start) + u32::from(t.estimate_min),
+            summary: t.title.clone(),
+            category: app.config.category_name(&t.category),
+            description: format!("est {}m", t.estimate_min),
+        })
+        .collect()
+}
+
+fn session_events(app: &App, tasks: &[Task], ymd: u32, now_min: Option<u16>) -> Vec<Event> {
+    let keys = task_keys(tasks);
+    let mut out = Vec::new();
+    for (t, key) in tasks.iter().zip(keys) {
+        for (si, s) in t.sessions.iter().enumerate() {
+            let Some(end) = s.end_min.or(now_min) else {
+                continue;
+            };
+            let start = u32::from(s.start_min);
+            // An end before the start ran past midnight
+            let end = if end < s.start_min { u32::from(end) + 24 * 60 } else { u32::from(end) };
+            // Like the Calendar view, sub-minute sessions are left out
+            if end <= start {
+                continue;
+            }
+            out.push(Event {
+                uid: uid("actual", ymd, &format!("{key}\u{1f}{}", si + 1)),
+                kind: Kind::Actual,
+                ymd,
+                start_min: start,
+                end_min: end,
+                summary: t.title.clone(),
+                category: app.config.category_name(&t.category),
+                description: format!("session {} of {}", si + 1, t.sessions.len()),
+            });
+        }
+    }
+    out
+}
+
+/// Identity of each task within its day: `remote_id` or title, numbered when repeated.
+fn task_keys(tasks: &[Task]) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    tasks
+        .iter()
+        .map(|t| {
+            let base = t.remote_id.as_deref().unwrap_or(&t.title);
+            let n = seen.entry(base).or_default();
+            *n += 1;
+            format!("{base}\u{1f}{n}")
+        })
+        .collect()
+}
+
+fn uid(kind: &str, ymd: u32, key: &str) -> String {
+    format!("{kind}-{ymd}-{:016x}@chute-kun", fnv1a(key.as_bytes()))
+}
+
+// Stable across runs and Rust versions, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ u64::from(*b)).wrapping_mul(0x100_0000_01b3))
+}
+
+fn render(events: &[Event], part: Part) -> String {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", part.calendar_name()),
+    ];
+    for e in events {
+        let kind = match e.kind {
+            Kind::Plan => "Plan",
+            Kind::Actual => "Actual",
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", e.uid));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("DTSTART:{}", datetime(e.ymd, e.start_min)));
+        lines.push(format!("DTEND:{}", datetime(e.ymd, e.end_min)));
+        lines.push(format!("SUMMARY:{}", escape(&e.summary)));
+        lines.push(format!("CATEGORIES:{},{kind}", escape(&e.category)));
+        lines.push(format!("DESCRIPTION:{}", escape(&e.description)));
+        // Plans should not block free/busy lookups
+        if e.kind == Kind::Plan {
+            lines.push("TRANSP:TRANSPARENT".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+/// Floating local date-time `YYYYMMDDTHHMMSS`; minutes past 24:00 roll into the next days.
+fn datetime(ymd: u32, min: u32) -> String {
+    let day = crate::date::add_days_to_ymd(ymd, (min / (24 * 60)) as i32);
+    let min = min % (24 * 60);
+    format!("{day:08}T{:02}{:02}00", min / 60, min % 60)
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Fold content lines longer than 75 octets (continuations start with a space).
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+// ---- Import ----
+
+/// `[ics]` settings: calendar files whose events for today become fixed-start tasks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IcsConfig {
+    pub paths: Vec<PathBuf>,
+}
+
+/// Start or end of an event: a whole day, or a local date and minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum When {
+    Date(u32),
+    At(u32, u16),
+}
+
+impl When {
+    fn ymd(self) -> u32 {
+        match self {
+            When::Date(d) | When::At(d, _) => d,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freq {
+    Daily,
+    Weekly,
+    /// Any other rule: only the first occurrence is used.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    freq: Freq,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<u32>,
+    /// Weekdays, Monday = 0.
+    by_day: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct VEvent {
+    uid: String,
+    summary: String,
+    start: Option<When>,
+    end: Option<When>,
+    duration_min: Option<u32>,
+    rule: Option<Rule>,
+    exdates: Vec<u32>,
+    /// Set on an edited occurrence of a series: the date it replaces.
+    recurrence_ymd: Option<u32>,
+    cancelled: bool,
+    categories: Vec<String>,
+}
+
+/// Events of the calendar files in `[ics] paths`, imported as appointments.
+pub struct IcsSource {
+    pub path: PathBuf,
+    /// Day whose events are fetched.
+    pub day: u32,
+}
+
+impl TaskSource for IcsSource {
+    fn name(&self) -> &'static str {
+        "ics"
+    }
+
+    fn fetch(&self) -> Result<Vec<RemoteTask>> {
+        let text = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("read {}", self.path.display()))?;
+        Ok(appointments(&text, self.day))
+    }
+}
+
+/// Timed events occurring on `day`, in start order. The id is `<UID>/<YYYYMMDD>`, so
+/// every occurrence of a series is imported once.
+pub fn appointments(text: &str, day: u32) -> Vec<RemoteTask> {
+    let events = parse_events(text);
+    let mut out: Vec<(u16, RemoteTask)> = Vec::new();
+    for ev in &events {
+        let Some(When::At(start_ymd, start_min)) = ev.start else {
+            continue; // all-day events are not appointments
+        };
+        if ev.cancelled {
+            continue;
+        }
+        let hit = match (ev.recurrence_ymd, &ev.rule) {
+            (Some(_), _) | (None, None) => start_ymd == day,
+            (None, Some(rule)) => {
+                // An edited occurrence replaces the series on its date
+                let replaced =
+                    events.iter().any(|o| o.uid == ev.uid && o.recurrence_ymd == Some(day));
+                !ev.exdates.contains(&day) && !replaced && occurs(rule, start_ymd, day)
+            }
+        };
+        if !hit {
+            continue;
+        }
+        let length = ev.duration_min.or_else(|| match ev.end {
+            Some(When::At(ymd, min)) => {
+                let days = crate::date::days_between(start_ymd, ymd)?;
+                u32::try_from(days * 24 * 60 + i64::from(min) - i64::from(start_min)).ok()
+            }
+            _ => None,
+        });
+        out.push((
+            start_min,
+            RemoteTask {
+                id: format!("{}/{day}", ev.uid),
+                title: ev.summary.clone(),
+                due_ymd: Some(day),
+                due_min: Some(start_min),
+                estimate_min: length.filter(|m| *m > 0).map(|m| m.min(u32::from(u16::MAX)) as u16),
+                labels: ev.categories.clone(),
+            },
+        ));
+    }
+    out.sort_by_key(|(start, _)| *start);
+    out.into_iter().map(|(_, t)| t).collect()
+}
+
+/// Import today's appointments from every `[ics] paths` file. Each new task is placed
+/// before the first planned task scheduled at or after its start. A file that fails does
+/// not stop the others; the first error is returned.
+pub fn import_configured(app: &mut App) -> Result<Imported> {
+    let mut total = Imported::default();
+    let mut first_err = None;
+    for path in app.config.ics.paths.clone() {
+        let source = IcsSource { path, day: crate::date::today_ymd() };
+        match import_file(app, &source) {
+            Ok(imported) => {
+                total.added.extend(imported.added);
+                total.skipped += imported.skipped;
+            }
+            Err(e) => {
+                first_err.get_or_insert(e);
+            }
+        }
+    }
+    match first_err {
+        Some(e) => Err(e),
+        None => Ok(total),
+    }
+}
+
+/// Import the appointments of one file (`chute import ics <file>`).
+pub fn import_file(app: &mut App, source: &IcsSource) -> Result<Imported> {
+    let imported = crate::source::import_from(app, source)?;
+    for (view, task) in &imported.added {
+        if *view == View::Today {
+            place_appointment(app, task);
+        }
+    }
+    Ok(imported)
+}
+
+// Move the just-added `task` (last in Today) in front of the first planned task that
+// would start at or after it, so the schedule reads in time order.
+fn place_appointment(app: &mut App, task: &Task) {
+    let tasks = &app.day.tasks;
+    let Some(from) = tasks.iter().rposition(|t| t.remote_id == task.remote_id) else {
+        return;
+    };
+    let Some(start) = task.fixed_start_min else {
+        return;
+    };
+    let starts = crate::ui::compute_planned_starts(app.config.day_start_minutes, &tasks[..from]);
+    let slot = (0..from).find(|&i| {
+        tasks[i].state == TaskState::Planned
+            && tasks[i].fixed_start_min.unwrap_or(starts[i]) >= start
+    });
+    if let Some(slot) = slot {
+        app.day.move_index(from, slot);
+    }
+}
+
+fn occurs(rule: &Rule, start: u32, day: u32) -> bool {
+    if day < start || rule.until.is_some_and(|u| day > u) {
+        return false;
+    }
+    let Some(count) = rule.count else {
+        return matches_rule(rule, start, day);
+    };
+    // COUNT needs the occurrences before `day`
+    let Some(span) = crate::date::days_between(start, day) else {
+        return false;
+    };
+    let mut n = 0;
+    for offset in 0..=span {
+        let d = crate::date::add_days_to_ymd(start, offset as i32);
+        if matches_rule(rule, start, d) {
+            n += 1;
+            if n > count {
+                return false;
+            }
+        }
+    }
+    matches_rule(rule, start, day)
+}
+
+fn matches_rule(rule: &Rule, start: u32, d: u32) -> bool {
+    let Some(wd) = crate::date::weekday_index(d) else {
+        return false;
+    };
+    let interval = i64::from(rule.interval.max(1));
+    match rule.freq {
+        Freq::Daily => {
+            crate::date::days_between(start, d).is_some_and(|n| n % interval == 0)
+                && (rule.by_day.is_empty() || rule.by_day.contains(&wd))
+        }
+        Freq::Weekly => {
+            let monday = |ymd: u32| {
+                let wd = crate::date::weekday_index(ymd)?;
+                crate::date::checked_add_days_to_ymd(ymd, -i32::from(wd))
+            };
+            let weeks =
+                monday(start).zip(monday(d)).and_then(|(a, b)| crate::date::days_between(a, b));
+            let days = if rule.by_day.is_empty() {
+                crate::date::weekday_index(start).into_iter().collect()
+            } else {
+                rule.by_day.clone()
+            };
+            weeks.is_some_and(|w| (w / 7) % interval == 0) && days.contains(&wd)
+        }
+        Freq::Other => d == start,
+    }
+}
+
+fn parse_events(text: &str) -> Vec<VEvent> {
+    // Unfold continuation lines
+    let unfolded = text.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut events = Vec::new();
+    let mut cur: Option<VEvent> = None;
+    // Components nested in a VEVENT (VALARM) are skipped
+    let mut nested = 0;
+    for line in unfolded.lines() {
+        let Some((head, value)) = split_property(line) else {
+            continue;
+        };
+        let mut parts = head.split(';');
+        let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let params: Vec<&str> = parts.collect();
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => cur = Some(VEvent::default()),
+            ("END", "VEVENT") => {
+                if let Some(mut ev) = cur.take() {
+                    if ev.uid.is_empty() {
+                        ev.uid = format!("{}@{:?}", ev.summary, ev.start);
+                    }
+                    events.push(ev);
+                }
+            }
+            ("BEGIN", _) if cur.is_some() => nested += 1,
+            ("END", _) if cur.is_some() => nested -= 1,
+            _ => {}
+        }
+        let Some(ev) = cur.as_mut().filter(|_| nested == 0) else {
+            continue;
+        };
+        match name.as_str() {
+            "UID" => ev.uid = value.to_string(),
+            "SUMMARY" => ev.summary = unescape(value),
+            "DTSTART" => ev.start = parse_when(value, &params),
+            "DTEND" => ev.end = parse_when(value, &params),
+            "DURATION" => ev.duration_min = parse_duration(value),
+            "RRULE" => ev.rule = Some(parse_rule(value)),
+            "EXDATE" => ev
+                .exdates
+                .extend(value.split(',').filter_map(|v| parse_when(v, &params)).map(When::ymd)),
+            "RECURRENCE-ID" => ev.recurrence_ymd = parse_when(value, &params).map(When::ymd),
+            "STATUS" => ev.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "CATEGORIES" => ev
+                .categories
+                .extend(value.split(',').map(|c| unescape(c.trim())).filter(|c| !c.is_empty())),
+            _ => {}
+        }
+    }
+    events
+}
+
+// `NAME;PARAM=x:value`, with `:` allowed inside quoted parameter values
+fn split_property(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some((&line[..i], line[i + 1..].trim_end())),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `20250901` (all day), `20250901T090000` (local; `TZID` is read as local time) or
+/// `20250901T090000Z` (UTC, converted to local). Out-of-range times are `None`.
+fn parse_when(value: &str, params: &[&str]) -> Option<When> {
+    let value = value.trim();
+    let date_only = params.iter().any(|p| p.eq_ignore_ascii_case("VALUE=DATE"));
+    let (date, time) = match value.split_once('T') {
+        Some((d, t)) if !date_only => (d, Some(t)),
+        _ => (value.get(..8)?, None),
+    };
+    let ymd: u32 = date.parse().ok().filter(|d| crate::date::is_valid_ymd(*d))?;
+    let Some(time) = time else {
+        return Some(When::Date(ymd));
+    };
+    let (hh, mm) = (time.get(..2)?.parse::<u32>().ok()?, time.get(2..4)?.parse::<u32>().ok()?);
+    if hh >= 24 || mm >= 60 {
+        return None;
+    }
+    if !time.ends_with('Z') {
+        return Some(When::At(ymd, (hh * 60 + mm) as u16));
+    }
+    let naive = NaiveDate::from_ymd_opt((ymd / 10000) as i32, ymd / 100 % 100, ymd % 100)?
+        .and_hms_opt(hh, mm, 0)?;
+    let local = chrono::Utc.from_utc_datetime(&naive).with_timezone(&Local);
+    let ymd = local.year() as u32 * 10000 + local.month() * 100 + local.day();
+    Some(When::At(ymd, (local.hour() * 60 + local.minute()) as u16))
+}
+
+/// `PT1H30M`, `P1D`, `P1W` in minutes; `None` when malformed or too large.
+fn parse_duration(value: &str) -> Option<u32> {
+    let rest = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let (mut total, mut num) = (0u32, 0u32);
+    let add = |total: u32, num: u32, unit: u32| total.checked_add(num.checked_mul(unit)?);
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => num = num.checked_mul(10)?.checked_add(c.to_digit(10)?)?,
+            'W' => total = add(total, num, 7 * 24 * 60)?,
+            'D' => total = add(total, num, 24 * 60)?,
+            'H' => total = add(total, num, 60)?,
+            'M' => total = add(total, num, 1)?,
+            'S' | 'T' => {}
+            _ => return None,
+        }
+        if !c.is_ascii_digit() {
+            num = 0;
+        }
+    }
+    Some(total)
+}
+
+fn parse_rule(value: &str) -> Rule {
+    let mut rule =
+        Rule { freq: Freq::Other, interval: 1, count: None, until: None, by_day: vec![] };
+    for part in value.split(';') {
+        let Some((k, v)) = part.split_once('=') else {
+            continue;
+        };
+        match k.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                rule.freq = match v.to_ascii_uppercase().as_str() {
+                    "DAILY" => Freq::Daily,
+                    "WEEKLY" => Freq::Weekly,
+                    _ => Freq::Other,
+                }
+            }
+            "INTERVAL" => rule.interval = v.parse().unwrap_or(1),
+            "COUNT" => rule.count = v.parse().ok(),
+            "UNTIL" => rule.until = parse_when(v, &[]).map(When::ymd),
+            "BYDAY" => {
+                const DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+                // Ordinals (`1MO`) only make sense for monthly rules
+                rule.by_day = v
+                    .split(',')
+                    .filter_map(|d| DAYS.iter().position(|x| d.eq_ignore_ascii_case(x)))
+                    .map(|i| i as u8)
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    rule
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n' | 'N') => out.push(' '),
+                Some(other) => out.push(other),
+                None => {}
+            },
+            (c, false) => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,436 @@
+//! Write-ahead journal of task-list events, kept next to the snapshot (`snapshot.journal`).
+//! - One JSON `JournalEntry` per line, appended and synced as soon as a change is seen.
+//! - Entries are derived by diffing Today/Future and the routines against the last recorded
+//!   state, so every mutation path is covered without each `App` action having to report
+//!   itself. The comparison runs first and the lists are only copied and diffed when it
+//!   finds a change.
+//! - Past is loaded lazily, so it cannot be diffed; the `App` keeps its edits
+//!   (`App::history_edits`) until they are recorded, and replay applies them to the loaded
+//!   app (`replay_past`).
+//! - The snapshot stores the last `seq` it contains (`SnapshotV1::journal_seq`); on load,
+//!   newer entries are replayed, then a checkpoint truncates the journal.
+
+use crate::app::App;
+use crate::routine::Routine;
+use crate::storage::SnapshotV1;
+use crate::task::Task;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListKind {
+    Today,
+    Future,
+}
+
+/// A single list edit. Indices refer to the list state right before the op.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalOp {
+    Insert {
+        list: ListKind,
+        index: usize,
+        task: Task,
+    },
+    Update {
+        list: ListKind,
+        index: usize,
+        task: Task,
+    },
+    Remove {
+        list: ListKind,
+        index: usize,
+    },
+    Move {
+        list: ListKind,
+        from: usize,
+        to: usize,
+    },
+    /// Remove a Done task from Today and hand it to Past (day rollover sweep).
+    Archive {
+        index: usize,
+        task: Task,
+    },
+    /// Replace the Past task equal to `before`.
+    UpdatePast {
+        before: Task,
+        task: Task,
+    },
+    /// The routine definitions after the change.
+    Routines {
+        routines: Vec<Routine>,
+    },
+}
+
+/// What the user did, for readability of the journal; replay only looks at `ops`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalEvent {
+    Add,
+    Start,
+    Pause,
+    Finish,
+    Delete,
+    Reorder,
+    Postpone,
+    Bring,
+    Archive,
+    Edit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    /// Unix time (seconds) when the change was recorded.
+    pub ts: u64,
+    pub event: JournalEvent,
+    pub ops: Vec<JournalOp>,
+}
+
+/// Journal file for a snapshot path: same directory and stem, `.journal` extension.
+pub fn journal_path_for(state_path: &Path) -> PathBuf {
+    state_path.with_extension("journal")
+}
+
+/// Read all entries; a missing file is empty. A torn last line (crash mid-append) is ignored.
+pub fn read_entries(path: &Path) -> Result<Vec<JournalEntry>> {
+    let s = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context("read journal"),
+    };
+    let mut out = Vec::new();
+    let lines: Vec<&str> = s.lines().filter(|l| !l.trim().is_empty()).collect();
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str::<JournalEntry>(line) {
+            Ok(e) => out.push(e),
+            Err(_) if i + 1 == lines.len() => break,
+            Err(e) => return Err(e).context(format!("parse journal line {}", i + 1)),
+        }
+    }
+    Ok(out)
+}
+
+/// Apply entries newer than `snap.journal_seq` to the snapshot's lists and routines.
+/// Archived tasks are appended to `snap.past` (history migration skips ones already on
+/// disk); Past edits are left to `replay_past`.
+/// Returns the number of entries applied; stops at the first entry that does not fit.
+pub fn replay(snap: &mut SnapshotV1, entries: &[JournalEntry]) -> Result<usize> {
+    let mut applied = 0;
+    let from = snap.journal_seq;
+    for e in entries.iter().filter(|e| e.seq > from) {
+        let mut today = snap.today.clone();
+        let mut future = snap.future.clone();
+        let mut past = Vec::new();
+        let mut routines = snap.routines.clone();
+        for op in &e.ops {
+            apply_op(op, &mut today, &mut future, &mut past, &mut routines)
+                .with_context(|| format!("replay journal entry {}", e.seq))?;
+        }
+        snap.today = today;
+        snap.future = future;
+        snap.past.extend(past);
+        snap.routines = routines;
+        snap.journal_seq = e.seq;
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+/// Apply the Past edits of entries after `from` up to `app.journal_seq()` (what `replay`
+/// applied) once the app and its history are loaded. Edits of tasks no longer in Past are
+/// skipped. Returns the number of edits applied.
+pub fn replay_past(app: &mut App, entries: &[JournalEntry], from: u64) -> usize {
+    let upto = app.journal_seq();
+    let mut applied = 0;
+    for e in entries.iter().filter(|e| e.seq > from && e.seq <= upto) {
+        for op in &e.ops {
+            if let JournalOp::UpdatePast { before, task } = op {
+                if app.replace_history_task(before, task.clone()) {
+                    applied += 1;
+                }
+            }
+        }
+    }
+    applied
+}
+
+fn apply_op(
+    op: &JournalOp,
+    today: &mut Vec<Task>,
+    future: &mut Vec<Task>,
+    past: &mut Vec<Task>,
+    routines: &mut Vec<Routine>,
+) -> Result<()> {
+    let out_of_range = || anyhow!("index out of range");
+    match op {
+        JournalOp::Insert { list, index, task } => {
+            let v = select(*list, today, future);
+            if *index > v.len() {
+                return Err(out_of_range());
+            }
+            v.insert(*index, task.clone());
+        }
+        JournalOp::Update { list, index, task } => {
+            let v = select(*list, today, future);
+            *v.get_mut(*index).ok_or_else(out_of_range)? = task.clone();
+        }
+        JournalOp::Remove { list, index } => {
+            let v = select(*list, today, future);
+            if *index >= v.len() {
+                return Err(out_of_range());
+            }
+            v.remove(*index);
+        }
+        JournalOp::Move { list, from, to } => {
+            let v = select(*list, today, future);
+            if *from >= v.len() || *to >= v.len() {
+                return Err(out_of_range());
+            }
+            let t = v.remove(*from);
+            v.insert(*to, t);
+        }
+        JournalOp::Archive { index, task } => {
+            if *index >= today.len() {
+                return Err(out_of_range());
+            }
+            today.remove(*index);
+            past.push(task.clone());
+        }
+        JournalOp::UpdatePast { .. } => {}
+        JournalOp::Routines { routines: r } => *routines = r.clone(),
+    }
+    Ok(())
+}
+
+fn select<'a>(
+    list: ListKind,
+    today: &'a mut Vec<Task>,
+    future: &'a mut Vec<Task>,
+) -> &'a mut Vec<Task> {
+    match list {
+        ListKind::Today => today,
+        ListKind::Future => future,
+    }
+}
+
+/// Tasks compared for journaling ignore sub-minute carry, which changes every tick.
+fn same(a: &Task, b: &Task) -> bool {
+    if a.actual_carry_sec == b.actual_carry_sec {
+        return a == b;
+    }
+    let mut a = a.clone();
+    a.actual_carry_sec = b.actual_carry_sec;
+    &a == b
+}
+
+fn same_all(a: &[Task], b: &[Task]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y))
+}
+
+/// Express the change from `before` to `after` as list edits.
+/// - Same length: a single move, or in-place updates.
+/// - Otherwise: removals (descending) then insertions (ascending) around the longest
+///   common subsequence, so a rollover sweeping several tasks stays a set of removals.
+pub fn diff_list(list: ListKind, before: &[Task], after: &[Task]) -> Vec<JournalOp> {
+    if same_all(before, after) {
+        return Vec::new();
+    }
+    if before.len() == after.len() {
+        let i = before.iter().zip(after).position(|(a, b)| !same(a, b)).unwrap_or(0);
+        let j = (0..before.len()).rev().find(|&k| !same(&before[k], &after[k])).unwrap_or(i);
+        if i < j && same(&before[i], &after[j]) && same_all(&before[i + 1..=j], &after[i..j]) {
+            return vec![JournalOp::Move { list, from: i, to: j }];
+        }
+        if i < j && same(&after[i], &before[j]) && same_all(&before[i..j], &after[i + 1..=j]) {
+            return vec![JournalOp::Move { list, from: j, to: i }];
+        }
+        return (i..=j)
+            .filter(|&k| !same(&before[k], &after[k]))
+            .map(|k| JournalOp::Update { list, index: k, task: after[k].clone() })
+            .collect();
+    }
+    // LCS table over task equality; lists are short (one day's worth of tasks)
+    let (n, m) = (before.len(), after.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for a in (0..n).rev() {
+        for b in (0..m).rev() {
+            lcs[a][b] = if same(&before[a], &after[b]) {
+                lcs[a + 1][b + 1] + 1
+            } else {
+                lcs[a + 1][b].max(lcs[a][b + 1])
+            };
+        }
+    }
+    let (mut removed, mut inserted) = (Vec::new(), Vec::new());
+    let (mut a, mut b) = (0, 0);
+    while a < n || b < m {
+        if a < n && b < m && same(&before[a], &after[b]) {
+            a += 1;
+            b += 1;
+        } else if b == m || (a < n && lcs[a + 1][b] >= lcs[a][b + 1]) {
+            removed.push(a);
+            a += 1;
+        } else {
+            inserted.push(b);
+            b += 1;
+        }
+    }
+    let mut ops: Vec<JournalOp> =
+        removed.into_iter().rev().map(|index| JournalOp::Remove { list, index }).collect();
+    ops.extend(inserted.into_iter().map(|index| JournalOp::Insert {
+        list,
+        index,
+        task: after[index].clone(),
+    }));
+    ops
+}
+
+fn classify(ops: &[JournalOp], before_today: &[Task]) -> JournalEvent {
+    use crate::task::TaskState as S;
+    let has = |f: &dyn Fn(&JournalOp) -> bool| ops.iter().any(f);
+    let removed_from =
+        |l: ListKind| has(&|op| matches!(op, JournalOp::Remove { list, .. } if *list == l));
+    let inserted_into =
+        |l: ListKind| has(&|op| matches!(op, JournalOp::Insert { list, .. } if *list == l));
+    if has(&|op| matches!(op, JournalOp::Archive { .. })) {
+        return JournalEvent::Archive;
+    }
+    if removed_from(ListKind::Today) && inserted_into(ListKind::Future) {
+        return JournalEvent::Postpone;
+    }
+    if removed_from(ListKind::Future) && inserted_into(ListKind::Today) {
+        return JournalEvent::Bring;
+    }
+    let mut event = JournalEvent::Edit;
+    for op in ops {
+        match op {
+            JournalOp::Insert { .. } => return JournalEvent::Add,
+            JournalOp::Remove { .. } => return JournalEvent::Delete,
+            JournalOp::Move { .. } => return JournalEvent::Reorder,
+            JournalOp::Update { list: ListKind::Today, index, task } => {
+                let prev = before_today.get(*index).map(|t| t.state);
+                match (prev, task.state) {
+                    (Some(p), S::Active) if p != S::Active => return JournalEvent::Start,
+                    (Some(p), S::Done) if p != S::Done => return JournalEvent::Finish,
+                    (Some(S::Active), S::Paused) => event = JournalEvent::Pause,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    event
+}
+
+/// Appends entries for changes observed in an `App` and checkpoints it.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    next_seq: u64,
+    today: Vec<Task>,
+    future: Vec<Task>,
+    routines: Vec<Routine>,
+}
+
+impl Journal {
+    /// Start journaling from the app's current state; sequence numbers continue after
+    /// `App::journal_seq` (which includes anything replayed at load).
+    pub fn open(path: PathBuf, app: &App) -> Self {
+        Self {
+            path,
+            next_seq: app.journal_seq() + 1,
+            today: app.day.tasks.clone(),
+            future: app.tomorrow_tasks().clone(),
+            routines: app.routines().clone(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Diff the app against the last recorded state and append one entry if it changed.
+    /// Returns true when an entry was written.
+    pub fn record(&mut self, app: &mut App) -> Result<bool> {
+        // Most calls (every tick of the main loop) see no change; compare before copying
+        if same_all(&self.today, &app.day.tasks)
+            && same_all(&self.future, app.tomorrow_tasks())
+            && self.routines == *app.routines()
+            && app.history_edits().is_empty()
+        {
+            return Ok(false);
+        }
+        let today = app.day.tasks.clone();
+        let future = app.tomorrow_tasks().clone();
+        let mut ops = diff_list(ListKind::Today, &self.today, &today);
+        // Removals that landed in Past are archives, not deletions
+        for op in ops.iter_mut() {
+            if let JournalOp::Remove { list: ListKind::Today, index } = *op {
+                let removed = &self.today[index];
+                if let Some(h) = app.history_tasks().iter().rev().find(|h| same(h, removed)) {
+                    *op = JournalOp::Archive { index, task: h.clone() };
+                }
+            }
+        }
+        ops.extend(diff_list(ListKind::Future, &self.future, &future));
+        ops.extend(app.history_edits().iter().map(|(before, task)| JournalOp::UpdatePast {
+            before: before.clone(),
+            task: task.clone(),
+        }));
+        if self.routines != *app.routines() {
+            ops.push(JournalOp::Routines { routines: app.routines().clone() });
+        }
+        if ops.is_empty() {
+            return Ok(false);
+        }
+        let entry = JournalEntry {
+            seq: self.next_seq,
+            ts: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            event: classify(&ops, &self.today),
+            ops,
+        };
+        self.append(&entry)?;
+        app.set_journal_seq(entry.seq);
+        app.mark_history_edits_journaled();
+        self.next_seq += 1;
+        self.today = today;
+        self.future = future;
+        self.routines = app.routines().clone();
+        Ok(true)
+    }
+
+    fn append(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .context("open journal")?;
+        let mut line = serde_json::to_string(entry).context("serialize journal entry")?;
+        line.push('\n');
+        f.write_all(line.as_bytes()).context("append journal")?;
+        f.sync_data().context("sync journal")
+    }
+
+    /// Save the snapshot (and changed history days), then truncate the journal.
+    /// Any pending change is recorded first so the snapshot's `journal_seq` covers it.
+    pub fn checkpoint(&mut self, app: &mut App, state_path: &Path) -> Result<()> {
+        self.record(app)?;
+        crate::storage::save_to_path(app, state_path)?;
+        app.mark_history_saved();
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("truncate journal"),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Single-line text editor shared by the title, rename, command and search inputs.
+//! - The cursor is a char index; `cursor_width` gives its display column (CJK is 2 wide).
+//! - Keys: Left/Right/Home/End, Ctrl/Alt+Left/Right and Alt+b/f (words), Backspace/Delete,
+//!   Ctrl+A/E (start/end), Ctrl+W (word before), Ctrl+U/K (to start/end).
+//! - History recall (Up/Down) walks a caller-owned list; the text typed before recalling
+//!   comes back after the newest entry.
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+/// Entries kept per history list.
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineEditor {
+    text: String,
+    cursor: usize,
+    // (index into the history, text before recalling)
+    recall: Option<(usize, String)>,
+}
+
+impl LineEditor {
+    /// Editor holding `text` with the cursor at the end.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let cursor = text.chars().count();
+        Self { text, cursor, recall: None }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+    /// Display width of the text before the cursor.
+    pub fn cursor_width(&self) -> usize {
+        self.text[..self.byte_at(self.cursor)].width()
+    }
+    /// Replace the text and move the cursor to the end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.len();
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+    fn byte_at(&self, idx: usize) -> usize {
+        self.text.char_indices().nth(idx).map_or(self.text.len(), |(b, _)| b)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let at = self.byte_at(self.cursor);
+        self.text.insert(at, c);
+        self.cursor += 1;
+    }
+    pub fn insert_str(&mut self, s: &str) {
+        let at = self.byte_at(self.cursor);
+        self.text.insert_str(at, s);
+        self.cursor += s.chars().count();
+    }
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let at = self.byte_at(self.cursor);
+            self.text.remove(at);
+        }
+    }
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            let at = self.byte_at(self.cursor);
+            self.text.remove(at);
+        }
+    }
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+    pub fn move_end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    fn word_start_before(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+    fn word_end_after(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        i
+    }
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start_before();
+    }
+    pub fn word_right(&mut self) {
+        self.cursor = self.word_end_after();
+    }
+    // Remove chars in `from..self.cursor` and leave the cursor at `from`
+    fn remove_before(&mut self, from: usize) {
+        let (a, b) = (self.byte_at(from), self.byte_at(self.cursor));
+        self.text.replace_range(a..b, "");
+        self.cursor = from;
+    }
+    /// Ctrl+W: delete the word before the cursor (and the spaces after it).
+    pub fn delete_word_before(&mut self) {
+        self.remove_before(self.word_start_before());
+    }
+    /// Ctrl+U: delete from the start of the line to the cursor.
+    pub fn kill_to_start(&mut self) {
+        self.remove_before(0);
+    }
+    /// Ctrl+K: delete from the cursor to the end of the line.
+    pub fn kill_to_end(&mut self) {
+        let at = self.byte_at(self.cursor);
+        self.text.truncate(at);
+    }
+
+    /// Apply an editing key. Returns false for keys that are not editing keys
+    /// (Enter, Esc, Up/Down, ...) so the caller can handle them.
+    pub fn handle_key(&mut self, code: KeyCode, mods: KeyModifiers) -> bool {
+        let ctrl = mods.contains(KeyModifiers::CONTROL);
+        let alt = mods.contains(KeyModifiers::ALT);
+        match code {
+            KeyCode::Left if ctrl || alt => self.word_left(),
+            KeyCode::Right if ctrl || alt => self.word_right(),
+            KeyCode::Char('b') if alt => self.word_left(),
+            KeyCode::Char('f') if alt => self.word_right(),
+            KeyCode::Char('a') if ctrl => self.move_home(),
+            KeyCode::Char('e') if ctrl => self.move_end(),
+            KeyCode::Char('w') if ctrl => self.delete_word_before(),
+            KeyCode::Char('u') if ctrl => self.kill_to_start(),
+            KeyCode::Char('k') if ctrl => self.kill_to_end(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Home => self.move_home(),
+            KeyCode::End => self.move_end(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Char(c) if !ctrl && !alt => self.insert_char(c),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Up: show the previous (older) entry of `history`.
+    pub fn recall_prev(&mut self, history: &[String]) {
+        let idx = match &self.recall {
+            Some((i, _)) => i.saturating_sub(1),
+            None if history.is_empty() => return,
+            None => {
+                self.recall = Some((history.len(), self.text.clone()));
+                history.len() - 1
+            }
+        };
+        if let Some((i, _)) = self.recall.as_mut() {
+            *i = idx;
+        }
+        self.set_text(history[idx].clone());
+    }
+
+    /// Down: show the next (newer) entry, or the text typed before recalling.
+    pub fn recall_next(&mut self, history: &[String]) {
+        let Some((i, draft)) = self.recall.take() else {
+            return;
+        };
+        if i + 1 < history.len() {
+            self.recall = Some((i + 1, draft));
+            self.set_text(history[i + 1].clone());
+        } else {
+            self.set_text(draft);
+        }
+    }
+}
+
+/// Append a submitted line to `history`, skipping blanks and immediate repeats.
+pub fn push_history(history: &mut Vec<String>, entry: &str) {
+    let entry = entry.trim();
+    if entry.is_empty() || history.last().map(String::as_str) == Some(entry) {
+        return;
+    }
+    history.push(entry.to_string());
+    if history.len() > HISTORY_LIMIT {
+        history.remove(0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Durable queue of pending calls to external services, kept next to the snapshot.
+//! - One JSON item per line. New items are appended and synced before they are sent,
+//!   so a missing network (or a crash) only delays them.
+//! - Items leave the queue in order once delivered; the file is rewritten atomically
+//!   (`storage::write_atomic`) and removed when empty.
+//! - A call that succeeded right before a crash may be sent again on the next run.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct Outbox<T> {
+    path: PathBuf,
+    items: Vec<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Outbox<T> {
+    /// Load the queue at `path`; a missing file is empty and a torn last line is ignored.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let s = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).context("read outbox"),
+        };
+        let lines: Vec<&str> = s.lines().filter(|l| !l.trim().is_empty()).collect();
+        let mut items = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<T>(line) {
+                Ok(item) => items.push(item),
+                Err(_) if i + 1 == lines.len() => break,
+                Err(e) => return Err(e).context(format!("parse outbox line {}", i + 1)),
+            }
+        }
+        Ok(Self { path, items })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pending(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Append `items` and sync them to disk.
+    pub fn push(&mut self, items: Vec<T>) -> Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        let mut buf = String::new();
+        for item in &items {
+            buf.push_str(&serde_json::to_string(item).context("serialize outbox item")?);
+            buf.push('\n');
+        }
+        let mut f =
+            OpenOptions::new().create(true).append(true).open(&self.path).context("open outbox")?;
+        f.write_all(buf.as_bytes()).context("append outbox")?;
+        f.sync_data().context("sync outbox")?;
+        self.items.extend(items);
+        Ok(())
+    }
+
+    /// Drop the first item (after it was delivered or rejected for good).
+    pub fn pop_front(&mut self) -> Result<()> {
+        if self.items.is_empty() {
+            return Ok(());
+        }
+        self.items.remove(0);
+        if self.items.is_empty() {
+            return match fs::remove_file(&self.path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e).context("remove outbox"),
+            };
+        }
+        let mut buf = String::new();
+        for item in &self.items {
+            buf.push_str(&serde_json::to_string(item).context("serialize outbox item")?);
+            buf.push('\n');
+        }
+        crate::storage::write_atomic(&self.path, buf.as_bytes()).context("rewrite outbox")
+    }
+}
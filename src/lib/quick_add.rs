@@ -0,0 +1,182 @@
+//! Quick-add grammar for new task titles, shared by the TUI title input and CLI `add`.
+//! - `Write report 45m @work ^14:00 >tomorrow #client-a`
+//! - `45m` / `2h` / `1h30m`: estimate. `@id` or `@name`: a category declared in config.
+//! - `^HH:MM` / `^HHMM`: fixed start. `>today`, `>tomorrow`, `>YYYY-MM-DD`, `>3d`, `>mon`:
+//!   planned date (weekdays mean the next one after today).
+//! - `#tag`: tags (see `tags.rs`).
+//! - Words that do not parse (unknown category, bad time) stay in the title; when a
+//!   field is given twice the last one wins.
+//! - `paste_lines` splits a multi-line paste (Markdown checklist, mail excerpt) into one
+//!   title per line.
+
+use crate::config::Config;
+use crate::routine::Weekday;
+use crate::task::{Category, Task};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuickAdd {
+    pub title: String,
+    pub estimate_min: Option<u16>,
+    pub category: Option<Category>,
+    pub fixed_start_min: Option<u16>,
+    pub planned_ymd: Option<u32>,
+    pub tags: Vec<String>,
+}
+
+/// Estimate of a new task whose title gives none.
+pub const DEFAULT_ESTIMATE_MIN: u16 = 25;
+/// Estimate of a new interrupt whose title gives none.
+pub const DEFAULT_INTERRUPT_ESTIMATE_MIN: u16 = 15;
+
+/// `45m`, `2h`, `1h30m` (also `1h30`) in minutes.
+pub(crate) fn parse_duration(s: &str) -> Option<u16> {
+    let digits = |t: &str| !t.is_empty() && t.bytes().all(|b| b.is_ascii_digit());
+    let (h, m) = match s.split_once('h') {
+        Some((h, "")) if digits(h) => (h, "0"),
+        Some((h, m)) => {
+            let m = m.strip_suffix('m').unwrap_or(m);
+            if !digits(h) || !digits(m) {
+                return None;
+            }
+            (h, m)
+        }
+        None => {
+            let m = s.strip_suffix('m')?;
+            if !digits(m) {
+                return None;
+            }
+            ("0", m)
+        }
+    };
+    let total = h.parse::<u32>().ok()?.checked_mul(60)?.checked_add(m.parse::<u32>().ok()?)?;
+    u16::try_from(total).ok()
+}
+
+/// `None` (the word stays in the title) when `>Nd` lands past the calendar's range.
+fn parse_date(s: &str, today: u32) -> Option<u32> {
+    let add = |days: u32| crate::date::checked_add_days_to_ymd(today, i32::try_from(days).ok()?);
+    match s {
+        "today" => Some(today),
+        "tomorrow" => add(1),
+        _ => {
+            if let Some(n) = s.strip_suffix('d').and_then(|n| n.parse::<u32>().ok()) {
+                return add(n);
+            }
+            if let Some(w) = Weekday::parse(s) {
+                let cur = crate::date::weekday_index(today)? as u32;
+                let ahead = (w.index() as u32 + 7 - cur - 1) % 7 + 1;
+                return add(ahead);
+            }
+            crate::date::parse_ymd(s)
+        }
+    }
+}
+
+pub(crate) fn find_category(s: &str, cfg: &Config) -> Option<Category> {
+    cfg.categories
+        .entries
+        .iter()
+        .find(|e| e.id.is(s) || e.name.eq_ignore_ascii_case(s))
+        .map(|e| e.id.clone())
+}
+
+impl QuickAdd {
+    /// Split `input` into the title and the fields given by its quick-add words.
+    pub fn parse(input: &str, cfg: &Config, today: u32) -> Self {
+        let mut out = QuickAdd::default();
+        let mut words: Vec<&str> = Vec::new();
+        for w in input.split_whitespace() {
+            if let Some(min) = parse_duration(w) {
+                out.estimate_min = Some(min);
+            } else if let Some(cat) = w.strip_prefix('@').and_then(|c| find_category(c, cfg)) {
+                out.category = Some(cat);
+            } else if let Some(Ok((h, m))) =
+                w.strip_prefix('^').map(crate::config::parse_hhmm_or_compact)
+            {
+                out.fixed_start_min = Some(h * 60 + m);
+            } else if let Some(ymd) = w.strip_prefix('>').and_then(|d| parse_date(d, today)) {
+                out.planned_ymd = Some(ymd);
+            } else if let Some(tag) = w.strip_prefix('#').and_then(crate::tags::normalize_tag) {
+                crate::tags::add_tag(&mut out.tags, tag);
+            } else {
+                words.push(w);
+            }
+        }
+        out.title = words.join(" ");
+        out
+    }
+
+    /// Whether any field besides the title was given.
+    pub fn has_fields(&self) -> bool {
+        self.estimate_min.is_some()
+            || self.category.is_some()
+            || self.fixed_start_min.is_some()
+            || self.planned_ymd.is_some()
+            || !self.tags.is_empty()
+    }
+
+    /// The task to create. Dates before `today` are clamped to today.
+    pub fn into_task(self, default_estimate: u16, today: u32) -> Task {
+        let mut t = Task::new(&self.title, self.estimate_min.unwrap_or(default_estimate));
+        t.planned_ymd = self.planned_ymd.unwrap_or(today).max(today);
+        if let Some(cat) = self.category {
+            t.category = cat;
+        }
+        t.fixed_start_min = self.fixed_start_min;
+        t.tags = self.tags;
+        t
+    }
+
+    /// Short summary of the parsed fields for previews (`45m  @work  ^14:00  >tomorrow  #a`).
+    pub fn summary(&self, cfg: &Config, today: u32) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(m) = self.estimate_min {
+            parts.push(format!("{m}m"));
+        }
+        if let Some(c) = &self.category {
+            parts.push(format!("@{}", cfg.category_name(c)));
+        }
+        if let Some(s) = self.fixed_start_min {
+            parts.push(format!("^{:02}:{:02}", s / 60, s % 60));
+        }
+        if let Some(d) = self.planned_ymd {
+            let label = if d <= today {
+                "today".to_string()
+            } else if d == crate::date::add_days_to_ymd(today, 1) {
+                "tomorrow".to_string()
+            } else {
+                crate::date::format_ymd(d)
+            };
+            parts.push(format!(">{label}"));
+        }
+        parts.extend(self.tags.iter().map(|t| format!("#{t}")));
+        parts.join("  ")
+    }
+}
+
+/// Non-empty lines of a paste with Markdown list markers (`- [ ]`, `- [x]`, `*`, `+`, `1.`,
+/// `1)`) removed.
+pub fn paste_lines(text: &str) -> Vec<String> {
+    text.lines().map(strip_list_marker).filter(|l| !l.is_empty()).map(str::to_string).collect()
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let mut s = line.trim();
+    let bullet = s.strip_prefix(['-', '*', '+']).filter(|r| r.is_empty() || r.starts_with(' '));
+    let numbered = || {
+        let digits = s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        (digits > 0)
+            .then(|| s[digits..].strip_prefix(['.', ')']))
+            .flatten()
+            .filter(|r| r.is_empty() || r.starts_with(' '))
+    };
+    if let Some(rest) = bullet.or_else(numbered) {
+        s = rest.trim_start();
+    }
+    for checkbox in ["[ ]", "[x]", "[X]"] {
+        if let Some(rest) = s.strip_prefix(checkbox) {
+            return rest.trim_start();
+        }
+    }
+    s
+}
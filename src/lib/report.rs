@@ -0,0 +1,260 @@
+//! Daily report in Markdown (`chute report day [date]`, `:report`).
+//! - Tasks come from the day's list (`timelog::collect`): Today, a Past day or a Future date.
+//! - The text is a template with `{{name}}` placeholders (`PLACEHOLDERS`); `[report]
+//!   template` in the config replaces `DEFAULT_TEMPLATE`. Unknown placeholders stay as
+//!   written.
+//! - "ESD at day start" schedules the tasks that are not interruptions from
+//!   `day_start_minutes`, honouring fixed starts, like the list does before anything ran.
+//! - Times come from the sessions: the first start, and the last end of a finished session.
+
+use crate::app::App;
+use crate::task::{Task, TaskState};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_TEMPLATE: &str = "\
+# Daily report {{date}} ({{weekday}})
+
+- Work: {{first_start}} - {{last_finish}}
+- ESD at day start: {{planned_end}}, actual end: {{last_finish}} ({{end_delta}})
+- Total: estimate {{estimate_total}}, actual {{actual_total}} ({{delta_total}})
+
+## Plan vs actual
+
+{{tasks}}
+
+## By category
+
+{{categories}}
+
+## By tag
+
+{{tags}}
+
+## Interruptions
+
+{{interruptions}}
+";
+
+/// Names usable as `{{name}}` in a template.
+pub const PLACEHOLDERS: &[&str] = &[
+    "date",
+    "weekday",
+    "tasks",
+    "categories",
+    "tags",
+    "interruptions",
+    "first_start",
+    "last_finish",
+    "planned_end",
+    "end_delta",
+    "estimate_total",
+    "actual_total",
+    "delta_total",
+];
+
+/// `[report]` settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportConfig {
+    /// Markdown template file; `DEFAULT_TEMPLATE` when `None`.
+    pub template: Option<PathBuf>,
+}
+
+/// The template to use: `path`, else the configured file, else `DEFAULT_TEMPLATE`.
+pub fn template(cfg: &ReportConfig, path: Option<&Path>) -> Result<String> {
+    match path.or(cfg.template.as_deref()) {
+        Some(p) => std::fs::read_to_string(p)
+            .with_context(|| format!("read report template {}", p.display())),
+        None => Ok(DEFAULT_TEMPLATE.to_string()),
+    }
+}
+
+/// The tasks listed under `ymd` (empty when the day has none).
+pub fn tasks_for_day(app: &App, today: u32, ymd: u32) -> Result<Vec<Task>> {
+    let days = crate::timelog::collect(app, today, ymd, ymd)?;
+    Ok(days.into_iter().next().map(|(_, tasks)| tasks).unwrap_or_default())
+}
+
+/// Fill `template` for the day `ymd` with `tasks` in list order.
+pub fn render(app: &App, ymd: u32, tasks: &[Task], template: &str) -> String {
+    let planned: Vec<Task> = tasks.iter().filter(|t| !t.interrupt).cloned().collect();
+    let planned_end = crate::ui::compute_planned_starts(app.config.day_start_minutes, &planned)
+        .last()
+        .zip(planned.last())
+        .map(|(start, t)| u32::from(*start) + u32::from(t.estimate_min));
+    // Minutes from midnight of `ymd`; a session past midnight ends after 24:00
+    let spans: Vec<(u32, Option<u32>)> = tasks
+        .iter()
+        .flat_map(|t| &t.sessions)
+        .map(|s| {
+            let start = u32::from(s.start_min);
+            let end = s.end_min.map(|e| start + (u32::from(e) + 24 * 60 - start) % (24 * 60));
+            (start, end)
+        })
+        .collect();
+    let first_start = spans.iter().map(|(s, _)| *s).min();
+    let last_finish = spans.iter().filter_map(|(_, e)| *e).max();
+    let estimate_total: u32 = tasks.iter().map(|t| u32::from(t.estimate_min)).sum();
+    let actual_total: u32 = tasks.iter().map(|t| u32::from(t.actual_min)).sum();
+
+    let value = |name: &str| -> Option<String> {
+        Some(match name {
+            "date" => crate::date::format_ymd(ymd),
+            "weekday" => crate::date::weekday_short_en(ymd).to_string(),
+            "tasks" => task_table(app, tasks),
+            "categories" => category_table(app, tasks),
+            "tags" => tag_table(tasks),
+            "interruptions" => interruptions(tasks),
+            "first_start" => clock(first_start),
+            "last_finish" => clock(last_finish),
+            "planned_end" => clock(planned_end),
+            "end_delta" => match (planned_end, last_finish) {
+                (Some(p), Some(a)) => delta(i64::from(a) - i64::from(p)),
+                _ => "-".to_string(),
+            },
+            "estimate_total" => format!("{estimate_total}m"),
+            "actual_total" => format!("{actual_total}m"),
+            "delta_total" => delta(i64::from(actual_total) - i64::from(estimate_total)),
+            _ => return None,
+        })
+    };
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        match after.find("}}").and_then(|close| Some((close, value(after[..close].trim())?))) {
+            Some((close, v)) => {
+                out.push_str(&v);
+                rest = &after[close + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn task_table(app: &App, tasks: &[Task]) -> String {
+    if tasks.is_empty() {
+        return "No tasks.".to_string();
+    }
+    let mut lines = vec![
+        "| # | Task | Category | Est | Act | Delta | State |".to_string(),
+        "|---:|---|---|---:|---:|---:|---|".to_string(),
+    ];
+    for (i, t) in tasks.iter().enumerate() {
+        let title = if t.interrupt { format!("{} (interrupt)", t.title) } else { t.title.clone() };
+        // Nothing to compare before the task ran
+        let d = if t.state == TaskState::Planned && t.actual_min == 0 {
+            "-".to_string()
+        } else {
+            delta(i64::from(t.actual_min) - i64::from(t.estimate_min))
+        };
+        lines.push(format!(
+            "| {} | {} | {} | {}m | {}m | {} | {} |",
+            i + 1,
+            cell(&title),
+            cell(&app.config.category_name(&t.category)),
+            t.estimate_min,
+            t.actual_min,
+            d,
+            crate::task::state_str(t.state)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn category_table(app: &App, tasks: &[Task]) -> String {
+    if tasks.is_empty() {
+        return "No tasks.".to_string();
+    }
+    // Config order, so reports of different days line up
+    let mut totals: Vec<(String, u32, u32)> = Vec::new();
+    for e in &app.config.categories.entries {
+        let of_cat: Vec<&Task> = tasks.iter().filter(|t| t.category == e.id).collect();
+        if !of_cat.is_empty() {
+            let est = of_cat.iter().map(|t| u32::from(t.estimate_min)).sum();
+            let act = of_cat.iter().map(|t| u32::from(t.actual_min)).sum();
+            totals.push((e.name.clone(), est, act));
+        }
+    }
+    // Categories no longer in the config
+    for t in tasks {
+        let name = app.config.category_name(&t.category);
+        if app.config.categories.get(&t.category).is_none() {
+            match totals.iter_mut().find(|(n, _, _)| *n == name) {
+                Some(row) => {
+                    row.1 += u32::from(t.estimate_min);
+                    row.2 += u32::from(t.actual_min);
+                }
+                None => totals.push((name, u32::from(t.estimate_min), u32::from(t.actual_min))),
+            }
+        }
+    }
+    let mut lines =
+        vec!["| Category | Est | Act | Delta |".to_string(), "|---|---:|---:|---:|".to_string()];
+    for (name, est, act) in totals {
+        let d = delta(i64::from(act) - i64::from(est));
+        lines.push(format!("| {} | {est}m | {act}m | {d} |", cell(&name)));
+    }
+    lines.join("\n")
+}
+
+fn tag_table(tasks: &[Task]) -> String {
+    if tasks.iter().all(|t| t.tags.is_empty()) {
+        return "No tags.".to_string();
+    }
+    let mut lines = vec![
+        "| Tag | Tasks | Est | Act | Delta |".to_string(),
+        "|---|---:|---:|---:|---:|".to_string(),
+    ];
+    for g in crate::tags::group_by_tag(tasks) {
+        let name = g.tag.map_or_else(|| "(untagged)".to_string(), |t| format!("#{t}"));
+        let (est, act) = (g.estimate_min, g.actual_min);
+        let d = delta(i64::from(act) - i64::from(est));
+        lines.push(format!("| {} | {} | {est}m | {act}m | {d} |", cell(&name), g.tasks.len()));
+    }
+    lines.join("\n")
+}
+
+fn interruptions(tasks: &[Task]) -> String {
+    let list: Vec<String> = tasks
+        .iter()
+        .filter(|t| t.interrupt)
+        .map(|t| {
+            let at = clock(t.sessions.first().map(|s| u32::from(s.start_min)));
+            format!("- {at} {} ({}m)", t.title, t.actual_min)
+        })
+        .collect();
+    if list.is_empty() {
+        return "None.".to_string();
+    }
+    let total: u32 = tasks.iter().filter(|t| t.interrupt).map(|t| u32::from(t.actual_min)).sum();
+    format!("{}\n\nTotal: {} ({total}m)", list.join("\n"), list.len())
+}
+
+/// `HH:MM`, with `+1d` past midnight; `-` when unknown.
+fn clock(min: Option<u32>) -> String {
+    match min {
+        Some(m) if m >= 24 * 60 => format!("{:02}:{:02} +1d", m / 60 % 24, m % 60),
+        Some(m) => format!("{:02}:{:02}", m / 60, m % 60),
+        None => "-".to_string(),
+    }
+}
+
+/// Signed minutes: `+15m`, `-5m`, `±0m`.
+fn delta(min: i64) -> String {
+    match min {
+        0 => "±0m".to_string(),
+        m if m > 0 => format!("+{m}m"),
+        m => format!("{m}m"),
+    }
+}
+
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
@@ -0,0 +1,284 @@
+//! Routines: recurring task templates (TaskChute "ルーチン").
+//! - A `Routine` carries the task fields to copy plus a `Recurrence` rule.
+//! - `App` instantiates due routines into Today once per date (startup and day rollover).
+//! - Generated tasks keep `Task::routine_id` so they can be traced back to their routine.
+//! - Finished instances feed `Routine::history`; `EstimatePolicy` derives the next estimate.
+
+use serde::{Deserialize, Serialize};
+
+use crate::task::{Category, Task};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// Monday = 0 .. Sunday = 6 (same as `date::weekday_index`).
+    pub fn index(self) -> u8 {
+        match self {
+            Weekday::Mon => 0,
+            Weekday::Tue => 1,
+            Weekday::Wed => 2,
+            Weekday::Thu => 3,
+            Weekday::Fri => 4,
+            Weekday::Sat => 5,
+            Weekday::Sun => 6,
+        }
+    }
+
+    /// Parse English short/long names case-insensitively (e.g., "mon", "Monday").
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mon" | "monday" => Some(Weekday::Mon),
+            "tue" | "tuesday" => Some(Weekday::Tue),
+            "wed" | "wednesday" => Some(Weekday::Wed),
+            "thu" | "thursday" => Some(Weekday::Thu),
+            "fri" | "friday" => Some(Weekday::Fri),
+            "sat" | "saturday" => Some(Weekday::Sat),
+            "sun" | "sunday" => Some(Weekday::Sun),
+            _ => None,
+        }
+    }
+}
+
+/// When a routine produces a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Every day.
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    /// Specific weekdays (e.g., Mon/Wed/Fri).
+    Weekly { days: Vec<Weekday> },
+    /// Every `n` days counted from the routine's `anchor_ymd`.
+    EveryNDays { n: u16 },
+    /// A fixed day of month; days past the month end fall on its last day.
+    Monthly { day: u8 },
+}
+
+impl Recurrence {
+    /// Parse a compact rule as typed in the command palette:
+    /// `daily`, `weekdays`, `mon,wed,fri` (optionally prefixed by `weekly`),
+    /// `every 3` / `every 3d`, `monthly 15`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut it = s.split_whitespace();
+        let head = it.next()?.to_ascii_lowercase();
+        let arg = it.next();
+        if it.next().is_some() {
+            return None;
+        }
+        match (head.as_str(), arg) {
+            ("daily", None) => Some(Recurrence::Daily),
+            ("weekdays", None) => Some(Recurrence::Weekdays),
+            ("weekly", Some(days)) => parse_weekday_list(days),
+            ("every", Some(n)) => {
+                let n: u16 = n.trim_end_matches('d').parse().ok()?;
+                (n > 0).then_some(Recurrence::EveryNDays { n })
+            }
+            ("monthly", Some(d)) => {
+                let day: u8 = d.parse().ok()?;
+                (1..=31).contains(&day).then_some(Recurrence::Monthly { day })
+            }
+            (_, None) => parse_weekday_list(&head),
+            _ => None,
+        }
+    }
+
+    /// Whether the rule fires on `ymd`, given the routine's anchor date.
+    pub fn occurs_on(&self, ymd: u32, anchor_ymd: u32) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekdays => crate::date::weekday_index(ymd).is_some_and(|wd| wd < 5),
+            Recurrence::Weekly { days } => crate::date::weekday_index(ymd)
+                .is_some_and(|wd| days.iter().any(|d| d.index() == wd)),
+            Recurrence::EveryNDays { n } => crate::date::days_between(anchor_ymd, ymd)
+                .is_some_and(|diff| diff >= 0 && diff % ((*n).max(1) as i64) == 0),
+            Recurrence::Monthly { day } => {
+                let dom = ymd % 100;
+                let last = crate::date::days_in_month(ymd);
+                dom == (*day as u32).min(last)
+            }
+        }
+    }
+}
+
+fn parse_weekday_list(s: &str) -> Option<Recurrence> {
+    let mut days: Vec<Weekday> = Vec::new();
+    for part in s.split(',').filter(|p| !p.trim().is_empty()) {
+        let d = Weekday::parse(part)?;
+        if !days.contains(&d) {
+            days.push(d);
+        }
+    }
+    if days.is_empty() {
+        return None;
+    }
+    days.sort_by_key(|d| d.index());
+    Some(Recurrence::Weekly { days })
+}
+
+/// Maximum number of past actuals kept per routine.
+pub const HISTORY_CAP: usize = 60;
+
+/// How a routine's next estimate is derived from logged actuals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EstimatePolicy {
+    /// Keep the estimate as entered (no learning).
+    Fixed,
+    /// Use the most recent actual.
+    Last,
+    /// Rounded mean over the last `window` runs.
+    Mean { window: u16 },
+    /// Median over the last `window` runs (upper median for even counts).
+    Median { window: u16 },
+    /// Nearest-rank percentile `p` (1..=100) over the last `window` runs.
+    Percentile { p: u8, window: u16 },
+}
+
+impl Default for EstimatePolicy {
+    fn default() -> Self {
+        EstimatePolicy::Mean { window: 5 }
+    }
+}
+
+impl EstimatePolicy {
+    /// Parse `fixed`, `last`, `mean[:N]`, `median[:N]` or `pXX[:N]` (e.g., `p80:10`).
+    /// Window defaults to 5 when omitted.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let (head, window) = match s.split_once(':') {
+            Some((h, w)) => (h.to_string(), w.parse::<u16>().ok().filter(|w| *w > 0)?),
+            None => (s.clone(), 5),
+        };
+        match head.as_str() {
+            "fixed" | "none" => Some(EstimatePolicy::Fixed),
+            "last" => Some(EstimatePolicy::Last),
+            "mean" | "avg" => Some(EstimatePolicy::Mean { window }),
+            "median" => Some(EstimatePolicy::Median { window }),
+            h if h.starts_with('p') => {
+                let p: u8 = h[1..].parse().ok()?;
+                (1..=100).contains(&p).then_some(EstimatePolicy::Percentile { p, window })
+            }
+            _ => None,
+        }
+    }
+
+    /// Derive an estimate from `history` (oldest first). `None` when there is nothing to learn.
+    pub fn estimate(&self, history: &[u16]) -> Option<u16> {
+        let recent = |w: u16| &history[history.len().saturating_sub(w as usize)..];
+        match *self {
+            EstimatePolicy::Fixed => None,
+            EstimatePolicy::Last => history.last().copied(),
+            EstimatePolicy::Mean { window } => {
+                let xs = recent(window);
+                if xs.is_empty() {
+                    return None;
+                }
+                let sum: u32 = xs.iter().map(|&v| v as u32).sum();
+                let n = xs.len() as u32;
+                Some(((sum + n / 2) / n) as u16)
+            }
+            EstimatePolicy::Median { window } => {
+                let mut xs = recent(window).to_vec();
+                xs.sort_unstable();
+                xs.get(xs.len() / 2).copied()
+            }
+            EstimatePolicy::Percentile { p, window } => percentile(recent(window), p),
+        }
+    }
+}
+
+fn percentile(xs: &[u16], p: u8) -> Option<u16> {
+    if xs.is_empty() {
+        return None;
+    }
+    let mut sorted = xs.to_vec();
+    sorted.sort_unstable();
+    // Nearest-rank: ceil(p/100 * n), 1-based
+    let n = sorted.len();
+    let rank = ((p as usize) * n).div_ceil(100).clamp(1, n);
+    Some(sorted[rank - 1])
+}
+
+/// A recurring task template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Routine {
+    /// Stable identifier referenced by generated tasks (`Task::routine_id`).
+    pub id: u32,
+    pub title: String,
+    pub estimate_min: u16,
+    #[serde(default)]
+    pub category: Category,
+    #[serde(default)]
+    pub fixed_start_min: Option<u16>,
+    pub recurrence: Recurrence,
+    /// First date the routine may fire (YYYYMMDD); also the origin for `EveryNDays`.
+    pub anchor_ymd: u32,
+    /// Last date a task was generated for (YYYYMMDD). Guards against duplicates.
+    #[serde(default)]
+    pub last_generated_ymd: Option<u32>,
+    /// Actual minutes of finished instances, oldest first (capped at `HISTORY_CAP`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<u16>,
+    /// Per-routine override of `Config::routine_estimate`.
+    #[serde(default)]
+    pub estimate_policy: Option<EstimatePolicy>,
+}
+
+impl Routine {
+    /// Build a routine from an existing task's title/estimate/category/fixed start.
+    pub fn from_task(id: u32, task: &Task, recurrence: Recurrence, anchor_ymd: u32) -> Self {
+        Self {
+            id,
+            title: task.title.clone(),
+            estimate_min: task.estimate_min,
+            category: task.category.clone(),
+            fixed_start_min: task.fixed_start_min,
+            recurrence,
+            anchor_ymd,
+            last_generated_ymd: None,
+            history: Vec::new(),
+            estimate_policy: None,
+        }
+    }
+
+    /// Log a finished instance's actual minutes and refresh `estimate_min` using the
+    /// routine's own policy, falling back to `default_policy`.
+    pub fn record_actual(&mut self, actual_min: u16, default_policy: EstimatePolicy) {
+        self.history.push(actual_min);
+        if self.history.len() > HISTORY_CAP {
+            let excess = self.history.len() - HISTORY_CAP;
+            self.history.drain(..excess);
+        }
+        let policy = self.estimate_policy.unwrap_or(default_policy);
+        if let Some(est) = policy.estimate(&self.history) {
+            self.estimate_min = est;
+        }
+    }
+
+    /// True when a task should be generated for `ymd` and has not been yet.
+    pub fn is_due(&self, ymd: u32) -> bool {
+        ymd >= self.anchor_ymd
+            && self.last_generated_ymd.map_or(true, |d| d < ymd)
+            && self.recurrence.occurs_on(ymd, self.anchor_ymd)
+    }
+
+    /// Create the task instance for `ymd`.
+    pub fn instantiate(&self, ymd: u32) -> Task {
+        let mut t = Task::new(&self.title, self.estimate_min);
+        t.category = self.category.clone();
+        t.fixed_start_min = self.fixed_start_min;
+        t.planned_ymd = ymd;
+        t.routine_id = Some(self.id);
+        t
+    }
+}
@@ -0,0 +1,75 @@
+//! Incremental `/` search over a view's tasks.
+//! - The query is split on whitespace; every term must match (AND).
+//! - A term matches the title or the category id/name case-insensitively; `#term` matches
+//!   tags only. `is:done` (or the bare keyword `planned`, `active`, `paused`, `done`) matches
+//!   the state, so short words like `d` still only search titles.
+//! - `title_match_ranges` gives the byte ranges to highlight in the title.
+
+use crate::config::Config;
+use crate::task::{state_str, Task};
+
+fn terms(query: &str) -> impl Iterator<Item = &str> {
+    query.split_whitespace()
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Byte ranges in `hay` where `needle` occurs, compared char by char case-insensitively.
+fn find_all(hay: &str, needle: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = needle.chars().map(fold).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = hay.char_indices().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= chars.len() {
+        if chars[i..i + needle.len()].iter().zip(&needle).all(|(&(_, c), &n)| fold(c) == n) {
+            let end = chars.get(i + needle.len()).map_or(hay.len(), |&(b, _)| b);
+            out.push((chars[i].0, end));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+fn contains(hay: &str, needle: &str) -> bool {
+    !find_all(hay, needle).is_empty()
+}
+
+/// Whether `task` matches every term of `query` (an empty query matches everything).
+pub fn matches(task: &Task, query: &str, cfg: &Config) -> bool {
+    terms(query).all(|term| {
+        if let Some(tag) = term.strip_prefix('#') {
+            return task.tags.iter().any(|t| contains(t, tag));
+        }
+        if let Some(state) = term.strip_prefix("is:") {
+            return state_str(task.state).eq_ignore_ascii_case(state);
+        }
+        contains(&task.title, term)
+            || contains(task.category.id(), term)
+            || contains(&cfg.category_name(&task.category), term)
+            || state_str(task.state).eq_ignore_ascii_case(term)
+    })
+}
+
+/// Sorted, non-overlapping byte ranges of `title` matched by the query terms.
+pub fn title_match_ranges(title: &str, query: &str) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = terms(query)
+        .filter(|t| !t.starts_with('#') && !t.starts_with("is:"))
+        .flat_map(|t| find_all(title, t))
+        .collect();
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (s, e) in ranges {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _ => merged.push((s, e)),
+        }
+    }
+    merged
+}
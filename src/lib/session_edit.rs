@@ -0,0 +1,149 @@
+//! Session editor: a working copy of one task's `Session`s, edited in a popup and
+//! written back on OK. Times are minutes since local midnight like `Task.sessions`.
+//! - A running session (`end_min = None`) keeps its open end and always stays last.
+//! - Closed sessions keep `start <= end` within the day.
+
+use crate::task::Session;
+
+/// Last selectable minute of the day (23:59).
+pub const LAST_MINUTE: u16 = 23 * 60 + 59;
+/// Length of a session inserted with `insert_after`.
+pub const INSERT_LEN_MIN: u16 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionField {
+    #[default]
+    Start,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEditor {
+    /// Title of the task being edited (display only).
+    pub title: String,
+    pub sessions: Vec<Session>,
+    pub cursor: usize,
+    pub field: SessionField,
+}
+
+impl SessionEditor {
+    /// Start editing with the latest session selected.
+    pub fn new(title: &str, sessions: Vec<Session>) -> Self {
+        let cursor = sessions.len().saturating_sub(1);
+        Self { title: title.to_string(), sessions, cursor, field: SessionField::Start }
+    }
+
+    pub fn selected(&self) -> Option<&Session> {
+        self.sessions.get(self.cursor)
+    }
+
+    pub fn select_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn select_down(&mut self) {
+        if self.cursor + 1 < self.sessions.len() {
+            self.cursor += 1;
+        }
+    }
+
+    pub fn toggle_field(&mut self) {
+        self.field = match self.field {
+            SessionField::Start => SessionField::End,
+            SessionField::End => SessionField::Start,
+        };
+    }
+
+    /// Minutes under the cursor; `None` for the open end of a running session.
+    pub fn selected_minutes(&self) -> Option<u16> {
+        let s = self.selected()?;
+        match self.field {
+            SessionField::Start => Some(s.start_min),
+            SessionField::End => s.end_min,
+        }
+    }
+
+    /// Set the field under the cursor, clamped so the session keeps `start <= end`.
+    /// The open end of a running session is left alone.
+    pub fn set_minutes(&mut self, minutes: u16) {
+        let field = self.field;
+        let Some(s) = self.sessions.get_mut(self.cursor) else {
+            return;
+        };
+        match field {
+            SessionField::Start => {
+                s.start_min = minutes.min(s.end_min.unwrap_or(LAST_MINUTE));
+            }
+            SessionField::End => {
+                if s.end_min.is_some() {
+                    s.end_min = Some(minutes.clamp(s.start_min, LAST_MINUTE));
+                }
+            }
+        }
+    }
+
+    /// Shift the field under the cursor by `delta` minutes.
+    pub fn adjust(&mut self, delta: i16) {
+        if let Some(cur) = self.selected_minutes() {
+            let next = (cur as i32 + delta as i32).clamp(0, LAST_MINUTE as i32) as u16;
+            self.set_minutes(next);
+        }
+    }
+
+    /// Insert a missed session right after the selected one and select it.
+    /// It starts where the selected session ended (or at `default_start` when the list is
+    /// empty); next to a running session it is placed just before it instead.
+    pub fn insert_after(&mut self, default_start: u16) {
+        let after = |start: u16| start.saturating_add(INSERT_LEN_MIN).min(LAST_MINUTE);
+        let (at, start, end) = match self.selected() {
+            None => {
+                let start = default_start.min(LAST_MINUTE);
+                (0, start, after(start))
+            }
+            Some(&Session { start_min, end_min: None }) => {
+                (self.cursor, start_min.saturating_sub(INSERT_LEN_MIN), start_min)
+            }
+            Some(&Session { end_min: Some(end), .. }) => (self.cursor + 1, end, after(end)),
+        };
+        // Never insert behind the running session
+        let at = match self.sessions.iter().position(|s| s.end_min.is_none()) {
+            Some(open) => at.min(open),
+            None => at,
+        };
+        self.sessions.insert(at, Session { start_min: start, end_min: Some(end) });
+        self.cursor = at;
+        self.field = SessionField::Start;
+    }
+
+    /// Split the selected closed session at its midpoint. Returns false when the
+    /// session is running or shorter than 2 minutes.
+    pub fn split_selected(&mut self) -> bool {
+        let Some(&Session { start_min, end_min: Some(end) }) = self.selected() else {
+            return false;
+        };
+        if end.saturating_sub(start_min) < 2 {
+            return false;
+        }
+        let mid = start_min + (end - start_min) / 2;
+        self.sessions[self.cursor].end_min = Some(mid);
+        self.sessions.insert(self.cursor + 1, Session { start_min: mid, end_min: Some(end) });
+        true
+    }
+
+    /// Delete the selected closed session. The running session can only be ended by pausing.
+    pub fn delete_selected(&mut self) -> bool {
+        if !matches!(self.selected(), Some(Session { end_min: Some(_), .. })) {
+            return false;
+        }
+        self.sessions.remove(self.cursor);
+        self.cursor = self.cursor.min(self.sessions.len().saturating_sub(1));
+        true
+    }
+
+    /// Edited sessions in chronological order, with the running session last.
+    pub fn into_sessions(self) -> Vec<Session> {
+        let mut sessions = self.sessions;
+        sessions.sort_by_key(|s| (s.end_min.is_none(), s.start_min));
+        sessions
+    }
+}
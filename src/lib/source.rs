@@ -0,0 +1,111 @@
+//! Importing tasks from external task managers (`chute import <source>`).
+//! - A `TaskSource` fetches `RemoteTask`s; `import` adds them to Today or Future.
+//! - Due dates become `planned_ymd` (overdue tasks land in Today) and a due time becomes
+//!   the fixed start. Durations become estimates.
+//! - Labels naming a category (id or name) set the category, `est_25m` labels set the
+//!   estimate when there is no duration, and the other labels become tags.
+//! - Imported tasks keep `Task::remote_id` (`<source>:<id>`), so re-imports skip them.
+
+use crate::app::{App, View};
+use crate::config::Config;
+use crate::date::today_ymd;
+use crate::task::Task;
+use anyhow::Result;
+
+/// Estimate for imported tasks without a duration or `est_` label.
+pub const DEFAULT_ESTIMATE_MIN: u16 = 25;
+
+/// A task as fetched from a source, before it becomes a `Task`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteTask {
+    /// Id in the source (without the `<source>:` prefix).
+    pub id: String,
+    pub title: String,
+    pub due_ymd: Option<u32>,
+    /// Local due time in minutes since midnight.
+    pub due_min: Option<u16>,
+    pub estimate_min: Option<u16>,
+    pub labels: Vec<String>,
+}
+
+/// Something tasks can be imported from.
+pub trait TaskSource {
+    /// Prefix of `Task::remote_id` for tasks from this source (`todoist`).
+    fn name(&self) -> &'static str;
+    /// Tasks to import, in the source's order.
+    fn fetch(&self) -> Result<Vec<RemoteTask>>;
+}
+
+/// `Task::remote_id` of task `id` from `source`.
+pub fn remote_id(source: &str, id: &str) -> String {
+    format!("{source}:{id}")
+}
+
+impl RemoteTask {
+    /// The task to create. Overdue tasks are planned for today without a fixed start.
+    pub fn into_task(self, source: &str, cfg: &Config, today: u32) -> Task {
+        let mut t = Task::new(&self.title, DEFAULT_ESTIMATE_MIN);
+        let due = self.due_ymd.unwrap_or(today);
+        t.planned_ymd = due.max(today);
+        if due >= today {
+            t.fixed_start_min = self.due_min;
+        }
+        let mut label_estimate = None;
+        for label in &self.labels {
+            if let Some(cat) = crate::quick_add::find_category(label, cfg) {
+                t.category = cat;
+            } else if let Some(m) =
+                label.strip_prefix("est_").and_then(crate::quick_add::parse_duration)
+            {
+                label_estimate = Some(m);
+            } else if let Some(tag) = crate::tags::normalize_tag(label) {
+                crate::tags::add_tag(&mut t.tags, tag);
+            }
+        }
+        if let Some(m) = self.estimate_min.or(label_estimate) {
+            t.estimate_min = m;
+        }
+        t.remote_id = Some(remote_id(source, &self.id));
+        t
+    }
+}
+
+/// Outcome of `import`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Imported {
+    /// New tasks with the list each went to, in fetch order.
+    pub added: Vec<(View, Task)>,
+    /// Fetched tasks already present in Today or Future.
+    pub skipped: usize,
+}
+
+/// Add the fetched `items` from `source` to `app`, skipping ones imported before.
+pub fn import(app: &mut App, source: &str, items: Vec<RemoteTask>) -> Imported {
+    let today = today_ymd();
+    let mut known: Vec<String> = app
+        .day
+        .tasks
+        .iter()
+        .chain(app.tomorrow_tasks())
+        .filter_map(|t| t.remote_id.clone())
+        .collect();
+    let mut out = Imported::default();
+    for item in items {
+        let id = remote_id(source, &item.id);
+        if known.contains(&id) {
+            out.skipped += 1;
+            continue;
+        }
+        known.push(id);
+        let task = item.into_task(source, &app.config, today);
+        let (view, _) = app.add_planned_task(task.clone());
+        out.added.push((view, task));
+    }
+    out
+}
+
+/// Fetch from `source` and import into `app`.
+pub fn import_from(app: &mut App, source: &dyn TaskSource) -> Result<Imported> {
+    let items = source.fetch()?;
+    Ok(import(app, source.name(), items))
+}
@@ -1,10 +1,15 @@
 use crate::app::App;
 use crate::config::Config;
+use crate::history::HistoryStore;
+use crate::journal;
+use crate::routine::Routine;
 use crate::task::Task;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Snapshot of user-visible task state.
 /// - versioned for forwards/backwards compatibility
@@ -18,11 +23,28 @@ pub struct SnapshotV1 {
     pub future: Vec<Task>,
     #[serde(default)]
     pub past: Vec<Task>,
+    /// Recurring task templates (see `routine`). Omitted when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routines: Vec<Routine>,
+    /// Last journal entry already reflected here; newer entries are replayed on load.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub journal_seq: u64,
+}
+
+fn is_zero(v: &u64) -> bool {
+    *v == 0
 }
 
 impl Default for SnapshotV1 {
     fn default() -> Self {
-        Self { version: 1, today: vec![], future: vec![], past: vec![] }
+        Self {
+            version: 1,
+            today: vec![],
+            future: vec![],
+            past: vec![],
+            routines: vec![],
+            journal_seq: 0,
+        }
     }
 }
 
@@ -32,15 +54,36 @@ impl SnapshotV1 {
             version: 1,
             today: app.day.tasks.clone(),
             future: app.tomorrow_tasks().clone(),
-            past: app.history_tasks().clone(),
+            // With a history store, Past lives in per-date files instead
+            past: match app.history_store() {
+                Some(_) => vec![],
+                None => app.history_tasks().clone(),
+            },
+            routines: app.routines().clone(),
+            journal_seq: app.journal_seq(),
         }
     }
 
     pub fn into_app(self, config: Config) -> App {
         let mut app = App::with_config(config);
+        // Routines first so that startup generation in `apply_snapshot` can see them
+        app.set_routines(self.routines);
+        app.set_journal_seq(self.journal_seq);
         app.apply_snapshot(self.today, self.future, self.past);
         app
     }
+
+    /// Like `into_app`, but Past is backed by `store`. A legacy `past` list is migrated
+    /// into day files first (idempotent), so the next save drops it from the snapshot.
+    pub fn into_app_with_history(self, config: Config, store: HistoryStore) -> Result<App> {
+        store.migrate(self.past).context("migrate past into history store")?;
+        let mut app = App::with_config(config);
+        app.attach_history_store(store);
+        app.set_routines(self.routines);
+        app.set_journal_seq(self.journal_seq);
+        app.apply_snapshot(self.today, self.future, vec![]);
+        Ok(app)
+    }
 }
 
 /// Serialize current app state into a TOML string.
@@ -55,26 +98,88 @@ pub fn load_from_str(s: &str, config: Config) -> Result<App> {
     Ok(snap.into_app(config))
 }
 
+/// Write the Past days changed in this session to the app's history store (if any).
+/// Days that could not be loaded are never overwritten.
+pub fn save_history(app: &App) -> Result<()> {
+    let Some(store) = app.history_store() else {
+        return Ok(());
+    };
+    for &ymd in app.history_dirty_days() {
+        if !app.history_day_loaded(ymd) {
+            return Err(anyhow::anyhow!(
+                "history for {} could not be loaded; refusing to overwrite {}",
+                crate::date::format_ymd(ymd),
+                store.day_path(ymd).display()
+            ));
+        }
+        store.save_day(ymd, &app.history_tasks_for_day(ymd))?;
+    }
+    Ok(())
+}
+
 /// Save to a file path, creating parent directories if missing.
+/// History days are written before the snapshot so a failure never drops swept tasks.
 pub fn save_to_path<P: AsRef<Path>>(app: &App, path: P) -> Result<()> {
+    save_history(app)?;
     let s = save_to_string(app)?;
-    if let Some(parent) = path.as_ref().parent() {
-        fs::create_dir_all(parent).ok();
+    write_atomic(path.as_ref(), s.as_bytes()).context("write snapshot file")
+}
+
+/// Write via a synced temp file in the same directory, then rename over `path`,
+/// so readers (and crashes) only ever see the old or the new content.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir).ok();
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("snapshot");
+    let tmp = dir.join(format!(".{}.tmp", name));
+    {
+        let mut f = fs::File::create(&tmp).context("create temp file")?;
+        f.write_all(contents).context("write temp file")?;
+        f.sync_all().context("sync temp file")?;
+    }
+    fs::rename(&tmp, path).context("rename temp file into place")?;
+    // Persist the rename itself; not supported everywhere, so best effort
+    #[cfg(unix)]
+    if let Ok(d) = fs::File::open(dir) {
+        let _ = d.sync_all();
     }
-    fs::write(&path, s).context("write snapshot file")
+    Ok(())
 }
 
 /// Load from a file path; returns `Ok(None)` if the file does not exist.
+/// Journal entries newer than the snapshot are replayed (crash recovery).
+/// Past is backed by the history store next to the snapshot (`HistoryStore::for_state_path`).
 pub fn load_from_path<P: AsRef<Path>>(path: P, config: Config) -> Result<Option<App>> {
     let path = path.as_ref();
-    if !path.exists() {
+    let journal_path = journal::journal_path_for(path);
+    // A journal without a snapshot means the first save never happened: recover from it
+    if !path.exists() && !journal_path.exists() {
         return Ok(None);
     }
-    let s = fs::read_to_string(path).context("read snapshot file")?;
-    let app = load_from_str(&s, config)?;
+    let mut snap = if path.exists() {
+        let s = fs::read_to_string(path).context("read snapshot file")?;
+        toml::from_str(&s).context("parse snapshot toml")?
+    } else {
+        SnapshotV1::default()
+    };
+    let entries = journal::read_entries(&journal_path)?;
+    let from = snap.journal_seq;
+    journal::replay(&mut snap, &entries)?;
+    let mut app = snap.into_app_with_history(config, HistoryStore::for_state_path(path))?;
+    journal::replay_past(&mut app, &entries, from);
     Ok(Some(app))
 }
 
+/// Fresh app for a snapshot path that does not exist yet, with its history store attached.
+pub fn new_app_at_path<P: AsRef<Path>>(path: P, config: Config) -> App {
+    let mut app = App::with_config(config);
+    app.attach_history_store(HistoryStore::for_state_path(path.as_ref()));
+    app
+}
+
 /// Resolve default snapshot file path with XDG semantics.
 /// Priority:
 /// 1) `CHUTE_KUN_STATE` env var (explicit override)
@@ -82,22 +187,33 @@ pub fn load_from_path<P: AsRef<Path>>(path: P, config: Config) -> Result<Option<
 /// 3) `$HOME/.local/share/chute_kun/snapshot.toml` (cross‑platform friendly)
 /// 4) Fallback: `dirs::data_dir()/chute_kun/snapshot.toml`
 pub fn default_state_path() -> Option<PathBuf> {
+    default_state_path_for(None)
+}
+
+/// Like `default_state_path`, but a profile keeps its snapshot (and the history store
+/// next to it) in `chute_kun/profiles/<name>/`.
+pub fn default_state_path_for(profile: Option<&str>) -> Option<PathBuf> {
     if let Ok(p) = std::env::var("CHUTE_KUN_STATE") {
         return Some(PathBuf::from(p));
     }
-    if let Some(xdg) = std::env::var_os("XDG_DATA_HOME") {
-        return Some(PathBuf::from(xdg).join("chute_kun").join("snapshot.toml"));
-    }
-    if let Some(home) = std::env::var_os("HOME") {
-        return Some(PathBuf::from(home).join(".local/share/chute_kun/snapshot.toml"));
-    }
-    dirs::data_dir().map(|b| b.join("chute_kun").join("snapshot.toml"))
+    let dir = if let Some(xdg) = std::env::var_os("XDG_DATA_HOME") {
+        PathBuf::from(xdg).join("chute_kun")
+    } else if let Some(home) = std::env::var_os("HOME") {
+        PathBuf::from(home).join(".local/share/chute_kun")
+    } else {
+        dirs::data_dir()?.join("chute_kun")
+    };
+    let dir = match profile {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    };
+    Some(dir.join("snapshot.toml"))
 }
 
 /// Resolve the snapshot path with precedence:
-/// 1) Config `state_path` (if set)
+/// 1) Config `state_path` (if set; a profile never inherits the base one)
 /// 2) CLI `--state` override
-/// 3) Env/OS defaults via `default_state_path()`
+/// 3) Env/OS defaults via `default_state_path_for(cfg.profile)`
 pub fn resolve_state_path(cfg: &Config, cli_override: Option<PathBuf>) -> Option<PathBuf> {
     if let Some(p) = cfg.state_path.clone() {
         return Some(p);
@@ -105,5 +221,88 @@ pub fn resolve_state_path(cfg: &Config, cli_override: Option<PathBuf>) -> Option
     if let Some(p) = cli_override {
         return Some(p);
     }
-    default_state_path()
+    default_state_path_for(cfg.profile.as_deref())
+}
+
+/// Lock file guarding a snapshot: `snapshot.lock` next to `snapshot.toml`.
+pub fn lock_path_for(state_path: &Path) -> PathBuf {
+    state_path.with_extension("lock")
+}
+
+/// Exclusive advisory lock on a snapshot. The TUI holds it for its whole session and a
+/// changing headless command while it saves, so neither overwrites the other's state or
+/// checkpoints away the other's journal. Released on drop (and by the OS on exit).
+#[derive(Debug)]
+pub struct StateLock {
+    _file: fs::File,
+}
+
+impl StateLock {
+    /// Take the lock of `state_path`; `None` while another process (or handle) holds it.
+    pub fn try_acquire(state_path: &Path) -> Result<Option<Self>> {
+        use fs2::FileExt;
+        let path = lock_path_for(state_path);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).context("create state dir")?;
+        }
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("open lock file {}", path.display()))?;
+        match file.try_lock_exclusive() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("lock {}", path.display())),
+        }
+    }
+}
+
+/// Debounced autosave schedule: a save is due once changes have been quiet for `quiet`,
+/// or at the latest `max_delay` after the first unsaved change (e.g., while a task runs).
+#[derive(Debug, Clone)]
+pub struct Autosave {
+    quiet: Duration,
+    max_delay: Duration,
+    first_change: Option<Instant>,
+    last_change: Option<Instant>,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(2), Duration::from_secs(30))
+    }
+}
+
+impl Autosave {
+    pub fn new(quiet: Duration, max_delay: Duration) -> Self {
+        Self { quiet, max_delay, first_change: None, last_change: None }
+    }
+
+    /// Note that state may have changed at `now`.
+    pub fn touch(&mut self, now: Instant) {
+        self.first_change.get_or_insert(now);
+        self.last_change = Some(now);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.first_change.is_some()
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        match (self.first_change, self.last_change) {
+            (Some(first), Some(last)) => {
+                now.saturating_duration_since(last) >= self.quiet
+                    || now.saturating_duration_since(first) >= self.max_delay
+            }
+            _ => false,
+        }
+    }
+
+    /// Clear the pending state after a successful save.
+    pub fn saved(&mut self) {
+        self.first_change = None;
+        self.last_change = None;
+    }
 }
@@ -0,0 +1,86 @@
+//! Free-form task tags (`#client-a`, `#urgent`).
+//! - Stored on `Task.tags` without the `#`, in the spelling first typed; matching is
+//!   case-insensitive.
+//! - A tag is a single word without `#` or `,`; all-digit words (`#123`) stay in the title
+//!   so issue numbers are not mistaken for tags.
+//! - `group_by_tag` is the grouping dimension for reports (`{{tags}}`) and exports.
+
+use crate::task::Task;
+
+/// Normalize user input (`#Client-A`, `client-a`) to a stored tag, or `None` if invalid.
+pub fn normalize_tag(s: &str) -> Option<String> {
+    let t = s.trim().trim_start_matches('#');
+    let valid = !t.is_empty()
+        && !t.chars().all(|c| c.is_ascii_digit())
+        && t.chars().all(|c| !c.is_whitespace() && c != '#' && c != ',');
+    valid.then(|| t.to_string())
+}
+
+pub fn has_tag(tags: &[String], tag: &str) -> bool {
+    let tag = tag.trim_start_matches('#');
+    tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Append `tag` unless an equal tag is already present. Returns whether it was added.
+pub fn add_tag(tags: &mut Vec<String>, tag: String) -> bool {
+    if has_tag(tags, &tag) {
+        return false;
+    }
+    tags.push(tag);
+    true
+}
+
+/// Remove `tag` (case-insensitive). Returns whether anything was removed.
+pub fn remove_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    let tag = tag.trim_start_matches('#');
+    let before = tags.len();
+    tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+    tags.len() != before
+}
+
+/// Tasks sharing one tag (`tag = None` collects untagged tasks).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagGroup<'a> {
+    pub tag: Option<String>,
+    pub tasks: Vec<&'a Task>,
+    pub estimate_min: u32,
+    pub actual_min: u32,
+}
+
+/// Group tasks by tag, sorted by tag name (case-insensitive) with untagged tasks last.
+/// A task with several tags counts toward each of them.
+pub fn group_by_tag<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Vec<TagGroup<'a>> {
+    let mut groups: Vec<TagGroup<'a>> = Vec::new();
+    let mut push = |tag: Option<&str>, t: &'a Task| {
+        let pos = groups.iter().position(|g| match (&g.tag, tag) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (None, None) => true,
+            _ => false,
+        });
+        let g = match pos {
+            Some(i) => &mut groups[i],
+            None => {
+                groups.push(TagGroup {
+                    tag: tag.map(str::to_string),
+                    tasks: Vec::new(),
+                    estimate_min: 0,
+                    actual_min: 0,
+                });
+                groups.last_mut().expect("just pushed")
+            }
+        };
+        g.tasks.push(t);
+        g.estimate_min += t.estimate_min as u32;
+        g.actual_min += t.actual_min as u32;
+    };
+    for t in tasks {
+        if t.tags.is_empty() {
+            push(None, t);
+        }
+        for tag in &t.tags {
+            push(Some(tag), t);
+        }
+    }
+    groups.sort_by_key(|g| (g.tag.is_none(), g.tag.as_ref().map(|t| t.to_lowercase())));
+    groups
+}
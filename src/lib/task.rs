@@ -1,12 +1,62 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-pub enum Category {
-    #[default]
-    General,
-    Work,
-    Home,
-    Hobby,
+/// Category id of a task. Besides the built-ins, any `[categories.<id>]` table in the
+/// config defines one. Ids compare case-insensitively and are stored verbatim, so old
+/// snapshots (`category = "Work"`) load as-is and ids missing from config round-trip.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Category(Cow<'static, str>);
+
+impl Category {
+    pub const GENERAL: Category = Category(Cow::Borrowed("General"));
+    pub const WORK: Category = Category(Cow::Borrowed("Work"));
+    pub const HOME: Category = Category(Cow::Borrowed("Home"));
+    pub const HOBBY: Category = Category(Cow::Borrowed("Hobby"));
+
+    pub fn new(id: impl Into<String>) -> Self {
+        Category(Cow::Owned(id.into()))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether this category matches the config key `id` (case-insensitive).
+    pub fn is(&self, id: &str) -> bool {
+        self.0.eq_ignore_ascii_case(id)
+    }
+}
+
+// Prints the bare id, like the former enum variants (`General`).
+impl fmt::Debug for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Default for Category {
+    fn default() -> Self {
+        Category::GENERAL
+    }
+}
+
+impl PartialEq for Category {
+    fn eq(&self, other: &Self) -> bool {
+        self.is(&other.0)
+    }
+}
+
+impl Eq for Category {}
+
+impl Hash for Category {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.0.bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,6 +74,47 @@ pub enum TaskState {
     Done,
 }
 
+/// Lowercase state keyword used by the CLI, search (`is:<state>`), reports and exports.
+pub fn state_str(state: TaskState) -> &'static str {
+    match state {
+        TaskState::Planned => "planned",
+        TaskState::Active => "active",
+        TaskState::Paused => "paused",
+        TaskState::Done => "done",
+    }
+}
+
+/// Estimate argument shared by `chute estimate` and the `:estimate` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateArg {
+    Set(u16),
+    Delta(i16),
+}
+
+impl EstimateArg {
+    /// `30`, `30m`, `+15m`, `-5`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_end_matches('m');
+        if let Some(rest) = s.strip_prefix('+') {
+            rest.parse::<i16>().ok().map(EstimateArg::Delta)
+        } else if s.starts_with('-') {
+            s.parse::<i16>().ok().map(EstimateArg::Delta)
+        } else {
+            s.parse::<u16>().ok().map(EstimateArg::Set)
+        }
+    }
+}
+
+/// Fixed start argument shared by `chute at` and the `:at` command: `HH:MM`/`HHMM` in
+/// minutes, or `None` for `-`/`none` (clear).
+pub fn parse_at_arg(s: &str) -> anyhow::Result<Option<u16>> {
+    if s == "-" || s.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let (h, m) = crate::config::parse_hhmm_or_compact(s)?;
+    Ok(Some(h * 60 + m))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Task {
     pub title: String,
@@ -59,6 +150,22 @@ pub struct Task {
     /// and pushes the subsequent tasks based on estimates from there.
     #[serde(default)]
     pub fixed_start_min: Option<u16>,
+    /// Id of the routine this task was generated from (`routine::Routine::id`), if any.
+    #[serde(default)]
+    pub routine_id: Option<u32>,
+    /// Free-form multi-line note (what happened during the task, follow-ups, ...).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+    /// Free-form tags without `#` (see `tags`). Matched case-insensitively.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Id of the remote task this one was imported from, as `<source>:<id>`
+    /// (e.g. `todoist:123`). Re-imports skip tasks whose id is already present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_id: Option<String>,
+    /// Added as an interruption (`i`, `:interrupt`) rather than planned work.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupt: bool,
 }
 
 impl Task {
@@ -74,8 +181,13 @@ impl Task {
             state: TaskState::Planned,
             planned_ymd: crate::date::today_ymd(),
             done_ymd: None,
-            category: Category::General,
+            category: Category::GENERAL,
             fixed_start_min: None,
+            routine_id: None,
+            notes: String::new(),
+            tags: Vec::new(),
+            remote_id: None,
+            interrupt: false,
         }
     }
 }
@@ -95,9 +207,33 @@ impl Task {
             }
         }
     }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        crate::tags::has_tag(&self.tags, tag)
+    }
+
+    /// Rebuild the actual-time fields from `sessions` after they were edited by hand.
+    /// - `actual_min`: sum of session lengths (a running session counts up to `now_min`).
+    /// - `started_at_min`: earliest session start.
+    /// - `finished_at_min`: latest session end for Done tasks (kept when none is closed).
+    pub fn recompute_from_sessions(&mut self, now_min: u16) {
+        self.actual_min = self
+            .sessions
+            .iter()
+            .map(|s| s.end_min.unwrap_or(now_min).saturating_sub(s.start_min))
+            .fold(0u16, |a, b| a.saturating_add(b));
+        self.actual_carry_sec = 0;
+        self.started_at_min = self.sessions.iter().map(|s| s.start_min).min();
+        self.finished_at_min = match self.state {
+            TaskState::Done => {
+                self.sessions.iter().filter_map(|s| s.end_min).max().or(self.finished_at_min)
+            }
+            _ => None,
+        };
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct DayPlan {
     pub tasks: Vec<Task>,
     active: Option<usize>,
@@ -277,6 +413,13 @@ impl DayPlan {
         dest_final
     }
 
+    /// Rearrange tasks so that new index `i` holds the task previously at `order[i]`.
+    /// `order` must be a permutation of the indices. Keeps `active` on the same task.
+    pub fn apply_order(&mut self, order: &[usize]) {
+        self.tasks = order.iter().map(|&i| self.tasks[i].clone()).collect();
+        self.active = self.active.and_then(|a| order.iter().position(|&i| i == a));
+    }
+
     pub fn adjust_estimate(&mut self, index: usize, delta_min: i16) {
         if let Some(t) = self.tasks.get_mut(index) {
             let cur = t.estimate_min as i16 + delta_min;
@@ -0,0 +1,234 @@
+//! Time log export for spreadsheets and billing (`chute export --format csv|json`).
+//! - Rows come from Past (history files, or memory when the day is loaded), Today and
+//!   Future, for the dates in `from..=to`. A task's date is the day it is listed under:
+//!   today for Today, `planned_ymd` for Future and `history::day_key` for Past.
+//! - `Rows::Sessions`: one row per `Session`, ordered by date then start. A running
+//!   session has no end and counts up to now; a session past midnight ends on the next day.
+//! - `Rows::Tasks`: one row per task, in list order within each date.
+//! - The column names (`SESSION_COLUMNS`, `TASK_COLUMNS`) are part of the format; add new
+//!   columns at the end.
+
+use crate::app::App;
+use crate::task::Task;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+
+pub const SESSION_COLUMNS: &[&str] =
+    &["date", "task", "category", "start", "end", "duration_min", "tags"];
+pub const TASK_COLUMNS: &[&str] = &[
+    "date",
+    "task",
+    "category",
+    "state",
+    "estimate_min",
+    "actual_min",
+    "started",
+    "finished",
+    "planned_date",
+    "done_date",
+    "tags",
+];
+
+/// What one row stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rows {
+    #[default]
+    Sessions,
+    Tasks,
+}
+
+/// Dates and rows to export. Missing dates are resolved by `Query::range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Query {
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+    pub rows: Rows,
+    /// Round `duration_min` and `actual_min` to the nearest multiple (halves round up).
+    pub round_min: Option<u16>,
+}
+
+impl Query {
+    /// `from..=to` (YYYYMMDD). Without dates this is just `today`; `from` alone runs
+    /// through today and `to` alone starts today, unless the given date is on the other side.
+    pub fn range(&self, today: u32) -> (u32, u32) {
+        let from = self.from.unwrap_or_else(|| self.to.unwrap_or(today).min(today));
+        let to = self.to.unwrap_or_else(|| from.max(today));
+        (from, to)
+    }
+}
+
+/// One cell: text, a number or empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Text(String),
+    Int(i64),
+    Empty,
+}
+
+/// Rows under fixed column names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub columns: &'static [&'static str],
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// Tasks by date within `from..=to`, ascending: Past days, Today, then Future dates.
+pub fn collect(app: &App, today: u32, from: u32, to: u32) -> Result<Vec<(u32, Vec<Task>)>> {
+    let in_range = |ymd: u32| (from..=to).contains(&ymd);
+    let mut past: BTreeSet<u32> =
+        app.history_tasks().iter().map(crate::history::day_key).filter(|d| in_range(*d)).collect();
+    if let Some(store) = app.history_store() {
+        past.extend(store.list_days()?.into_iter().filter(|d| in_range(*d)));
+    }
+    let mut out = Vec::new();
+    for ymd in past.into_iter().filter(|d| *d < today) {
+        let tasks = match app.history_store() {
+            Some(store) if !app.history_day_loaded(ymd) => store.load_day(ymd)?,
+            _ => app.history_tasks_for_day(ymd),
+        };
+        if !tasks.is_empty() {
+            out.push((ymd, tasks));
+        }
+    }
+    if in_range(today) && !app.day.tasks.is_empty() {
+        out.push((today, app.day.tasks.clone()));
+    }
+    let future: BTreeSet<u32> =
+        app.tomorrow_tasks().iter().map(|t| t.planned_ymd).filter(|d| in_range(*d)).collect();
+    for ymd in future {
+        let tasks = app.tomorrow_tasks().iter().filter(|t| t.planned_ymd == ymd).cloned();
+        out.push((ymd, tasks.collect()));
+    }
+    Ok(out)
+}
+
+/// Build the rows for `days` (from `collect`). `now_min` ends running sessions.
+pub fn table(app: &App, days: &[(u32, Vec<Task>)], now_min: u16, query: &Query) -> Table {
+    let round = |m: u32| match query.round_min {
+        Some(n) if n > 0 => (m + u32::from(n) / 2) / u32::from(n) * u32::from(n),
+        _ => m,
+    };
+    let mut rows = Vec::new();
+    for (ymd, tasks) in days {
+        let date = Cell::Text(crate::date::format_ymd(*ymd));
+        match query.rows {
+            Rows::Sessions => {
+                let mut day: Vec<(u16, Vec<Cell>)> = Vec::new();
+                for t in tasks {
+                    for s in &t.sessions {
+                        let end = s.end_min.unwrap_or(now_min);
+                        let length =
+                            (u32::from(end) + 24 * 60 - u32::from(s.start_min)) % (24 * 60);
+                        day.push((
+                            s.start_min,
+                            vec![
+                                date.clone(),
+                                Cell::Text(t.title.clone()),
+                                Cell::Text(app.config.category_name(&t.category)),
+                                hhmm(Some(s.start_min)),
+                                hhmm(s.end_min),
+                                Cell::Int(i64::from(round(length))),
+                                tags_cell(t),
+                            ],
+                        ));
+                    }
+                }
+                day.sort_by_key(|(start, _)| *start);
+                rows.extend(day.into_iter().map(|(_, row)| row));
+            }
+            Rows::Tasks => {
+                for t in tasks {
+                    rows.push(vec![
+                        date.clone(),
+                        Cell::Text(t.title.clone()),
+                        Cell::Text(app.config.category_name(&t.category)),
+                        Cell::Text(crate::task::state_str(t.state).to_string()),
+                        Cell::Int(i64::from(t.estimate_min)),
+                        Cell::Int(i64::from(round(u32::from(t.actual_min)))),
+                        hhmm(t.started_at_min),
+                        hhmm(t.finished_at_min),
+                        ymd_cell(Some(t.planned_ymd).filter(|d| *d > 0)),
+                        ymd_cell(t.done_ymd),
+                        tags_cell(t),
+                    ]);
+                }
+            }
+        }
+    }
+    let columns = match query.rows {
+        Rows::Sessions => SESSION_COLUMNS,
+        Rows::Tasks => TASK_COLUMNS,
+    };
+    Table { columns, rows }
+}
+
+impl Table {
+    /// RFC 4180 CSV with a header line; fields with `,`, `"` or line breaks are quoted.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| c.to_string()).collect();
+        for line in std::iter::once(header).chain(self.rows.iter().map(|r| {
+            r.iter()
+                .map(|c| match c {
+                    Cell::Text(s) => csv_field(s),
+                    Cell::Int(n) => n.to_string(),
+                    Cell::Empty => String::new(),
+                })
+                .collect()
+        })) {
+            out.push_str(&line.join(","));
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    /// An array of objects keyed by column; empty cells are `null`.
+    pub fn to_json(&self) -> Value {
+        let rows = self.rows.iter().map(|r| {
+            let obj: Map<String, Value> = self
+                .columns
+                .iter()
+                .zip(r)
+                .map(|(col, c)| {
+                    let v = match c {
+                        Cell::Text(s) => json!(s),
+                        Cell::Int(n) => json!(n),
+                        Cell::Empty => Value::Null,
+                    };
+                    (col.to_string(), v)
+                })
+                .collect();
+            Value::Object(obj)
+        });
+        Value::Array(rows.collect())
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn hhmm(min: Option<u16>) -> Cell {
+    match min {
+        Some(m) => Cell::Text(format!("{:02}:{:02}", m / 60, m % 60)),
+        None => Cell::Empty,
+    }
+}
+
+/// Space-separated tags without `#`, so a spreadsheet can split or filter on them.
+fn tags_cell(t: &Task) -> Cell {
+    if t.tags.is_empty() {
+        Cell::Empty
+    } else {
+        Cell::Text(t.tags.join(" "))
+    }
+}
+
+fn ymd_cell(ymd: Option<u32>) -> Cell {
+    ymd.map_or(Cell::Empty, |d| Cell::Text(crate::date::format_ymd(d)))
+}
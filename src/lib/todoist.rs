@@ -0,0 +1,459 @@
+//! Todoist REST API v2 client (see `docs/adr/ADR-001-TaskManagementAPI-Selection.md`).
+//! - `TodoistSource` fetches active tasks matching `[todoist] filter` (default
+//!   `today | overdue`) for `source::import`.
+//! - The API token is read from `TODOIST_API_TOKEN` only and never written to config or
+//!   snapshots.
+//! - `[todoist] base_url` points the client at another server (tests use a local mock).
+//! - Write-back: `Tracker` turns finishing (and undoing it) and date moves of imported tasks
+//!   into `TodoistOp`s. They go through the durable `outbox` next to the snapshot; the TUI
+//!   delivers them on a `SyncWorker` thread so a missing network never blocks it.
+
+use crate::app::App;
+use crate::outbox::Outbox;
+use crate::source::{RemoteTask, TaskSource};
+use crate::task::{Task, TaskState};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+pub const TOKEN_ENV: &str = "TODOIST_API_TOKEN";
+pub const DEFAULT_BASE_URL: &str = "https://api.todoist.com/rest/v2";
+pub const DEFAULT_FILTER: &str = "today | overdue";
+/// Prefix of `Task::remote_id` for Todoist tasks.
+pub const SOURCE: &str = "todoist";
+/// First retry delay after a failed delivery; doubles up to `RETRY_MAX`.
+const RETRY_MIN: Duration = Duration::from_secs(30);
+const RETRY_MAX: Duration = Duration::from_secs(15 * 60);
+
+/// `[todoist]` section of the config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoistConfig {
+    /// API root without a trailing slash.
+    pub base_url: String,
+    /// Todoist filter query selecting the tasks to import.
+    pub filter: String,
+    /// Post a `log_comment` when an imported task is finished.
+    pub comment_on_finish: bool,
+}
+
+impl Default for TodoistConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            filter: DEFAULT_FILTER.to_string(),
+            comment_on_finish: true,
+        }
+    }
+}
+
+pub struct TodoistSource {
+    base_url: String,
+    token: String,
+    filter: String,
+    agent: ureq::Agent,
+}
+
+impl TodoistSource {
+    pub fn new(cfg: &TodoistConfig, token: impl Into<String>) -> Self {
+        Self {
+            base_url: cfg.base_url.trim_end_matches('/').to_string(),
+            token: token.into(),
+            filter: cfg.filter.clone(),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(15)).build(),
+        }
+    }
+
+    /// Source configured by `cfg` with the token from `TODOIST_API_TOKEN`.
+    pub fn from_env(cfg: &TodoistConfig) -> Result<Self> {
+        let token = std::env::var(TOKEN_ENV)
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| anyhow!("{TOKEN_ENV} is not set"))?;
+        Ok(Self::new(cfg, token.trim()))
+    }
+}
+
+impl TodoistSource {
+    /// Deliver one write-back call.
+    pub fn send(&self, op: &TodoistOp) -> Result<(), SendError> {
+        let (path, body) = match op {
+            TodoistOp::Close { task_id } => (format!("/tasks/{task_id}/close"), None),
+            TodoistOp::Reopen { task_id } => (format!("/tasks/{task_id}/reopen"), None),
+            TodoistOp::Comment { task_id, content } => (
+                "/comments".to_string(),
+                Some(serde_json::json!({ "task_id": task_id, "content": content })),
+            ),
+            TodoistOp::Due { task_id, ymd } => (
+                format!("/tasks/{task_id}"),
+                Some(serde_json::json!({ "due_date": crate::date::format_ymd(*ymd) })),
+            ),
+        };
+        let req = self
+            .agent
+            .post(&format!("{}{path}", self.base_url))
+            .set("Authorization", &format!("Bearer {}", self.token));
+        let res = match body {
+            Some(b) => req.set("Content-Type", "application/json").send_string(&b.to_string()),
+            None => req.call(),
+        };
+        match res {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code, r)) => {
+                let msg = r.into_string().unwrap_or_default();
+                Err(SendError {
+                    message: format!("POST {path} returned {code}: {}", msg.trim()),
+                    // Rate limits and server errors pass; other refusals will not
+                    retry: code == 429 || code >= 500,
+                })
+            }
+            Err(e) => Err(SendError { message: format!("POST {path} failed: {e}"), retry: true }),
+        }
+    }
+}
+
+impl TaskSource for TodoistSource {
+    fn name(&self) -> &'static str {
+        SOURCE
+    }
+
+    fn fetch(&self) -> Result<Vec<RemoteTask>> {
+        let url = format!("{}/tasks", self.base_url);
+        let res = self
+            .agent
+            .get(&url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .query("filter", &self.filter)
+            .call();
+        let body = match res {
+            Ok(r) => r.into_string().context("todoist: read response")?,
+            Err(ureq::Error::Status(code, r)) => {
+                let msg = r.into_string().unwrap_or_default();
+                return Err(anyhow!("todoist: GET /tasks returned {code}: {}", msg.trim()));
+            }
+            Err(e) => return Err(anyhow!(e).context("todoist: GET /tasks failed")),
+        };
+        parse_tasks(&body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTask {
+    id: String,
+    content: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    due: Option<ApiDue>,
+    #[serde(default)]
+    duration: Option<ApiDuration>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDue {
+    date: String,
+    #[serde(default)]
+    datetime: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDuration {
+    amount: u32,
+    unit: String,
+}
+
+fn parse_tasks(body: &str) -> Result<Vec<RemoteTask>> {
+    let tasks: Vec<ApiTask> = serde_json::from_str(body).context("todoist: parse tasks")?;
+    Ok(tasks.into_iter().map(remote_task).collect())
+}
+
+fn remote_task(t: ApiTask) -> RemoteTask {
+    let (due_ymd, due_min) = match &t.due {
+        Some(due) => match due.datetime.as_deref().and_then(local_datetime) {
+            Some(dt) => {
+                let ymd = dt.year() as u32 * 10000 + dt.month() * 100 + dt.day();
+                (Some(ymd), Some((dt.hour() * 60 + dt.minute()) as u16))
+            }
+            None => (crate::date::parse_ymd(&due.date), None),
+        },
+        None => (None, None),
+    };
+    // Durations in days do not map to an estimate
+    let estimate_min = t
+        .duration
+        .filter(|d| d.unit == "minute")
+        .map(|d| u16::try_from(d.amount).unwrap_or(u16::MAX));
+    RemoteTask {
+        id: t.id,
+        title: t.content.trim().to_string(),
+        due_ymd,
+        due_min,
+        estimate_min,
+        labels: t.labels,
+    }
+}
+
+/// `2025-10-20T14:00:00Z` (UTC, converted to local time) or `2025-10-20T14:00:00` (floating).
+fn local_datetime(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+// ---- Write-back ----
+
+/// A write-back call waiting in the outbox.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TodoistOp {
+    Close {
+        task_id: String,
+    },
+    /// Undo of a finish.
+    Reopen {
+        task_id: String,
+    },
+    Comment {
+        task_id: String,
+        content: String,
+    },
+    /// Move the due date to `ymd` (`YYYYMMDD`).
+    Due {
+        task_id: String,
+        ymd: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendError {
+    pub message: String,
+    /// Whether the call should stay queued (network errors, 429, 5xx).
+    pub retry: bool,
+}
+
+/// Outbox file for a snapshot path: same directory and stem, `.todoist-queue` extension.
+pub fn queue_path_for(state_path: &Path) -> PathBuf {
+    state_path.with_extension("todoist-queue")
+}
+
+/// Todoist id of an imported task.
+pub fn task_id(task: &Task) -> Option<&str> {
+    task.remote_id.as_deref()?.strip_prefix(SOURCE)?.strip_prefix(':')
+}
+
+/// Finish comment: `tc_log_line` plus the date and sessions,
+/// e.g. `tc-log | Write report | act:40m | est:30m | state:Done | date:2025-09-01 | sessions:09:00-09:25,10:00-10:15`.
+pub fn log_comment(task: &Task) -> String {
+    let hm = |m: u16| format!("{:02}:{:02}", m / 60, m % 60);
+    let sessions: Vec<String> = task
+        .sessions
+        .iter()
+        .map(|s| match s.end_min {
+            Some(e) => format!("{}-{}", hm(s.start_min), hm(e)),
+            None => format!("{}-", hm(s.start_min)),
+        })
+        .collect();
+    let date = task.done_ymd.unwrap_or(task.planned_ymd);
+    format!(
+        "{} | date:{} | sessions:{}",
+        crate::task::tc_log_line(task),
+        crate::date::format_ymd(date),
+        if sessions.is_empty() { "-".to_string() } else { sessions.join(",") }
+    )
+}
+
+/// Derives write-back calls by diffing imported tasks in Today/Future against the last
+/// observed state (like `journal::Journal`), so every finish/postpone path is covered.
+#[derive(Debug)]
+pub struct Tracker {
+    comment_on_finish: bool,
+    seen: HashMap<String, Task>,
+}
+
+fn imported_tasks(app: &App) -> Vec<(String, Task)> {
+    app.day
+        .tasks
+        .iter()
+        .chain(app.tomorrow_tasks())
+        .filter_map(|t| task_id(t).map(|id| (id.to_string(), t.clone())))
+        .collect()
+}
+
+impl Tracker {
+    pub fn open(app: &App) -> Self {
+        Self {
+            comment_on_finish: app.config.todoist.comment_on_finish,
+            seen: imported_tasks(app).into_iter().collect(),
+        }
+    }
+
+    /// Calls for the changes since the last observation.
+    pub fn observe(&mut self, app: &App) -> Vec<TodoistOp> {
+        let now = imported_tasks(app);
+        let mut ops = Vec::new();
+        for (id, t) in &now {
+            let Some(before) = self.seen.get(id) else {
+                continue;
+            };
+            let done = t.state == TaskState::Done;
+            let was_done = before.state == TaskState::Done;
+            if done && !was_done {
+                if self.comment_on_finish {
+                    ops.push(TodoistOp::Comment { task_id: id.clone(), content: log_comment(t) });
+                }
+                ops.push(TodoistOp::Close { task_id: id.clone() });
+            } else if !done && was_done {
+                ops.push(TodoistOp::Reopen { task_id: id.clone() });
+            }
+            if !done && t.planned_ymd != before.planned_ymd {
+                ops.push(TodoistOp::Due { task_id: id.clone(), ymd: t.planned_ymd });
+            }
+        }
+        self.seen = now.into_iter().collect();
+        ops
+    }
+}
+
+/// Queue state after a delivery attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Calls still queued.
+    pub pending: usize,
+    /// Why the last attempt stopped or dropped a call.
+    pub error: Option<String>,
+}
+
+impl SyncStatus {
+    /// Header text for `App::set_sync_notice` (`None` when everything went through).
+    pub fn notice(&self) -> Option<String> {
+        self.error.as_ref().map(|e| format!("todoist: {e} ({} queued)", self.pending))
+    }
+}
+
+/// Send queued calls in order until the queue is empty or a call has to wait.
+/// Calls the server refuses for good are dropped and reported. The lock is not held
+/// while a request is in flight.
+pub fn deliver(queue: &Mutex<Outbox<TodoistOp>>, source: Option<&TodoistSource>) -> SyncStatus {
+    let lock = || queue.lock().unwrap_or_else(|e| e.into_inner());
+    let mut error = None;
+    loop {
+        let Some(op) = lock().pending().first().cloned() else {
+            return SyncStatus { pending: 0, error };
+        };
+        let Some(source) = source else {
+            let pending = lock().pending().len();
+            return SyncStatus { pending, error: Some(format!("{TOKEN_ENV} is not set")) };
+        };
+        match source.send(&op) {
+            Ok(()) => {}
+            Err(e) if !e.retry => error = Some(format!("dropped {}", e.message)),
+            Err(e) => {
+                let pending = lock().pending().len();
+                return SyncStatus { pending, error: Some(e.message) };
+            }
+        }
+        if let Err(e) = lock().pop_front() {
+            let pending = lock().pending().len();
+            return SyncStatus { pending, error: Some(format!("{e:#}")) };
+        }
+    }
+}
+
+/// Background thread delivering the outbox with exponential backoff.
+pub struct SyncWorker {
+    queue: Arc<Mutex<Outbox<TodoistOp>>>,
+    wake: mpsc::Sender<()>,
+    status: mpsc::Receiver<SyncStatus>,
+}
+
+impl SyncWorker {
+    /// Start delivering `queue`; calls left from earlier runs go first. Without a
+    /// `source` (no token) calls stay queued and the status says why.
+    pub fn spawn(queue: Outbox<TodoistOp>, source: Option<TodoistSource>) -> Self {
+        let queue = Arc::new(Mutex::new(queue));
+        let (wake, woken) = mpsc::channel::<()>();
+        let (report, status) = mpsc::channel();
+        let shared = Arc::clone(&queue);
+        std::thread::spawn(move || {
+            let mut delay = RETRY_MIN;
+            loop {
+                let st = deliver(&shared, source.as_ref());
+                let wait = if st.pending > 0 { Some(delay) } else { None };
+                delay = if wait.is_some() { (delay * 2).min(RETRY_MAX) } else { RETRY_MIN };
+                if report.send(st).is_err() {
+                    return;
+                }
+                let disconnected = match wait {
+                    Some(d) => {
+                        matches!(woken.recv_timeout(d), Err(mpsc::RecvTimeoutError::Disconnected))
+                    }
+                    None => woken.recv().is_err(),
+                };
+                if disconnected {
+                    return;
+                }
+            }
+        });
+        Self { queue, wake, status }
+    }
+
+    /// Persist `ops` and wake the worker. Only file I/O happens on the caller's thread.
+    pub fn push(&self, ops: Vec<TodoistOp>) -> Result<()> {
+        if ops.is_empty() {
+            return Ok(());
+        }
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).push(ops)?;
+        let _ = self.wake.send(());
+        Ok(())
+    }
+
+    /// The latest status reported since the previous call, if any.
+    pub fn try_status(&self) -> Option<SyncStatus> {
+        self.status.try_iter().last()
+    }
+}
+
+/// Write-back for the TUI: a `Tracker` feeding a `SyncWorker` on the snapshot's queue.
+pub struct WriteBack {
+    tracker: Tracker,
+    worker: SyncWorker,
+}
+
+impl WriteBack {
+    pub fn start(app: &App, state_path: &Path) -> Result<Self> {
+        let queue = Outbox::open(queue_path_for(state_path))?;
+        let source = TodoistSource::from_env(&app.config.todoist).ok();
+        Ok(Self { tracker: Tracker::open(app), worker: SyncWorker::spawn(queue, source) })
+    }
+
+    /// Queue calls for changes since the last update and show the worker's latest status.
+    pub fn update(&mut self, app: &mut App) {
+        let ops = self.tracker.observe(app);
+        if let Err(e) = self.worker.push(ops) {
+            app.set_sync_notice(SOURCE, Some(format!("todoist: {e:#}")));
+        }
+        if let Some(st) = self.worker.try_status() {
+            app.set_sync_notice(SOURCE, st.notice());
+        }
+    }
+}
+
+/// Queue `ops` for the snapshot at `state_path` and deliver the queue on this thread
+/// (headless commands). `None` when nothing is queued.
+pub fn sync_now(
+    state_path: &Path,
+    ops: Vec<TodoistOp>,
+    cfg: &TodoistConfig,
+) -> Result<Option<SyncStatus>> {
+    let mut queue = Outbox::open(queue_path_for(state_path))?;
+    queue.push(ops)?;
+    if queue.is_empty() {
+        return Ok(None);
+    }
+    let source = TodoistSource::from_env(cfg).ok();
+    Ok(Some(deliver(&Mutex::new(queue), source.as_ref())))
+}
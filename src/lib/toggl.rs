@@ -0,0 +1,582 @@
+//! Push-only Toggl Track sync (see `docs/features/toggl-sync-design-v1.md`).
+//! - One `Session` of a Today task is one time entry: a running session is a running entry,
+//!   a finished (or edited) one gets explicit `start`/`stop`.
+//! - The sidecar `sync/toggl.json` next to the snapshot maps `(ymd, task_index,
+//!   session_index)` to the entry id. Sync compares Today's sessions with it, so starting,
+//!   pausing, finishing, session edits and sessions recorded while offline all become the
+//!   same create/update calls.
+//! - Configured by `TOGGL_*` env vars only; the token never reaches config or snapshots.
+//! - The TUI runs `SyncWorker` on its own thread: requests are paced, and 429s and network
+//!   errors back off exponentially (honouring `Retry-After`).
+
+use crate::app::App;
+use crate::task::Task;
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.track.toggl.com/api/v9";
+/// Notice key for `App::set_sync_notice`.
+pub const SOURCE: &str = "toggl";
+const CREATED_WITH: &str = "chute-kun";
+/// Sidecar records older than this many days are dropped.
+const KEEP_DAYS: i32 = 14;
+const BACKOFF_MIN: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(5 * 60);
+
+/// Settings from `TOGGL_*` environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TogglConfig {
+    pub token: String,
+    /// `TOGGL_WORKSPACE_ID`; looked up with `GET /me` when unset.
+    pub workspace_id: Option<u64>,
+    pub project_id: Option<u64>,
+    pub tags: Vec<String>,
+    /// `TOGGL_API_URL`, the API root without a trailing slash.
+    pub base_url: String,
+    /// Minimum time between requests.
+    pub pace: Duration,
+}
+
+impl TogglConfig {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            workspace_id: None,
+            project_id: None,
+            tags: Vec::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            pace: Duration::from_secs(1),
+        }
+    }
+
+    /// Read `TOGGL_API_TOKEN`, `TOGGL_WORKSPACE_ID`, `TOGGL_PROJECT_ID_DEFAULT`,
+    /// `TOGGL_TAGS_DEFAULT`, `TOGGL_SYNC_MODE` and `TOGGL_API_URL`. `None` when there is
+    /// no token or the mode is `off`.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var =
+            |k: &str| std::env::var(k).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        match var("TOGGL_SYNC_MODE").as_deref() {
+            None | Some("push") => {}
+            Some("off") => return Ok(None),
+            Some(other) => return Err(anyhow!("TOGGL_SYNC_MODE must be off or push: {other}")),
+        }
+        let Some(token) = var("TOGGL_API_TOKEN") else {
+            return Ok(None);
+        };
+        let id = |k: &str| -> Result<Option<u64>> {
+            var(k)
+                .map(|v| v.parse::<u64>().with_context(|| format!("{k} must be a number")))
+                .transpose()
+        };
+        let mut cfg = Self::new(token);
+        cfg.workspace_id = id("TOGGL_WORKSPACE_ID")?;
+        cfg.project_id = id("TOGGL_PROJECT_ID_DEFAULT")?;
+        cfg.tags = var("TOGGL_TAGS_DEFAULT")
+            .map(|v| v.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+            .unwrap_or_default();
+        if let Some(url) = var("TOGGL_API_URL") {
+            cfg.base_url = url.trim_end_matches('/').to_string();
+        }
+        Ok(Some(cfg))
+    }
+}
+
+// ---- Sessions and the sidecar ----
+
+/// A session of a Today task as it should appear in Toggl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalSession {
+    /// Day the session belongs to (`YYYYMMDD`).
+    pub ymd: u32,
+    pub task_index: usize,
+    pub session_index: usize,
+    pub title: String,
+    /// Planned date of the task; tells a carried-over task from the next routine instance.
+    pub planned_ymd: u32,
+    pub start_min: u16,
+    pub end_min: Option<u16>,
+}
+
+/// Sessions of the Today list on `ymd`, in list order.
+pub fn local_sessions(tasks: &[Task], ymd: u32) -> Vec<LocalSession> {
+    let mut out = Vec::new();
+    for (ti, t) in tasks.iter().enumerate() {
+        for (si, s) in t.sessions.iter().enumerate() {
+            out.push(LocalSession {
+                ymd,
+                task_index: ti,
+                session_index: si,
+                title: t.title.clone(),
+                planned_ymd: t.planned_ymd,
+                start_min: s.start_min,
+                end_min: s.end_min,
+            });
+        }
+    }
+    out
+}
+
+/// A pushed session and its time entry id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryRecord {
+    pub ymd: u32,
+    pub task_index: usize,
+    pub session_index: usize,
+    pub entry_id: u64,
+    pub title: String,
+    pub planned_ymd: u32,
+    pub start_min: u16,
+    #[serde(default)]
+    pub end_min: Option<u16>,
+}
+
+impl EntryRecord {
+    /// Same task instance and session; the title is not part of it, so renames update.
+    fn same_session(&self, s: &LocalSession) -> bool {
+        self.planned_ymd == s.planned_ymd && self.session_index == s.session_index
+    }
+}
+
+/// Contents of `sync/toggl.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sidecar {
+    /// Workspace found via `GET /me`, so it is looked up once.
+    #[serde(default)]
+    pub workspace_id: Option<u64>,
+    #[serde(default)]
+    pub entries: Vec<EntryRecord>,
+}
+
+/// What `plan` wants done for one session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TogglOp {
+    Create(LocalSession),
+    /// Finalize or correct the entry's start/stop and description.
+    Update {
+        entry_id: u64,
+        session: LocalSession,
+    },
+}
+
+/// Sidecar path for a snapshot path: `sync/toggl.json` in the snapshot's directory.
+pub fn sidecar_path_for(state_path: &Path) -> PathBuf {
+    let dir = state_path.parent().unwrap_or(Path::new("."));
+    dir.join("sync").join("toggl.json")
+}
+
+impl Sidecar {
+    /// Load the sidecar at `path`; a missing file is empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => serde_json::from_str(&s).context("parse toggl sidecar"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).context("read toggl sidecar"),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let s = serde_json::to_string_pretty(self).context("serialize toggl sidecar")?;
+        crate::storage::write_atomic(path, s.as_bytes()).context("write toggl sidecar")
+    }
+
+    fn find(&self, s: &LocalSession) -> Option<usize> {
+        // A rename or a start edit keeps the entry; a different task moved into the slot does not
+        let by_key = self.entries.iter().position(|r| {
+            (r.ymd, r.task_index, r.session_index) == (s.ymd, s.task_index, s.session_index)
+                && r.same_session(s)
+                && (r.title == s.title || r.start_min == s.start_min)
+        });
+        // Reordered tasks and tasks carried over midnight keep their entry
+        let moved = |by_title: bool| {
+            self.entries.iter().position(|r| {
+                r.same_session(s) && r.start_min == s.start_min && (!by_title || r.title == s.title)
+            })
+        };
+        by_key.or_else(|| moved(true)).or_else(|| moved(false))
+    }
+
+    /// Point records of moved sessions at their current `(ymd, task_index)`.
+    /// Returns true when something changed.
+    pub fn rekey(&mut self, sessions: &[LocalSession]) -> bool {
+        let mut changed = false;
+        for s in sessions {
+            if let Some(i) = self.find(s) {
+                let r = &mut self.entries[i];
+                if (r.ymd, r.task_index) != (s.ymd, s.task_index) {
+                    r.ymd = s.ymd;
+                    r.task_index = s.task_index;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Calls that bring Toggl in line with `sessions`.
+    pub fn plan(&self, sessions: &[LocalSession]) -> Vec<TogglOp> {
+        let mut ops = Vec::new();
+        for s in sessions {
+            match self.find(s).map(|i| &self.entries[i]) {
+                None => ops.push(TogglOp::Create(s.clone())),
+                // A stopped entry does not restart; only stops, edits and renames are pushed
+                Some(r)
+                    if r.title != s.title
+                        || (s.end_min.is_some()
+                            && (r.start_min, r.end_min) != (s.start_min, s.end_min)) =>
+                {
+                    ops.push(TogglOp::Update { entry_id: r.entry_id, session: s.clone() })
+                }
+                Some(_) => {}
+            }
+        }
+        ops
+    }
+
+    /// Remember the result of a successful `op`.
+    pub fn record(&mut self, op: &TogglOp, entry_id: u64) {
+        let s = match op {
+            TogglOp::Create(s) | TogglOp::Update { session: s, .. } => s,
+        };
+        self.entries.retain(|r| r.entry_id != entry_id);
+        self.entries.push(EntryRecord {
+            ymd: s.ymd,
+            task_index: s.task_index,
+            session_index: s.session_index,
+            entry_id,
+            title: s.title.clone(),
+            planned_ymd: s.planned_ymd,
+            start_min: s.start_min,
+            end_min: s.end_min,
+        });
+    }
+
+    /// Drop records of days more than `KEEP_DAYS` before `today`.
+    pub fn prune(&mut self, today: u32) {
+        let oldest = crate::date::add_days_to_ymd(today, -KEEP_DAYS);
+        self.entries.retain(|r| r.ymd >= oldest);
+    }
+}
+
+// ---- HTTP ----
+
+/// Why a call failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallError {
+    /// 429, 5xx or a network error: try again after `retry_after` (or the backoff).
+    Retry { message: String, retry_after: Option<Duration> },
+    /// Refused for good (e.g. 400 or 403).
+    Rejected(String),
+}
+
+impl CallError {
+    pub fn message(&self) -> &str {
+        match self {
+            CallError::Retry { message, .. } | CallError::Rejected(message) => message,
+        }
+    }
+}
+
+/// `YYYYMMDD` + minutes as RFC 3339 in the local time zone.
+pub fn timestamp(ymd: u32, min: u16) -> String {
+    let date = NaiveDate::from_ymd_opt((ymd / 10000) as i32, ymd / 100 % 100, ymd % 100)
+        .unwrap_or_default();
+    let at =
+        date.and_hms_opt(0, 0, 0).unwrap_or_default() + chrono::Duration::minutes(i64::from(min));
+    // A time skipped by a DST change is sent as if it were UTC
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&at))
+        .to_rfc3339()
+}
+
+pub struct TogglClient {
+    cfg: TogglConfig,
+    auth: String,
+    agent: ureq::Agent,
+    last_call: Option<Instant>,
+}
+
+impl TogglClient {
+    pub fn new(cfg: TogglConfig) -> Self {
+        let auth =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:api_token", cfg.token));
+        Self {
+            cfg,
+            auth: format!("Basic {auth}"),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(15)).build(),
+            last_call: None,
+        }
+    }
+
+    fn call(
+        &mut self,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, CallError> {
+        if let Some(last) = self.last_call {
+            std::thread::sleep(self.cfg.pace.saturating_sub(last.elapsed()));
+        }
+        self.last_call = Some(Instant::now());
+        let req = self
+            .agent
+            .request(method, &format!("{}{path}", self.cfg.base_url))
+            .set("Authorization", &self.auth);
+        let res = match body {
+            Some(b) => req.set("Content-Type", "application/json").send_string(&b.to_string()),
+            None => req.call(),
+        };
+        match res {
+            Ok(r) => {
+                let text = r.into_string().unwrap_or_default();
+                Ok(serde_json::from_str(&text).unwrap_or(serde_json::Value::Null))
+            }
+            Err(ureq::Error::Status(code, r)) => {
+                let retry_after = r
+                    .header("Retry-After")
+                    .and_then(|v| v.trim().parse().ok())
+                    .map(Duration::from_secs);
+                let message = format!(
+                    "{method} {path} returned {code}: {}",
+                    r.into_string().unwrap_or_default().trim()
+                );
+                if code == 429 || code >= 500 {
+                    Err(CallError::Retry { message, retry_after })
+                } else {
+                    Err(CallError::Rejected(message))
+                }
+            }
+            Err(e) => Err(CallError::Retry {
+                message: format!("{method} {path} failed: {e}"),
+                retry_after: None,
+            }),
+        }
+    }
+
+    /// Configured workspace, or the one cached in `sidecar`, or `GET /me`.
+    fn workspace(&mut self, sidecar: &mut Sidecar) -> Result<u64, CallError> {
+        if let Some(id) = self.cfg.workspace_id.or(sidecar.workspace_id) {
+            return Ok(id);
+        }
+        let me = self.call("GET", "/me", None)?;
+        let id = me["default_workspace_id"]
+            .as_u64()
+            .ok_or_else(|| CallError::Rejected("GET /me has no default_workspace_id".into()))?;
+        sidecar.workspace_id = Some(id);
+        Ok(id)
+    }
+
+    /// Perform `op`; returns the entry id.
+    pub fn send(&mut self, op: &TogglOp, sidecar: &mut Sidecar) -> Result<u64, CallError> {
+        let wid = self.workspace(sidecar)?;
+        let entry = |s: &LocalSession| {
+            let mut v = serde_json::json!({ "start": timestamp(s.ymd, s.start_min) });
+            match s.end_min {
+                Some(end) => {
+                    // A stop before the start means the session ran past midnight
+                    let end_abs = if end < s.start_min { end + 24 * 60 } else { end };
+                    v["stop"] = timestamp(s.ymd, end_abs).into();
+                    v["duration"] = (i64::from(end_abs - s.start_min) * 60).into();
+                }
+                None => v["duration"] = (-1).into(),
+            }
+            v
+        };
+        match op {
+            TogglOp::Create(s) => {
+                let mut body = entry(s);
+                body["description"] = s.title.clone().into();
+                body["created_with"] = CREATED_WITH.into();
+                body["workspace_id"] = wid.into();
+                if let Some(p) = self.cfg.project_id {
+                    body["project_id"] = p.into();
+                }
+                if !self.cfg.tags.is_empty() {
+                    body["tags"] = self.cfg.tags.clone().into();
+                }
+                let res =
+                    self.call("POST", &format!("/workspaces/{wid}/time_entries"), Some(body))?;
+                res["id"]
+                    .as_u64()
+                    .ok_or_else(|| CallError::Rejected("created entry has no id".into()))
+            }
+            TogglOp::Update { entry_id, session } => {
+                let path = format!("/workspaces/{wid}/time_entries/{entry_id}");
+                let mut body = entry(session);
+                body["description"] = session.title.clone().into();
+                self.call("PATCH", &path, Some(body))?;
+                Ok(*entry_id)
+            }
+        }
+    }
+}
+
+/// Sync state after a pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Calls that still have to be made.
+    pub pending: usize,
+    pub error: Option<CallError>,
+}
+
+impl SyncStatus {
+    /// Header text for `App::set_sync_notice` (`None` when Toggl is up to date).
+    pub fn notice(&self) -> Option<String> {
+        self.error.as_ref().map(|e| format!("toggl: {} ({} pending)", e.message(), self.pending))
+    }
+}
+
+/// Push `sessions` once, saving the sidecar at `path` after every success.
+/// Stops at the first failure.
+pub fn push(
+    client: &mut TogglClient,
+    path: &Path,
+    sessions: &[LocalSession],
+) -> Result<SyncStatus> {
+    let mut sidecar = Sidecar::load(path)?;
+    if let Some(today) = sessions.first().map(|s| s.ymd) {
+        sidecar.prune(today);
+    }
+    if sidecar.rekey(sessions) {
+        sidecar.save(path)?;
+    }
+    let ops = sidecar.plan(sessions);
+    for (done, op) in ops.iter().enumerate() {
+        match client.send(op, &mut sidecar) {
+            Ok(id) => {
+                sidecar.record(op, id);
+                sidecar.save(path)?;
+            }
+            Err(e) => {
+                // Keep a looked-up workspace even when the call itself failed
+                sidecar.save(path)?;
+                return Ok(SyncStatus { pending: ops.len() - done, error: Some(e) });
+            }
+        }
+    }
+    Ok(SyncStatus { pending: 0, error: None })
+}
+
+/// Backoff before retry `attempt` (0-based): doubling from 1s up to 5 minutes, plus up to
+/// 25% jitter so several clients do not retry in lockstep.
+pub fn backoff(attempt: u32) -> Duration {
+    let base = BACKOFF_MIN.saturating_mul(1 << attempt.min(16)).min(BACKOFF_MAX);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    base + base.mul_f64(f64::from(nanos % 1000) / 4000.0)
+}
+
+/// Background thread pushing the latest sessions it was given.
+pub struct SyncWorker {
+    sessions: mpsc::Sender<Vec<LocalSession>>,
+    status: mpsc::Receiver<SyncStatus>,
+    last_sent: Option<Vec<LocalSession>>,
+}
+
+impl SyncWorker {
+    pub fn spawn(cfg: TogglConfig, sidecar_path: PathBuf) -> Self {
+        let (sessions, incoming) = mpsc::channel::<Vec<LocalSession>>();
+        let (report, status) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut client = TogglClient::new(cfg);
+            let mut latest: Option<Vec<LocalSession>> = None;
+            let mut attempt = 0;
+            loop {
+                // Wait for a change unless a failed push is waiting for its retry
+                if latest.is_none() {
+                    match incoming.recv() {
+                        Ok(s) => latest = Some(s),
+                        Err(_) => return,
+                    }
+                }
+                // Only the newest state matters
+                while let Ok(s) = incoming.try_recv() {
+                    latest = Some(s);
+                }
+                let Some(sessions) = latest.as_deref() else {
+                    continue;
+                };
+                let st = push(&mut client, &sidecar_path, sessions).unwrap_or_else(|e| {
+                    SyncStatus { pending: 0, error: Some(CallError::Rejected(format!("{e:#}"))) }
+                });
+                let delay = match &st.error {
+                    Some(CallError::Retry { retry_after, .. }) => {
+                        let d = retry_after.unwrap_or_else(|| backoff(attempt));
+                        attempt += 1;
+                        Some(d)
+                    }
+                    // Done, or refused: wait for the next change
+                    _ => {
+                        attempt = 0;
+                        latest = None;
+                        None
+                    }
+                };
+                if report.send(st).is_err() {
+                    return;
+                }
+                if let Some(d) = delay {
+                    match incoming.recv_timeout(d) {
+                        Ok(s) => latest = Some(s),
+                        Err(mpsc::RecvTimeoutError::Timeout) => {}
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            }
+        });
+        Self { sessions, status, last_sent: None }
+    }
+
+    /// Hand over Today's sessions when they changed since the last call.
+    pub fn observe(&mut self, sessions: Vec<LocalSession>) {
+        if self.last_sent.as_ref() == Some(&sessions) {
+            return;
+        }
+        let _ = self.sessions.send(sessions.clone());
+        self.last_sent = Some(sessions);
+    }
+
+    /// The latest status reported since the previous call, if any.
+    pub fn try_status(&self) -> Option<SyncStatus> {
+        self.status.try_iter().last()
+    }
+}
+
+/// Toggl sync for the TUI. `None` from `start` when Toggl is not configured.
+pub struct TogglSync {
+    worker: SyncWorker,
+}
+
+impl TogglSync {
+    pub fn start(state_path: &Path) -> Result<Option<Self>> {
+        let Some(cfg) = TogglConfig::from_env()? else {
+            return Ok(None);
+        };
+        Ok(Some(Self { worker: SyncWorker::spawn(cfg, sidecar_path_for(state_path)) }))
+    }
+
+    /// Push Today's sessions if they changed and show the worker's latest status.
+    pub fn update(&mut self, app: &mut App) {
+        self.worker.observe(local_sessions(&app.day.tasks, crate::date::today_ymd()));
+        if let Some(st) = self.worker.try_status() {
+            app.set_sync_notice(SOURCE, st.notice());
+        }
+    }
+}
+
+/// Push Today's sessions of `app` on this thread (headless commands). `None` when Toggl
+/// is not configured.
+pub fn sync_now(state_path: &Path, app: &App) -> Result<Option<SyncStatus>> {
+    let Some(cfg) = TogglConfig::from_env()? else {
+        return Ok(None);
+    };
+    let sessions = local_sessions(&app.day.tasks, crate::date::today_ymd());
+    push(&mut TogglClient::new(cfg), &sidecar_path_for(state_path), &sessions).map(Some)
+}
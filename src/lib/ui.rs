@@ -87,19 +87,23 @@ pub fn draw(f: &mut Frame, app: &App) {
         View::Today => app.day.tasks.clone(),
         View::Future => app.tomorrow_tasks().clone(),
     };
+    let (content, detail) = split_detail_pane(app, chunks[content_idx]);
     if tasks_slice.is_empty() {
         let para = Paragraph::new("No tasks — press 'i' to add");
-        f.render_widget(para, chunks[content_idx]);
+        f.render_widget(para, content);
     } else {
         match app.display_mode() {
-            DisplayMode::List => {
-                let table = build_task_table(now, app, &tasks_slice);
-                f.render_widget(table, chunks[content_idx]);
-            }
+            DisplayMode::List => match filtered_empty_message(app) {
+                Some(msg) => f.render_widget(Paragraph::new(msg), content),
+                None => {
+                    let table = build_task_table(now, app, &tasks_slice);
+                    f.render_widget(table, content);
+                }
+            },
             DisplayMode::Calendar => {
                 render_calendar_day_at(
                     f,
-                    chunks[content_idx],
+                    content,
                     app,
                     &tasks_slice,
                     crate::clock::system_now_minutes(),
@@ -107,6 +111,9 @@ pub fn draw(f: &mut Frame, app: &App) {
             }
         }
     }
+    if let Some(rect) = detail {
+        render_detail_pane(f, app, rect, crate::clock::system_now_minutes());
+    }
 
     // Help block with 24h gauge at the top of the help area (help text below)
     // When an active banner is present, help resides at the last chunk, not index 2.
@@ -270,13 +277,19 @@ pub fn draw(f: &mut Frame, app: &App) {
         f.render_widget(Clear, popup);
         f.render_widget(block.clone(), popup);
         let inner = block.inner(popup);
-        let buf = app.input_buffer().unwrap_or("");
-        let msg = format!("Title: {} _", buf);
         let msg_rect = Rect { x: inner.x, y: inner.y, width: inner.width, height: 1 };
-        f.render_widget(
-            Paragraph::new(Span::styled(msg, Style::default().fg(Color::Cyan))),
-            msg_rect,
-        );
+        render_line_input(f, app, msg_rect, "Title: ", Style::default().fg(Color::Cyan));
+        // Fields picked up from quick-add words, or the grammar while there are none
+        let today = crate::date::today_ymd();
+        let spec =
+            crate::quick_add::QuickAdd::parse(app.input_buffer().unwrap_or(""), &app.config, today);
+        let preview = if spec.has_fields() {
+            Span::styled(spec.summary(&app.config, today), Style::default().fg(Color::Cyan))
+        } else {
+            Span::styled("45m @category ^HH:MM >date #tag", Style::default().fg(Color::DarkGray))
+        };
+        let preview_rect = Rect { x: inner.x, y: inner.y + 1, width: inner.width, height: 1 };
+        f.render_widget(Paragraph::new(preview), preview_rect);
         let (add, cancel) = input_popup_button_hitboxes(app, popup);
         let btn_y = add.y;
         let mut spans: Vec<Span> = Vec::new();
@@ -314,12 +327,33 @@ pub fn draw(f: &mut Frame, app: &App) {
         f.render_widget(Clear, popup);
         f.render_widget(block.clone(), popup);
         let inner = block.inner(popup);
-        let buf = app.input_buffer().unwrap_or("");
         let title = app.day.tasks.get(app.selected_index()).map(|t| t.title.as_str()).unwrap_or("");
         let suffix = if title.is_empty() { "".to_string() } else { format!(" — {}", title) };
-        let msg = format!("Command: {} _{}", buf, suffix);
-        let msg_rect = Rect { x: inner.x, y: inner.y, width: inner.width, height: 1 };
-        f.render_widget(Paragraph::new(Span::styled(msg, border)), msg_rect);
+        let suffix_w = (suffix.width() as u16).min(inner.width / 2);
+        let prompt_w = inner.width.saturating_sub(suffix_w);
+        let msg_rect = Rect { x: inner.x, y: inner.y, width: prompt_w, height: 1 };
+        render_line_input(f, app, msg_rect, "Command: ", border);
+        let suffix_rect = Rect { x: inner.x + prompt_w, y: inner.y, width: suffix_w, height: 1 };
+        f.render_widget(Paragraph::new(Span::styled(suffix, border)), suffix_rect);
+        // Error of the last Enter, Tab candidates, or usage of the command being typed
+        let hint = match app.command_feedback() {
+            Some(crate::app::CommandFeedback::Error(e)) => {
+                Span::styled(e.clone(), Style::default().fg(Color::Red))
+            }
+            Some(crate::app::CommandFeedback::Candidates(c)) => {
+                Span::styled(c.join("  "), Style::default().fg(Color::DarkGray))
+            }
+            Some(crate::app::CommandFeedback::Done(msg)) => {
+                Span::styled(msg.clone(), Style::default().fg(Color::Green))
+            }
+            None => Span::styled(
+                crate::command::help_for(app.input_buffer().unwrap_or(""))
+                    .unwrap_or_else(|| "Tab: complete".to_string()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+        let hint_rect = Rect { x: inner.x, y: inner.y + 1, width: inner.width, height: 1 };
+        f.render_widget(Paragraph::new(hint), hint_rect);
         let (run, cancel) = command_popup_button_hitboxes(app, popup);
         let btn_y = run.y;
         let mut spans: Vec<Span> = Vec::new();
@@ -362,7 +396,7 @@ pub fn draw(f: &mut Frame, app: &App) {
             .input_buffer()
             .and_then(|s| s.parse::<u16>().ok())
             .or_else(|| app.new_task_default_estimate())
-            .unwrap_or(25);
+            .unwrap_or(crate::quick_add::DEFAULT_ESTIMATE_MIN);
         let title = app.new_task_title().unwrap_or("");
         let msg = if title.is_empty() {
             format!("Estimate: {}m", cur_est)
@@ -409,6 +443,12 @@ pub fn draw(f: &mut Frame, app: &App) {
         f.render_widget(Paragraph::new(Line::from(spans)), btn_rect);
     }
 
+    // Overlay: session editor (s) and note editor (n)
+    render_session_editor_popup(f, app, area, crate::clock::system_now_minutes());
+    render_note_popup(f, app, area);
+    render_title_popup(f, app, area);
+    render_paste_popup(f, app, area);
+
     // Overlay: centered delete confirmation popup with colored text + styled buttons
     if app.is_confirm_delete() {
         let popup = compute_delete_popup_rect(app, area).unwrap();
@@ -542,28 +582,32 @@ pub fn draw_with_clock(f: &mut Frame, app: &App, clock: &dyn Clock) {
         View::Today => app.day.tasks.clone(),
         View::Future => app.tomorrow_tasks().clone(),
     };
+    let (content, detail) = match chunks.get(content_idx) {
+        Some(c) => split_detail_pane(app, *c),
+        None => (Rect::default(), None),
+    };
     if tasks_slice.is_empty() {
         let para = Paragraph::new("No tasks — press 'i' to add");
-        if chunks.len() > content_idx && chunks[content_idx].height > 0 {
-            f.render_widget(para, chunks[content_idx]);
+        if content.height > 0 {
+            f.render_widget(para, content);
         }
-    } else if chunks.len() > content_idx && chunks[content_idx].height > 0 {
+    } else if content.height > 0 {
         match app.display_mode() {
-            DisplayMode::List => {
-                let table = build_task_table(now, app, &tasks_slice);
-                f.render_widget(table, chunks[content_idx]);
-            }
+            DisplayMode::List => match filtered_empty_message(app) {
+                Some(msg) => f.render_widget(Paragraph::new(msg), content),
+                None => {
+                    let table = build_task_table(now, app, &tasks_slice);
+                    f.render_widget(table, content);
+                }
+            },
             DisplayMode::Calendar => {
-                render_calendar_day_at(
-                    f,
-                    chunks[content_idx],
-                    app,
-                    &tasks_slice,
-                    clock.now_minutes(),
-                );
+                render_calendar_day_at(f, content, app, &tasks_slice, clock.now_minutes());
             }
         }
     }
+    if let Some(rect) = detail {
+        render_detail_pane(f, app, rect, clock.now_minutes());
+    }
 
     // Help block with gauge on top under injected clock
     let help_idx = chunks.len().saturating_sub(1);
@@ -684,6 +728,10 @@ pub fn draw_with_clock(f: &mut Frame, app: &App, clock: &dyn Clock) {
         let btn_rect = Rect { x: inner.x, y: btn_y, width: inner.width, height: 1 };
         f.render_widget(Paragraph::new(Line::from(spans)), btn_rect);
     }
+    render_session_editor_popup(f, app, area, now);
+    render_note_popup(f, app, area);
+    render_title_popup(f, app, area);
+    render_paste_popup(f, app, area);
 }
 
 // Tab metadata for the date views (Past/Today/Future).
@@ -717,7 +765,7 @@ pub fn format_task_lines_at(now_min: u16, app: &App) -> Vec<String> {
 /// - `cursor` starts at `now_min` (typically day_start)
 /// - For each task in order: if it has `fixed_start_min`, set `cursor = max(cursor, fixed)`.
 ///   The task's start is `cursor`; then advance `cursor += estimate_min`.
-fn compute_planned_starts(now_min: u16, tasks: &[crate::task::Task]) -> Vec<u16> {
+pub(crate) fn compute_planned_starts(now_min: u16, tasks: &[crate::task::Task]) -> Vec<u16> {
     let mut cursor = now_min;
     let mut out: Vec<u16> = Vec::with_capacity(tasks.len());
     for t in tasks.iter() {
@@ -804,7 +852,9 @@ fn build_task_table(now_min: u16, app: &App, tasks_slice: &[crate::task::Task])
     let dragging = app.is_dragging();
     let drag_from = app.drag_source_index();
     let pulse_on = app.pulse_on();
-    for (i, t) in tasks_slice.iter().enumerate() {
+    // Planned starts are computed over all tasks; a tag filter only hides rows
+    for i in app.visible_indices().into_iter().filter(|&i| i < tasks_slice.len()) {
+        let t = &tasks_slice[i];
         let hh = (starts[i] / 60) % 24;
         let mm = starts[i] % 60;
         let mut planned_cell = Cell::from(format!("{:02}:{:02}", hh, mm));
@@ -828,7 +878,7 @@ fn build_task_table(now_min: u16, app: &App, tasks_slice: &[crate::task::Task])
         spans.push(state_icon_span(t.state));
         spans.push(Span::raw(" "));
         // Category colored dot (configurable)
-        let cat_color = app.config.category_color(t.category);
+        let cat_color = app.config.category_color(&t.category);
         spans.push(Span::styled("●".to_string(), Style::default().fg(cat_color)));
         spans.push(Span::raw(" "));
         // Title color: by category; Done overrides to gray. Keep strikethrough for Done.
@@ -839,7 +889,11 @@ fn build_task_table(now_min: u16, app: &App, tasks_slice: &[crate::task::Task])
             Style::default()
         };
         title_style = title_style.fg(title_fg);
-        spans.push(Span::styled(t.title.clone(), title_style));
+        spans.extend(highlighted_title_spans(&t.title, app.search_query(), title_style));
+        for tag in &t.tags {
+            spans.push(Span::raw(" "));
+            spans.push(tag_badge_span(tag));
+        }
         let title_cell = Cell::from(Line::from(spans));
         // New dedicated estimate column
         let est_cell = Cell::from(format!("{}m", t.estimate_min));
@@ -882,7 +936,10 @@ fn build_task_table(now_min: u16, app: &App, tasks_slice: &[crate::task::Task])
     let header = Row::new(vec![
         Cell::from("Plan"),
         Cell::from("Est"),
-        Cell::from("Task"),
+        Cell::from(match app.tag_filter() {
+            Some(tag) => format!("Task #{}", tag),
+            None => "Task".to_string(),
+        }),
         Cell::from("Act"),
         Cell::from("Actual"),
     ])
@@ -901,6 +958,42 @@ fn build_task_table(now_min: u16, app: &App, tasks_slice: &[crate::task::Task])
     Table::new(rows, widths).header(header).column_spacing(1).block(Block::default())
 }
 
+/// Title split into spans with search hits highlighted.
+fn highlighted_title_spans(title: &str, query: Option<&str>, style: Style) -> Vec<Span<'static>> {
+    let ranges = query.map(|q| crate::search::title_match_ranges(title, q)).unwrap_or_default();
+    let hit_style = style.fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for (s, e) in ranges {
+        if s > pos {
+            spans.push(Span::styled(title[pos..s].to_string(), style));
+        }
+        spans.push(Span::styled(title[s..e].to_string(), hit_style));
+        pos = e;
+    }
+    if pos < title.len() || spans.is_empty() {
+        spans.push(Span::styled(title[pos..].to_string(), style));
+    }
+    spans
+}
+
+/// Placeholder when a tag filter or search hides every task of the view.
+fn filtered_empty_message(app: &App) -> Option<String> {
+    if !app.visible_indices().is_empty() {
+        return None;
+    }
+    match (app.search_query(), app.tag_filter()) {
+        (Some(q), _) => Some(format!("No tasks match '{}' — Esc to clear the search", q)),
+        (None, Some(tag)) => Some(format!("No tasks tagged #{} — ':filter off' to show all", tag)),
+        (None, None) => None,
+    }
+}
+
+/// Compact `#tag` badge shown after the task title.
+fn tag_badge_span(tag: &str) -> Span<'static> {
+    Span::styled(format!("#{}", tag), Style::default().fg(Color::White).bg(Color::DarkGray))
+}
+
 fn state_icon(state: TaskState) -> &'static str {
     match state {
         TaskState::Planned => " ",
@@ -968,6 +1061,13 @@ pub fn header_title_line(now_min: u16, app: &App) -> Line<'static> {
     line.spans.push(pill("Act", Color::Magenta));
     line.spans.push(Span::raw(" "));
     line.spans.push(val(format!("{}m {}s", act_m, act_s), Color::Magenta));
+    // External sync problem (queued write-backs), until the next successful delivery
+    if let Some(notice) = app.sync_notice() {
+        line.spans.push(Span::styled("  |  ", sep_style));
+        line.spans.push(pill("Sync", Color::Red));
+        line.spans.push(Span::raw(" "));
+        line.spans.push(Span::styled(notice, Style::default().fg(Color::Red)));
+    }
 
     line
 }
@@ -975,28 +1075,7 @@ pub fn header_title_line(now_min: u16, app: &App) -> Line<'static> {
 // ---- Category picker UI helpers ----
 
 pub fn category_options(app: &App) -> Vec<(String, Color, crate::task::Category)> {
-    vec![
-        (
-            app.config.category_name(crate::task::Category::General),
-            app.config.category_color(crate::task::Category::General),
-            crate::task::Category::General,
-        ),
-        (
-            app.config.category_name(crate::task::Category::Work),
-            app.config.category_color(crate::task::Category::Work),
-            crate::task::Category::Work,
-        ),
-        (
-            app.config.category_name(crate::task::Category::Home),
-            app.config.category_color(crate::task::Category::Home),
-            crate::task::Category::Home,
-        ),
-        (
-            app.config.category_name(crate::task::Category::Hobby),
-            app.config.category_color(crate::task::Category::Hobby),
-            crate::task::Category::Hobby,
-        ),
-    ]
+    app.config.categories.selectable().map(|e| (e.name.clone(), e.color, e.id.clone())).collect()
 }
 
 pub fn compute_category_popup_rect(app: &App, area: Rect) -> Option<Rect> {
@@ -1166,6 +1245,9 @@ pub fn help_items_for(app: &App) -> Vec<String> {
     if app.is_confirm_delete() {
         return vec!["Enter/y: delete".to_string(), "Esc/n: cancel".to_string()];
     }
+    if app.is_confirm_paste() {
+        return vec!["Enter/y: create tasks".to_string(), "Esc/n: cancel".to_string()];
+    }
     if app.is_start_time_edit() {
         return vec![
             "Enter: OK".to_string(),
@@ -1174,6 +1256,27 @@ pub fn help_items_for(app: &App) -> Vec<String> {
             "click slider: set time".to_string(),
         ];
     }
+    if app.is_note_edit() {
+        return vec![
+            "Enter: save".to_string(),
+            "Shift/Alt+Enter: newline".to_string(),
+            "Esc: cancel".to_string(),
+            "type/backspace: edit".to_string(),
+        ];
+    }
+    if app.is_session_edit() {
+        return vec![
+            "Enter: OK".to_string(),
+            "Esc: cancel".to_string(),
+            "j/k: session".to_string(),
+            "Tab: start/end".to_string(),
+            "Left/Right: +/-5m".to_string(),
+            ".,: +/-1m".to_string(),
+            "a: add".to_string(),
+            "s: split".to_string(),
+            "d: delete".to_string(),
+        ];
+    }
     if app.is_estimate_editing() {
         return vec![
             "Enter: OK".to_string(),
@@ -1193,19 +1296,30 @@ pub fn help_items_for(app: &App) -> Vec<String> {
             "click < >: date".to_string(),
         ];
     }
-    if app.is_command_mode() {
-        return vec![
-            "Enter: run".to_string(),
-            "Esc: cancel".to_string(),
-            "type/backspace: edit".to_string(),
-        ];
-    }
-    if app.is_text_input_mode() {
-        return vec![
-            "Enter: next".to_string(),
-            "Esc: cancel".to_string(),
-            "type/backspace: edit".to_string(),
-        ];
+    // Single-line inputs share the line editor keys
+    let line_input: Option<&[&str]> = if app.is_command_mode() {
+        Some(&["Enter: run", "Tab: complete", "Esc: cancel"])
+    } else if app.is_title_edit() {
+        Some(&["Enter: save", "Esc: cancel"])
+    } else if app.is_search_mode() {
+        Some(&["Enter: keep filter", "Esc: clear"])
+    } else if app.is_text_input_mode() {
+        Some(&["Enter: next", "Esc: cancel"])
+    } else {
+        None
+    };
+    if let Some(submit) = line_input {
+        return submit
+            .iter()
+            .copied()
+            .chain([
+                "Left/Right/Home/End: move",
+                "Ctrl+Left/Right: word",
+                "Ctrl+W/U/K: delete word/to start/to end",
+                "Up/Down: history",
+            ])
+            .map(str::to_string)
+            .collect();
     }
 
     // Default (no popup): view‑aware general help
@@ -1258,13 +1372,26 @@ pub fn help_items_for(app: &App) -> Vec<String> {
             // Minimal + category operations now available
             items.push(format!("{}: category", join(&km.category_cycle)));
             items.push(format!("{}: picker", join(&km.category_picker)));
+            items.push(format!("{}: sessions", join(&km.session_editor)));
+            items.push(format!("{}: detail", join(&km.detail)));
+            items.push(format!("{}: note", join(&km.edit_note)));
+            items.push(format!("{}: rename", join(&km.rename)));
+            items.push(format!("{}: search", join(&km.search)));
         }
         View::Future => {
             items.push(format!("{}: bring", join(&km.bring_to_today)));
             items.push(format!("{}: category", join(&km.category_cycle)));
             items.push(format!("{}: picker", join(&km.category_picker)));
+            items.push(format!("{}: detail", join(&km.detail)));
+            items.push(format!("{}: note", join(&km.edit_note)));
+            items.push(format!("{}: rename", join(&km.rename)));
+            items.push(format!("{}: search", join(&km.search)));
         }
     }
+    if app.search_query().is_some() {
+        items.push(format!("{}/{}: next/prev hit", join(&km.search_next), join(&km.search_prev)));
+        items.push("Esc: clear search".to_string());
+    }
     items
 }
 
@@ -1347,6 +1474,25 @@ fn render_tabs_line(f: &mut Frame, rect: Rect, app: &App) {
             line.spans.push(Span::styled("│".to_string(), Style::default().fg(Color::DarkGray)));
         }
     }
+    // Filters of the current view (after the tabs so tab hitboxes are unaffected)
+    if let Some(tag) = app.tag_filter() {
+        line.spans.push(Span::raw("  "));
+        line.spans.push(tag_badge_span(tag));
+    }
+    let filter_style = Style::default().fg(Color::Black).bg(Color::Yellow);
+    if app.is_search_mode() {
+        // The prompt takes the rest of the line
+        line.spans.push(Span::raw("  "));
+        let used = (line.width() as u16).min(rect.width);
+        f.render_widget(Paragraph::new(line), rect);
+        let prompt = Rect { x: rect.x + used, width: rect.width - used, ..rect };
+        render_line_input(f, app, prompt, "/", filter_style);
+        return;
+    }
+    if let Some(q) = app.search_query() {
+        line.spans.push(Span::raw("  "));
+        line.spans.push(Span::styled(format!("/{}", q), filter_style));
+    }
     let para = Paragraph::new(line);
     f.render_widget(para, rect);
 }
@@ -1370,7 +1516,7 @@ fn render_bottom_24h_gauge(f: &mut Frame, app: &App, rect: Rect, now_min: u16) {
         View::Future => app.tomorrow_tasks().clone(),
     };
     for t in tasks_slice.iter() {
-        let cat_color = app.config.category_color(t.category);
+        let cat_color = app.config.category_color(&t.category);
         for s in t.sessions.iter() {
             let s_min = s.start_min.min(23 * 60 + 59);
             let e_min = s.end_min.unwrap_or(now_min).min(23 * 60 + 59);
@@ -1592,8 +1738,8 @@ pub fn compute_input_popup_rect(app: &App, area: Rect) -> Option<Rect> {
     let buf = app.input_buffer().unwrap_or("");
     let msg = format!("Title: {} _", buf);
     let content_w = UnicodeWidthStr::width(msg.as_str()) as u16;
-    let popup_w = content_w.saturating_add(4).min(inner.width).max(30).min(inner.width);
-    let popup_h: u16 = 4; // message + buttons
+    let popup_w = content_w.saturating_add(4).min(inner.width).max(36).min(inner.width);
+    let popup_h: u16 = 5; // message + quick-add preview + buttons
     let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
     let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
     Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
@@ -1611,7 +1757,7 @@ pub fn compute_new_task_estimate_popup_rect(app: &App, area: Rect) -> Option<Rec
         .input_buffer()
         .and_then(|s| s.parse::<u16>().ok())
         .or_else(|| app.new_task_default_estimate())
-        .unwrap_or(25);
+        .unwrap_or(crate::quick_add::DEFAULT_ESTIMATE_MIN);
     let title = app.new_task_title().unwrap_or("");
     let msg = if title.is_empty() {
         format!("Estimate: {}m", est)
@@ -1642,8 +1788,8 @@ pub fn compute_command_popup_rect(app: &App, area: Rect) -> Option<Rect> {
     let suffix = if title.is_empty() { "".to_string() } else { format!(" — {}", title) };
     let content = format!("Command: {} _{}", buf, suffix);
     let content_w = content.width() as u16;
-    let popup_w = content_w.saturating_add(4).min(inner.width).max(30).min(inner.width);
-    let popup_h: u16 = 4; // message + buttons
+    let popup_w = content_w.saturating_add(4).min(inner.width).max(48).min(inner.width);
+    let popup_h: u16 = 5; // message + hint + buttons
     let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
     let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
     Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
@@ -1754,6 +1900,167 @@ pub fn compute_start_time_popup_rect(app: &App, area: Rect) -> Option<Rect> {
     Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
 }
 
+// Session editor popup: title, column header, one row per session, slider, buttons.
+const SESSION_POPUP_CHROME: u16 = 6; // borders + title + header + slider + buttons
+const SESSION_START_COL: u16 = 6; // "› " + "#" (2) + 2 spaces
+const SESSION_END_COL: u16 = 13;
+
+pub fn compute_session_popup_rect(app: &App, area: Rect) -> Option<Rect> {
+    let ed = app.session_editor().filter(|_| app.is_session_edit())?;
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if inner.width < 30 || inner.height < SESSION_POPUP_CHROME + 1 {
+        return None;
+    }
+    let rows = (ed.sessions.len().max(1) as u16).min(inner.height - SESSION_POPUP_CHROME);
+    let msg = session_popup_message(ed);
+    let content_w = UnicodeWidthStr::width(msg.as_str()) as u16;
+    let popup_w = content_w.saturating_add(4).max(40).min(inner.width);
+    let popup_h = rows + SESSION_POPUP_CHROME;
+    let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
+    let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
+    Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
+}
+
+/// Clickable regions of the session editor popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionEditorHitboxes {
+    /// Visible rows as (session index, start cell, end cell).
+    pub rows: Vec<(usize, Rect, Rect)>,
+    pub track: Rect,
+    pub ok: Rect,
+    pub cancel: Rect,
+}
+
+pub fn session_editor_hitboxes(app: &App, popup: Rect) -> SessionEditorHitboxes {
+    let inner = Rect {
+        x: popup.x + 1,
+        y: popup.y + 1,
+        width: popup.width.saturating_sub(2),
+        height: popup.height.saturating_sub(2),
+    };
+    let visible = popup.height.saturating_sub(SESSION_POPUP_CHROME);
+    let (first, len) = app
+        .session_editor()
+        .map_or((0, 0), |ed| (session_rows_offset(ed.cursor, visible as usize), ed.sessions.len()));
+    let rows_y = inner.y + 2;
+    let rows = (first..len.min(first + visible as usize))
+        .enumerate()
+        .map(|(row, i)| {
+            let y = rows_y + row as u16;
+            let start = Rect { x: inner.x + SESSION_START_COL, y, width: 5, height: 1 };
+            let end = Rect { x: inner.x + SESSION_END_COL, y, width: 5, height: 1 };
+            (i, start, end)
+        })
+        .collect();
+    let track_y = rows_y + visible;
+    let track =
+        Rect { x: inner.x + 2, y: track_y, width: inner.width.saturating_sub(4), height: 1 };
+    let ok_w = UnicodeWidthStr::width("OK") as u16;
+    let ca_w = UnicodeWidthStr::width("Cancel") as u16;
+    let start_x = inner.x + (inner.width.saturating_sub(ok_w + 2 + ca_w)) / 2;
+    let ok = Rect { x: start_x, y: track_y + 1, width: ok_w, height: 1 };
+    let cancel = Rect { x: start_x + ok_w + 2, y: track_y + 1, width: ca_w, height: 1 };
+    SessionEditorHitboxes { rows, track, ok, cancel }
+}
+
+// First session row shown so the cursor stays visible.
+fn session_rows_offset(cursor: usize, visible: usize) -> usize {
+    (cursor + 1).saturating_sub(visible.max(1))
+}
+
+fn session_popup_message(ed: &crate::session_edit::SessionEditor) -> String {
+    let total: u16 =
+        ed.sessions.iter().filter_map(|s| Some(s.end_min?.saturating_sub(s.start_min))).sum();
+    format!("Sessions: {} ({}m) — {}", ed.sessions.len(), total, ed.title)
+}
+
+fn render_session_editor_popup(f: &mut Frame, app: &App, area: Rect, now_min: u16) {
+    use crate::session_edit::SessionField;
+    let (Some(popup), Some(ed)) = (compute_session_popup_rect(app, area), app.session_editor())
+    else {
+        return;
+    };
+    let border = Style::default().fg(Color::Cyan);
+    let title_line = Line::from(Span::styled(" Sessions ", border.add_modifier(Modifier::BOLD)));
+    let block = Block::default().borders(Borders::ALL).title(title_line).border_style(border);
+    f.render_widget(Clear, popup);
+    f.render_widget(block.clone(), popup);
+    let inner = block.inner(popup);
+    let line_at = |y: u16| Rect { x: inner.x, y, width: inner.width, height: 1 };
+    f.render_widget(
+        Paragraph::new(Span::styled(session_popup_message(ed), border)),
+        line_at(inner.y),
+    );
+    let header = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    f.render_widget(
+        Paragraph::new(Span::styled("   #  Start  End     Dur", header)),
+        line_at(inner.y + 1),
+    );
+    let boxes = session_editor_hitboxes(app, popup);
+    if ed.sessions.is_empty() {
+        let hint = Span::styled("(no sessions — a: add)", Style::default().fg(Color::DarkGray));
+        f.render_widget(Paragraph::new(hint), line_at(inner.y + 2));
+    }
+    let hhmm = |m: u16| format!("{:02}:{:02}", m / 60, m % 60);
+    for (i, start_rect, _end) in &boxes.rows {
+        let s = ed.sessions[*i];
+        let selected = *i == ed.cursor;
+        let cell = |field: SessionField, text: String| {
+            if selected && ed.field == field {
+                Span::styled(
+                    text,
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::raw(text)
+            }
+        };
+        let end_text = s.end_min.map_or_else(|| "(now)".to_string(), hhmm);
+        let dur = s.end_min.unwrap_or(now_min).saturating_sub(s.start_min);
+        let line = Line::from(vec![
+            Span::raw(format!("{}{:>2}  ", if selected { "› " } else { "  " }, i + 1)),
+            cell(SessionField::Start, hhmm(s.start_min)),
+            Span::raw("  "),
+            cell(SessionField::End, end_text),
+            Span::raw(format!("  {:>4}m", dur)),
+        ]);
+        let mut para = Paragraph::new(line);
+        if selected {
+            para = para.style(Style::default().bg(SELECTED_ROW_BG));
+        }
+        f.render_widget(para, line_at(start_rect.y));
+    }
+    let mins = ed.selected_minutes().unwrap_or(now_min);
+    render_time_slider_line(f, boxes.track, mins);
+    let (ok, cancel) = (boxes.ok, boxes.cancel);
+    let mut spans: Vec<Span> = Vec::new();
+    let pad = (ok.x.saturating_sub(inner.x)) as usize;
+    if pad > 0 {
+        spans.push(Span::raw(" ".repeat(pad)));
+    }
+    let ok_style = if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstOk)) {
+        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::Blue).add_modifier(Modifier::BOLD)
+    };
+    spans.push(Span::styled("OK".to_string(), ok_style));
+    let gap = cancel.x.saturating_sub(ok.x + ok.width) as usize;
+    if gap > 0 {
+        spans.push(Span::raw(" ".repeat(gap)));
+    }
+    let cancel_style =
+        if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstCancel)) {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD)
+        };
+    spans.push(Span::styled("Cancel".to_string(), cancel_style));
+    f.render_widget(Paragraph::new(Line::from(spans)), line_at(ok.y));
+}
+
 fn render_time_slider_line(f: &mut Frame, track: Rect, minutes: u16) {
     let min = 0u16;
     let max = 23 * 60 + 59;
@@ -1875,6 +2182,371 @@ fn render_slider_line(f: &mut Frame, track: Rect, minutes: u16) {
 
 /// Compute key layout rectangles used by `draw`, for hit testing and tests.
 /// Returns (tabs, optional active banner, list/content, help) within the inner bordered area.
+// Detail pane: beside the content on wide terminals, below it otherwise.
+const DETAIL_SIDE_MIN_WIDTH: u16 = 100;
+const DETAIL_BOTTOM_MAX_HEIGHT: u16 = 12;
+
+/// Split the content area into (content, detail pane) when the detail pane is open.
+/// Too small areas keep the whole content and hide the pane.
+pub fn split_detail_pane(app: &App, content: Rect) -> (Rect, Option<Rect>) {
+    if !app.detail_open() {
+        return (content, None);
+    }
+    if content.width >= DETAIL_SIDE_MIN_WIDTH {
+        let w = (content.width * 2 / 5).max(36);
+        let main = Rect { width: content.width - w, ..content };
+        let pane = Rect { x: content.x + main.width, width: w, ..content };
+        return (main, Some(pane));
+    }
+    if content.height < MIN_LIST_LINES + 4 {
+        return (content, None);
+    }
+    let h = (content.height / 2).clamp(4, DETAIL_BOTTOM_MAX_HEIGHT);
+    let main = Rect { height: content.height - h, ..content };
+    let pane = Rect { y: content.y + main.height, height: h, ..content };
+    (main, Some(pane))
+}
+
+/// Lines shown in the detail pane for `t`.
+pub fn detail_lines(app: &App, t: &crate::task::Task, now_min: u16) -> Vec<Line<'static>> {
+    let label = Style::default().fg(Color::DarkGray);
+    let hhmm = |m: u16| format!("{:02}:{:02}", m / 60, m % 60);
+    let field = |name: &str, value: Span<'static>| {
+        Line::from(vec![Span::styled(format!("{:<9}", name), label), value])
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        t.title.clone(),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    lines.push(field(
+        "Category",
+        Span::styled(
+            app.config.category_name(&t.category),
+            Style::default().fg(app.config.category_color(&t.category)),
+        ),
+    ));
+    let date = if crate::date::is_valid_ymd(t.planned_ymd) {
+        date_label_for(t.planned_ymd)
+    } else {
+        "-".to_string()
+    };
+    lines.push(field("Date", Span::raw(date)));
+    let mut tags_line = vec![Span::styled(format!("{:<9}", "Tags"), label)];
+    for (i, tag) in t.tags.iter().enumerate() {
+        if i > 0 {
+            tags_line.push(Span::raw(" "));
+        }
+        tags_line.push(tag_badge_span(tag));
+    }
+    if t.tags.is_empty() {
+        tags_line.push(Span::raw("-"));
+    }
+    lines.push(Line::from(tags_line));
+    lines.push(field("Start at", Span::raw(t.fixed_start_min.map_or("-".to_string(), hhmm))));
+    let diff = t.actual_min as i32 - t.estimate_min as i32;
+    lines.push(field(
+        "Est/Act",
+        Span::raw(format!("{}m / {}m ({:+}m)", t.estimate_min, t.actual_min, diff)),
+    ));
+    lines.push(field("Sessions", Span::raw(t.sessions.len().to_string())));
+    for (i, s) in t.sessions.iter().enumerate() {
+        let end = s.end_min.map_or("now".to_string(), hhmm);
+        let dur = s.end_min.unwrap_or(now_min).saturating_sub(s.start_min);
+        lines.push(Line::from(format!(
+            "  {:>2}. {}–{} {:>4}m",
+            i + 1,
+            hhmm(s.start_min),
+            end,
+            dur
+        )));
+    }
+    lines.push(Line::from(Span::styled("Notes", label)));
+    if t.notes.is_empty() {
+        lines.push(Line::from(Span::styled("  (none)", label)));
+    } else {
+        lines.extend(t.notes.lines().map(|l| Line::from(format!("  {}", l))));
+    }
+    lines
+}
+
+fn render_detail_pane(f: &mut Frame, app: &App, rect: Rect, now_min: u16) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(" Detail ", Style::default().add_modifier(Modifier::BOLD)));
+    let para = match app.selected_task() {
+        Some(t) => Paragraph::new(detail_lines(app, t, now_min)),
+        None => {
+            Paragraph::new(Span::styled("No task selected", Style::default().fg(Color::DarkGray)))
+        }
+    };
+    f.render_widget(para.block(block).wrap(Wrap { trim: false }), rect);
+}
+
+// Note editor popup: multi-line text area + OK/Cancel.
+pub fn compute_note_popup_rect(app: &App, area: Rect) -> Option<Rect> {
+    if !app.is_note_edit() {
+        return None;
+    }
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if inner.width < 24 || inner.height < 5 {
+        return None;
+    }
+    let popup_w = (inner.width * 3 / 4).max(40).min(inner.width);
+    let popup_h = (inner.height * 2 / 3).clamp(5, 16).min(inner.height);
+    let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
+    let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
+    Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
+}
+
+pub fn note_popup_button_hitboxes(_app: &App, popup: Rect) -> (Rect, Rect) {
+    let inner = Rect {
+        x: popup.x + 1,
+        y: popup.y + 1,
+        width: popup.width.saturating_sub(2),
+        height: popup.height.saturating_sub(2),
+    };
+    let btn_y = inner.y + inner.height.saturating_sub(1);
+    let ok_w = UnicodeWidthStr::width("OK") as u16;
+    let ca_w = UnicodeWidthStr::width("Cancel") as u16;
+    let start_x = inner.x + (inner.width.saturating_sub(ok_w + 2 + ca_w)) / 2;
+    let ok = Rect { x: start_x, y: btn_y, width: ok_w, height: 1 };
+    let cancel = Rect { x: start_x + ok_w + 2, y: btn_y, width: ca_w, height: 1 };
+    (ok, cancel)
+}
+
+fn render_note_popup(f: &mut Frame, app: &App, area: Rect) {
+    let Some(popup) = compute_note_popup_rect(app, area) else {
+        return;
+    };
+    let border = Style::default().fg(Color::Cyan);
+    let title = app.selected_task().map(|t| t.title.as_str()).unwrap_or("");
+    let title_line = Line::from(Span::styled(
+        format!(" Note — {} ", title),
+        border.add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default().borders(Borders::ALL).title(title_line).border_style(border);
+    f.render_widget(Clear, popup);
+    f.render_widget(block.clone(), popup);
+    let inner = block.inner(popup);
+    // Text area above the buttons; keep the end of the note (cursor) in view
+    let text_h = inner.height.saturating_sub(1);
+    let mut lines: Vec<Line> =
+        app.input_buffer().unwrap_or("").split('\n').map(|l| Line::from(l.to_string())).collect();
+    if let Some(last) = lines.last_mut() {
+        last.spans.push(Span::styled("_", Style::default().fg(Color::Yellow)));
+    }
+    let skip = lines.len().saturating_sub(text_h as usize);
+    let text = Paragraph::new(lines.split_off(skip));
+    f.render_widget(text, Rect { height: text_h, ..inner });
+    let (ok, cancel) = note_popup_button_hitboxes(app, popup);
+    let mut spans: Vec<Span> = Vec::new();
+    let pad = (ok.x.saturating_sub(inner.x)) as usize;
+    if pad > 0 {
+        spans.push(Span::raw(" ".repeat(pad)));
+    }
+    let ok_style = if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstOk)) {
+        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::Blue).add_modifier(Modifier::BOLD)
+    };
+    spans.push(Span::styled("OK".to_string(), ok_style));
+    let gap = cancel.x.saturating_sub(ok.x + ok.width) as usize;
+    if gap > 0 {
+        spans.push(Span::raw(" ".repeat(gap)));
+    }
+    let cancel_style =
+        if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstCancel)) {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD)
+        };
+    spans.push(Span::styled("Cancel".to_string(), cancel_style));
+    f.render_widget(Paragraph::new(Line::from(spans)), Rect { y: ok.y, height: 1, ..inner });
+}
+
+/// Render `label` plus the current single-line input into `rect`, showing the cursor cell
+/// reversed and placing the terminal cursor there (IME candidate windows follow it).
+/// Leading text scrolls out when the cursor would pass the right edge.
+fn render_line_input(f: &mut Frame, app: &App, rect: Rect, label: &str, style: Style) {
+    use unicode_width::UnicodeWidthChar;
+    let Some(ed) = app.line_editor() else {
+        return;
+    };
+    let chars: Vec<char> = ed.text().chars().collect();
+    let cursor = ed.cursor().min(chars.len());
+    let avail = (rect.width as usize).saturating_sub(label.width() + 1);
+    let mut start = 0;
+    let mut before_w = ed.cursor_width();
+    while start < cursor && before_w > avail {
+        before_w -= chars[start].width().unwrap_or(0);
+        start += 1;
+    }
+    let before: String = chars[start..cursor].iter().collect();
+    let at: String = chars.get(cursor).map_or(" ".to_string(), |c| c.to_string());
+    let after: String = chars.iter().skip(cursor + 1).collect();
+    let line = Line::from(vec![
+        Span::styled(label.to_string(), style),
+        Span::styled(before, style),
+        Span::styled(at, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(after, style),
+    ]);
+    f.render_widget(Paragraph::new(line), rect);
+    let cx = rect.x + (label.width() + before_w) as u16;
+    f.set_cursor_position((cx.min(rect.x + rect.width.saturating_sub(1)), rect.y));
+}
+
+// Rename popup: single-line title editor with a movable cursor + OK/Cancel.
+pub fn compute_title_popup_rect(app: &App, area: Rect) -> Option<Rect> {
+    if !app.is_title_edit() {
+        return None;
+    }
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if inner.width < 24 || inner.height < 4 {
+        return None;
+    }
+    let content_w = UnicodeWidthStr::width(app.input_buffer().unwrap_or("")) as u16 + 9;
+    let popup_w = content_w.saturating_add(4).max(40).min(inner.width);
+    let popup_h: u16 = 4; // title line + buttons
+    let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
+    let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
+    Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
+}
+
+pub fn title_popup_button_hitboxes(app: &App, popup: Rect) -> (Rect, Rect) {
+    note_popup_button_hitboxes(app, popup)
+}
+
+fn render_title_popup(f: &mut Frame, app: &App, area: Rect) {
+    let Some(popup) = compute_title_popup_rect(app, area) else {
+        return;
+    };
+    let border = Style::default().fg(Color::Cyan);
+    let title_line = Line::from(Span::styled(" Rename ", border.add_modifier(Modifier::BOLD)));
+    let block = Block::default().borders(Borders::ALL).title(title_line).border_style(border);
+    f.render_widget(Clear, popup);
+    f.render_widget(block.clone(), popup);
+    let inner = block.inner(popup);
+    render_line_input(
+        f,
+        app,
+        Rect { height: 1, ..inner },
+        "Title: ",
+        Style::default().fg(Color::Cyan),
+    );
+
+    let (ok, cancel) = title_popup_button_hitboxes(app, popup);
+    let mut spans: Vec<Span> = Vec::new();
+    let pad = (ok.x.saturating_sub(inner.x)) as usize;
+    if pad > 0 {
+        spans.push(Span::raw(" ".repeat(pad)));
+    }
+    let ok_style = if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstOk)) {
+        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::Blue).add_modifier(Modifier::BOLD)
+    };
+    spans.push(Span::styled("OK".to_string(), ok_style));
+    let gap = cancel.x.saturating_sub(ok.x + ok.width) as usize;
+    if gap > 0 {
+        spans.push(Span::raw(" ".repeat(gap)));
+    }
+    let cancel_style =
+        if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstCancel)) {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD)
+        };
+    spans.push(Span::styled("Cancel".to_string(), cancel_style));
+    f.render_widget(Paragraph::new(Line::from(spans)), Rect { y: ok.y, height: 1, ..inner });
+}
+
+// Multi-line paste confirmation: one row per task to create, then OK/Cancel
+pub fn compute_paste_popup_rect(app: &App, area: Rect) -> Option<Rect> {
+    let tasks = app.paste_preview()?;
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if inner.width < 24 || inner.height < 5 {
+        return None;
+    }
+    let today = crate::date::today_ymd();
+    let content_w =
+        tasks.iter().map(|q| paste_row_text(app, q, today).width()).max().unwrap_or(0).max(24)
+            as u16;
+    let popup_w = content_w.saturating_add(4).max(40).min(inner.width);
+    // header + rows + buttons, rows limited to what fits
+    let rows = (tasks.len() as u16).min(inner.height.saturating_sub(4));
+    let popup_h = (rows + 4).min(inner.height);
+    let px = inner.x + (inner.width.saturating_sub(popup_w)) / 2;
+    let py = inner.y + (inner.height.saturating_sub(popup_h)) / 2;
+    Some(Rect { x: px, y: py, width: popup_w, height: popup_h })
+}
+
+pub fn paste_popup_button_hitboxes(app: &App, popup: Rect) -> (Rect, Rect) {
+    note_popup_button_hitboxes(app, popup)
+}
+
+fn paste_row_text(app: &App, q: &crate::quick_add::QuickAdd, today: u32) -> String {
+    let fields = q.summary(&app.config, today);
+    let title = if q.title.is_empty() { "New Task" } else { q.title.as_str() };
+    if fields.is_empty() {
+        format!("• {title}")
+    } else {
+        format!("• {title}  {fields}")
+    }
+}
+
+fn render_paste_popup(f: &mut Frame, app: &App, area: Rect) {
+    let (Some(popup), Some(tasks)) = (compute_paste_popup_rect(app, area), app.paste_preview())
+    else {
+        return;
+    };
+    let border = Style::default().fg(Color::Cyan);
+    let title_line = Line::from(Span::styled(" Paste ", border.add_modifier(Modifier::BOLD)));
+    let block = Block::default().borders(Borders::ALL).title(title_line).border_style(border);
+    f.render_widget(Clear, popup);
+    f.render_widget(block.clone(), popup);
+    let inner = block.inner(popup);
+    let header = format!("Create {} tasks?  (Enter=Create Esc=Cancel)", tasks.len());
+    f.render_widget(Paragraph::new(Span::styled(header, border)), Rect { height: 1, ..inner });
+    let today = crate::date::today_ymd();
+    let rows = inner.height.saturating_sub(2) as usize;
+    let mut lines: Vec<Line> =
+        tasks.iter().take(rows).map(|q| Line::from(paste_row_text(app, q, today))).collect();
+    if tasks.len() > rows && rows > 0 {
+        lines.pop();
+        lines.push(Line::from(Span::styled(
+            format!("… and {} more", tasks.len() - rows + 1),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let list_rect = Rect { y: inner.y + 1, height: rows as u16, ..inner };
+    f.render_widget(Paragraph::new(lines), list_rect);
+
+    let (ok, cancel) = paste_popup_button_hitboxes(app, popup);
+    let mut spans: Vec<Span> = Vec::new();
+    let pad = (ok.x.saturating_sub(inner.x)) as usize;
+    if pad > 0 {
+        spans.push(Span::raw(" ".repeat(pad)));
+    }
+    let ok_style = if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstOk)) {
+        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
+    };
+    spans.push(Span::styled("OK".to_string(), ok_style));
+    let gap = cancel.x.saturating_sub(ok.x + ok.width) as usize;
+    if gap > 0 {
+        spans.push(Span::raw(" ".repeat(gap)));
+    }
+    let cancel_style =
+        if matches!(app.popup_hover_button(), Some(crate::app::PopupButton::EstCancel)) {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Black).bg(Color::Gray).add_modifier(Modifier::BOLD)
+        };
+    spans.push(Span::styled("Cancel".to_string(), cancel_style));
+    f.render_widget(Paragraph::new(Line::from(spans)), Rect { y: ok.y, height: 1, ..inner });
+}
+
 pub fn compute_layout(app: &App, area: Rect) -> (Rect, Option<Rect>, Rect, Rect) {
     // Replicate the same sizing logic as `draw`.
     // First, account for the outer Block's borders.
@@ -1917,7 +2589,8 @@ pub fn compute_layout(app: &App, area: Rect) -> (Rect, Option<Rect>, Rect, Rect)
     };
     // List/content takes remaining minus help
     let list_height = inner.height.saturating_sub(y - inner.y).saturating_sub(help_height);
-    let list = Rect { x: inner.x, y, width: inner.width, height: list_height };
+    let (list, _detail) =
+        split_detail_pane(app, Rect { x: inner.x, y, width: inner.width, height: list_height });
     let help = Rect {
         x: inner.x,
         y: inner.y + inner.height.saturating_sub(help_height),
@@ -1943,24 +2616,21 @@ fn render_calendar_day_at(
         return;
     }
     let start_min = app_display_base(app);
-    // Planned end accumulates estimates sequentially from day start
-    let mut cur = start_min;
-    let mut planned_ranges: Vec<(u16, u16, String, TaskCategory)> = Vec::new();
-    for t in tasks.iter() {
-        if let Some(fs) = t.fixed_start_min {
-            cur = cur.max(fs);
-        }
-        let s = cur;
-        let e = cur.saturating_add(t.estimate_min);
-        planned_ranges.push((s, e, t.title.clone(), t.category));
-        cur = e;
-    }
+    // Planned blocks follow the list schedule (estimates from day start, fixed starts)
+    let planned_ranges: Vec<(u16, u16, String, TaskCategory)> =
+        compute_planned_starts(start_min, tasks)
+            .into_iter()
+            .zip(tasks)
+            .map(|(s, t)| {
+                (s, s.saturating_add(t.estimate_min), t.title.clone(), t.category.clone())
+            })
+            .collect();
     let mut act_ranges: Vec<(usize, u16, u16, String, TaskCategory, bool)> = Vec::new();
     for (ti, t) in tasks.iter().enumerate() {
         for s in t.sessions.iter() {
             let end = s.end_min.unwrap_or(now_min);
             let closed = s.end_min.is_some();
-            act_ranges.push((ti, s.start_min, end, t.title.clone(), t.category, closed));
+            act_ranges.push((ti, s.start_min, end, t.title.clone(), t.category.clone(), closed));
         }
     }
     // Hide sub-minute work on the calendar: drop zero-minute ranges (start == end)
@@ -2003,7 +2673,7 @@ fn render_calendar_day_at(
         let y0 = to_y(s, rect.height);
         let y1 = to_y(e, rect.height).max(y0);
         // Resolve this block's category color directly from range
-        let this_color = app.config.category_color(cat);
+        let this_color = app.config.category_color(&cat);
         for y in y0..=y1 {
             if let Some(row) = lines_plan.get_mut(y as usize) {
                 *row = "█".repeat(lane_w as usize);
@@ -2115,7 +2785,7 @@ fn render_calendar_day_at(
             }
             if let Some(slot) = act_col_colors.get_mut(yi).and_then(|row| row.get_mut(col)) {
                 if slot.is_none() {
-                    *slot = Some(app.config.category_color(b.cat));
+                    *slot = Some(app.config.category_color(&b.cat));
                 }
             }
         }
@@ -2129,7 +2799,7 @@ fn render_calendar_day_at(
             let cw = col_widths[col] as usize;
             let fitted = fit_to_width(&b.title, cw);
             use unicode_width::UnicodeWidthStr as UW;
-            let w = UW::width(fitted.as_str()) as usize;
+            let w = UW::width(fitted.as_str());
             if let Some(cell) = lines_act_cols.get_mut(yi).and_then(|row| row.get_mut(col)) {
                 let mut sline = String::new();
                 sline.push_str(&fitted);
@@ -2140,7 +2810,7 @@ fn render_calendar_day_at(
             }
             if let Some(slot) = act_col_colors.get_mut(yi).and_then(|row| row.get_mut(col)) {
                 if slot.is_none() {
-                    *slot = Some(app.config.category_color(b.cat));
+                    *slot = Some(app.config.category_color(&b.cat));
                 }
             }
         }
@@ -2148,8 +2818,8 @@ fn render_calendar_day_at(
     // Join columns per row into act strings
     for y in 0..rect.height as usize {
         let mut s = String::new();
-        for c in 0..ncols {
-            s.push_str(&lines_act_cols[y][c]);
+        for seg in lines_act_cols[y].iter().take(ncols) {
+            s.push_str(seg);
         }
         let w = s.chars().count() as u16;
         if w < lane_w {
@@ -11,9 +11,9 @@ category_cycle = "z"
     let cfg = Config::from_toml_str(toml).expect("parse config");
     let mut app = App::with_config(cfg);
     app.add_task("Task", 10);
-    assert!(matches!(app.day.tasks[0].category, Category::General));
+    assert_eq!(app.day.tasks[0].category, Category::GENERAL);
 
     // Press custom key 'z'
     app.handle_key_event(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE));
-    assert!(matches!(app.day.tasks[0].category, Category::Work));
+    assert_eq!(app.day.tasks[0].category, Category::WORK);
 }
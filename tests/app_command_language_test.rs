@@ -0,0 +1,131 @@
+use chute_kun::{
+    app::{App, CommandFeedback},
+    date::{add_days_to_ymd, today_ymd},
+    task::Category,
+    ui,
+};
+use crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+}
+
+fn run(app: &mut App, cmd: &str) {
+    app.handle_key(KeyCode::Char(':'));
+    type_text(app, cmd);
+    app.handle_key(KeyCode::Enter);
+}
+
+fn titles(app: &App) -> Vec<&str> {
+    app.day.tasks.iter().map(|t| t.title.as_str()).collect()
+}
+
+#[test]
+fn palette_commands_edit_order_category_and_dates() {
+    let mut app = App::new();
+    for (title, est) in [("A", 30), ("B", 10), ("C", 20), ("D", 5)] {
+        app.add_task(title, est);
+    }
+    run(&mut app, "move 3 top");
+    assert_eq!(titles(&app), ["C", "A", "B", "D"]);
+    assert_eq!(app.selected_index(), 0);
+    run(&mut app, "move down");
+    assert_eq!(titles(&app), ["A", "C", "B", "D"]);
+
+    run(&mut app, "sort by estimate");
+    assert_eq!(titles(&app), ["D", "B", "C", "A"]);
+    assert_eq!(app.selected_index(), 2, "the moved task C stays selected");
+
+    run(&mut app, "cat work");
+    assert_eq!(app.day.tasks[2].category, Category::WORK);
+    run(&mut app, "select 1");
+    run(&mut app, "postpone 3d");
+    assert_eq!(titles(&app), ["B", "C", "A"]);
+    assert_eq!(app.tomorrow_tasks()[0].planned_ymd, add_days_to_ymd(today_ymd(), 3));
+
+    run(&mut app, "date tomorrow");
+    assert_eq!(app.tomorrow_tasks()[1].title, "B");
+    assert_eq!(app.tomorrow_tasks()[1].planned_ymd, add_days_to_ymd(today_ymd(), 1));
+    run(&mut app, "finish");
+    assert!(app.day.tasks[0].done_ymd.is_some());
+
+    // `date today` brings a Future task back; `undo` reverts it like the key
+    run(&mut app, "view future");
+    run(&mut app, "date today");
+    assert_eq!(app.tomorrow_tasks().len(), 1);
+    assert_eq!(app.day.tasks.last().map(|t| t.title.as_str()), Some("D"));
+    run(&mut app, "undo");
+    assert_eq!(app.tomorrow_tasks().len(), 2);
+}
+
+#[test]
+fn errors_keep_the_prompt_open_and_tab_completes() {
+    let mut app = App::new();
+    app.add_task("A", 20);
+    app.day.tasks[0].tags = vec!["deep".into(), "design".into()];
+
+    run(&mut app, "postpone soon");
+    assert!(app.is_command_mode(), "a malformed command stays in the prompt");
+    assert_eq!(app.input_buffer(), Some("postpone soon"));
+    match app.command_feedback() {
+        Some(CommandFeedback::Error(e)) => assert!(e.contains("postpone"), "{e}"),
+        other => panic!("expected an error, got {other:?}"),
+    }
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let popup = ui::compute_command_popup_rect(&app, Rect::new(0, 0, 80, 20)).unwrap();
+    let buf = terminal.backend().buffer().clone();
+    let hint: String =
+        (popup.x + 1..popup.x + popup.width - 1).map(|x| buf[(x, popup.y + 2)].symbol()).collect();
+    assert!(hint.contains("expected a number of days"), "{hint}");
+    assert_eq!(buf[(popup.x + 1, popup.y + 2)].fg, ratatui::style::Color::Red);
+
+    // Unknown commands are reported too
+    app.handle_key(KeyCode::Esc);
+    run(&mut app, "frobnicate");
+    assert!(
+        matches!(app.command_feedback(), Some(CommandFeedback::Error(e)) if e.contains("unknown"))
+    );
+    app.handle_key(KeyCode::Esc);
+
+    // Tab: unique command names complete with a space, shared prefixes list choices
+    app.handle_key(KeyCode::Char(':'));
+    type_text(&mut app, "po");
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.input_buffer(), Some("postpone "));
+    for _ in 0..9 {
+        app.handle_key(KeyCode::Backspace);
+    }
+    type_text(&mut app, "se");
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.input_buffer(), Some("se"));
+    assert_eq!(
+        app.command_feedback(),
+        Some(&CommandFeedback::Candidates(vec![
+            "search".into(),
+            "select".into(),
+            "sessions".into()
+        ]))
+    );
+
+    // Arguments complete from tags and keywords
+    for _ in 0..2 {
+        app.handle_key(KeyCode::Backspace);
+    }
+    type_text(&mut app, "tag +de");
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.input_buffer(), Some("tag +de"));
+    type_text(&mut app, "s");
+    app.handle_key(KeyCode::Tab);
+    assert_eq!(app.input_buffer(), Some("tag +design "));
+    app.handle_key(KeyCode::Enter);
+    assert!(!app.in_input_mode());
+    assert_eq!(app.day.tasks[0].tags, ["deep", "design"]);
+    assert_eq!(
+        chute_kun::command::help_for("sort b").as_deref(),
+        Some("sort by start|estimate|title|category|state — reorder the current list")
+    );
+}
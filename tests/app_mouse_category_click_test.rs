@@ -15,7 +15,7 @@ fn left_click_on_category_dot_cycles_category() {
     app.add_task("Alpha", 20);
 
     // Before: default is General
-    assert!(matches!(app.day.tasks[0].category, Category::General));
+    assert_eq!(app.day.tasks[0].category, Category::GENERAL);
 
     // Render once to discover dot coordinates
     terminal.draw(|f| ui::draw(f, &app)).unwrap();
@@ -35,5 +35,5 @@ fn left_click_on_category_dot_cycles_category() {
     app.handle_mouse_event(ev, full);
 
     // After: category should cycle to Work
-    assert!(matches!(app.day.tasks[0].category, Category::Work));
+    assert_eq!(app.day.tasks[0].category, Category::WORK);
 }
@@ -0,0 +1,62 @@
+use chute_kun::{app::App, task::Category, ui};
+use crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+const CHECKLIST: &str = "- [ ] Draft agenda 15m\n\n* Book room @work\n2. [x] Send invite #team\n";
+
+#[test]
+fn pasted_checklist_is_confirmed_then_created_as_one_undo_step() {
+    let mut app = App::new();
+    app.add_task("Existing", 10);
+    app.handle_paste(CHECKLIST);
+    assert!(app.is_confirm_paste());
+    let titles: Vec<&str> = app.paste_preview().unwrap().iter().map(|q| q.title.as_str()).collect();
+    assert_eq!(titles, ["Draft agenda", "Book room", "Send invite"]);
+
+    let mut terminal = Terminal::new(TestBackend::new(70, 16)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let popup = ui::compute_paste_popup_rect(&app, Rect::new(0, 0, 70, 16)).unwrap();
+    let buf = terminal.backend().buffer().clone();
+    let row = |y: u16| -> String {
+        (popup.x..popup.x + popup.width).map(|x| buf[(x, y)].symbol()).collect()
+    };
+    assert!(row(popup.y + 1).contains("Create 3 tasks?"), "{}", row(popup.y + 1));
+    assert!(row(popup.y + 2).contains("• Draft agenda  15m"), "{}", row(popup.y + 2));
+
+    app.handle_key(KeyCode::Enter);
+    assert!(!app.in_input_mode());
+    let created: Vec<(&str, u16)> =
+        app.day.tasks.iter().map(|t| (t.title.as_str(), t.estimate_min)).collect();
+    assert_eq!(
+        created,
+        [("Existing", 10), ("Draft agenda", 15), ("Book room", 25), ("Send invite", 25)]
+    );
+    assert_eq!(app.day.tasks[2].category, Category::WORK);
+    assert_eq!(app.day.tasks[3].tags, ["team"]);
+
+    app.undo();
+    assert_eq!(app.day.tasks.len(), 1, "the whole batch is a single undo step");
+}
+
+#[test]
+fn cancel_returns_to_the_title_input_and_single_lines_on_the_list_are_ignored() {
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('I'));
+    app.handle_key(KeyCode::Char('x'));
+    app.handle_paste("Call back\nFix printer 10m");
+    assert!(app.is_confirm_paste());
+    app.handle_key(KeyCode::Esc);
+    assert!(app.is_text_input_mode());
+    assert_eq!(app.input_buffer(), Some("x"), "the draft survives a cancelled paste");
+
+    // Confirming from the Interrupt input uses its 15m default
+    app.handle_paste("Call back\nFix printer 10m");
+    app.handle_key(KeyCode::Char('y'));
+    let est: Vec<u16> = app.day.tasks.iter().map(|t| t.estimate_min).collect();
+    assert_eq!(est, [15, 10]);
+
+    // One line pasted on the list does nothing
+    app.handle_paste("  Review PR 20m \n");
+    assert!(!app.in_input_mode());
+    assert_eq!(app.day.tasks.len(), 2);
+}
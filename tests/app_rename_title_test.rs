@@ -0,0 +1,98 @@
+use chute_kun::{app::App, task::Task, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+fn key(app: &mut App, code: KeyCode, mods: KeyModifiers) {
+    app.handle_key_event(KeyEvent::new(code, mods));
+}
+
+#[test]
+fn r_opens_title_editor_with_cursor_and_word_jumps() {
+    let mut app = App::new();
+    app.add_task("Wirte report", 10);
+    key(&mut app, KeyCode::Char('r'), KeyModifiers::NONE);
+    assert!(app.is_title_edit());
+    assert_eq!(app.input_buffer(), Some("Wirte report"));
+    assert_eq!(app.line_editor().map(|e| e.cursor()), Some(12));
+
+    // Fix the typo in the first word: Ctrl+Left twice, then Right past "W"
+    key(&mut app, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(app.line_editor().map(|e| e.cursor()), Some(6));
+    key(&mut app, KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(app.line_editor().map(|e| e.cursor()), Some(0));
+    key(&mut app, KeyCode::Right, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+    for c in "ri".chars() {
+        key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    assert_eq!(app.input_buffer(), Some("Write report"));
+    // Wide (IME-committed) characters insert at the cursor like any other
+    key(&mut app, KeyCode::End, KeyModifiers::NONE);
+    for c in " 週次".chars() {
+        key(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    key(&mut app, KeyCode::Home, KeyModifiers::NONE);
+    app.handle_paste("Draft:\n");
+    assert_eq!(app.line_editor().map(|e| e.cursor()), Some(7));
+
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+
+    key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(!app.in_input_mode());
+    assert_eq!(app.day.tasks[0].title, "Draft: Write report 週次");
+
+    // Esc discards; an empty title keeps the old one
+    key(&mut app, KeyCode::Char('r'), KeyModifiers::NONE);
+    key(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Char('r'), KeyModifiers::NONE);
+    for _ in 0..40 {
+        key(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+    }
+    key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.day.tasks[0].title, "Draft: Write report 週次");
+}
+
+#[test]
+fn right_click_edits_estimate_ctrl_right_click_and_command_rename() {
+    let mut app = App::new();
+    app.add_task("Dentsit", 30);
+
+    let area = Rect::new(0, 0, 60, 12);
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let buf = terminal.backend().buffer().clone();
+    let (_tabs, _banner, list, _help) = ui::compute_layout(&app, area);
+    let row_y = list.y + 1;
+    let title_x = (list.x..list.x + list.width)
+        .find(|&x| buf[(x, row_y)].symbol() == "D")
+        .expect("title in Today list");
+    let right_click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Right),
+        column: title_x + 2,
+        row: row_y,
+        modifiers: KeyModifiers::NONE,
+    };
+    // Right click keeps opening the estimate editor, on the title too
+    app.handle_mouse_event(right_click, area);
+    assert!(app.is_estimate_editing());
+    assert!(!app.is_title_edit());
+    key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+
+    // Ctrl+right click is the context action for renaming
+    app.handle_mouse_event(MouseEvent { modifiers: KeyModifiers::CONTROL, ..right_click }, area);
+    assert!(app.is_title_edit());
+    assert_eq!(app.input_buffer(), Some("Dentsit"));
+    key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+
+    app.add_future_task(Task::new("Dentsit", 30));
+    app.handle_key(KeyCode::Tab); // Future
+    app.handle_key(KeyCode::Char(':'));
+    for c in "rename Dentist  appointment".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+    assert_eq!(app.tomorrow_tasks()[0].title, "Dentist  appointment");
+}
@@ -0,0 +1,56 @@
+use chute_kun::{config::Config, routine::EstimatePolicy, storage};
+
+const SNAPSHOT: &str = r#"
+version = 1
+
+[[routines]]
+id = 3
+title = "Email"
+estimate_min = 30
+anchor_ymd = 20250101
+history = [20]
+
+[routines.recurrence]
+kind = "daily"
+"#;
+
+#[test]
+fn finishing_a_routine_instance_updates_the_next_estimate() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let cfg = Config { routine_estimate: EstimatePolicy::Mean { window: 5 }, ..Config::default() };
+    let mut app = storage::load_from_str(SNAPSHOT, cfg).expect("load");
+    assert_eq!(app.day.tasks.len(), 1);
+
+    app.day.tasks[0].actual_min = 40;
+    app.finish_selected();
+    assert_eq!(app.routines()[0].history, vec![20, 40]);
+    assert_eq!(app.routines()[0].estimate_min, 30);
+    // Finishing an already-Done task again must not log twice
+    app.finish_selected();
+    assert_eq!(app.routines()[0].history.len(), 2);
+
+    // History survives a save/load round-trip and drives tomorrow's instance
+    let saved = storage::save_to_string(&app).expect("save");
+    let mut app = storage::load_from_str(&saved, Config::default()).expect("reload");
+    assert_eq!(app.generate_routine_tasks(20250902), 1);
+    let next = app.day.tasks.iter().find(|t| t.planned_ymd == 20250902).expect("instance");
+    assert_eq!(next.estimate_min, 30);
+}
+
+#[test]
+fn per_routine_policy_overrides_config_and_zero_actuals_are_ignored() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let snap =
+        SNAPSHOT.replace("history = [20]", "history = [20]\nestimate_policy = { kind = \"last\" }");
+    let mut app = storage::load_from_str(&snap, Config::default()).expect("load");
+
+    app.finish_selected();
+    assert_eq!(app.routines()[0].history, vec![20]);
+
+    app.generate_routine_tasks(20250902);
+    let idx = app.day.tasks.iter().position(|t| t.planned_ymd == 20250902).unwrap();
+    app.day.tasks[idx].actual_min = 12;
+    app.day.start(idx);
+    app.finish_active();
+    assert_eq!(app.routines()[0].estimate_min, 12);
+}
@@ -0,0 +1,78 @@
+use chute_kun::{app::App, config::Config, storage};
+use crossterm::event::KeyCode;
+
+const SNAPSHOT: &str = r#"
+version = 1
+
+[[routines]]
+id = 7
+title = "Commute"
+estimate_min = 40
+category = "Work"
+anchor_ymd = 20250101
+
+[routines.recurrence]
+kind = "daily"
+"#;
+
+#[test]
+fn startup_generates_due_routines_once_per_date() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let app = storage::load_from_str(SNAPSHOT, Config::default()).expect("load");
+    assert_eq!(app.day.tasks.len(), 1);
+    assert_eq!(app.day.tasks[0].title, "Commute");
+    assert_eq!(app.day.tasks[0].routine_id, Some(7));
+    assert_eq!(app.routines()[0].last_generated_ymd, Some(20250901));
+
+    // Restarting on the same date must not add another instance,
+    // even if the generated task was deleted in the meantime.
+    let saved = storage::save_to_string(&app).expect("save");
+    let mut reloaded = storage::load_from_str(&saved, Config::default()).expect("reload");
+    assert_eq!(reloaded.day.tasks.len(), 1);
+    reloaded.handle_key(KeyCode::Char('x'));
+    reloaded.handle_key(KeyCode::Enter);
+    let saved = storage::save_to_string(&reloaded).expect("save");
+    let reloaded = storage::load_from_str(&saved, Config::default()).expect("reload");
+    assert!(reloaded.day.tasks.is_empty());
+}
+
+#[test]
+fn routine_command_turns_selected_task_into_routine() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let mut app = App::new();
+    app.add_task("Stretch", 10);
+    app.handle_key(KeyCode::Char(':'));
+    for c in "routine weekdays".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+
+    assert_eq!(app.routines().len(), 1);
+    let id = app.routines()[0].id;
+    assert_eq!(app.day.tasks[0].routine_id, Some(id));
+    // Today's instance already exists: no duplicate for the same date
+    assert_eq!(app.generate_routine_tasks(20250901), 0);
+    // Next weekday produces a fresh instance
+    assert_eq!(app.generate_routine_tasks(20250902), 1);
+    assert_eq!(app.day.tasks.len(), 2);
+
+    // Turning it off detaches the task as well
+    app.handle_key(KeyCode::Char(':'));
+    for c in "routine off".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+    assert!(app.routines().is_empty());
+    assert_eq!(app.day.tasks[0].routine_id, None);
+}
+
+#[test]
+fn invalid_anchor_date_is_repaired_on_load() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let snapshot = SNAPSHOT
+        .replace("anchor_ymd = 20250101", "anchor_ymd = 20251399")
+        .replace("kind = \"daily\"", "kind = \"every_n_days\"\nn = 2");
+    let app = storage::load_from_str(&snapshot, Config::default()).expect("load");
+    assert_eq!(app.routines()[0].anchor_ymd, 20250901);
+    assert_eq!(app.day.tasks.len(), 1);
+}
@@ -0,0 +1,22 @@
+use chute_kun::routine::{Recurrence, Routine};
+use chute_kun::task::Task;
+use chute_kun::{app::App, config::Config};
+
+#[test]
+fn day_rollover_in_tick_generates_routine_tasks() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let mut app = App::with_config(Config::default());
+    let mut r = Routine::from_task(0, &Task::new("Inbox zero", 20), Recurrence::Daily, 20250901);
+    r.last_generated_ymd = Some(20250901);
+    app.add_routine(r);
+    app.tick(1);
+    assert!(app.day.tasks.is_empty(), "same day: nothing to generate");
+
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-02");
+    app.tick(1);
+    assert_eq!(app.day.tasks.len(), 1);
+    assert_eq!(app.day.tasks[0].title, "Inbox zero");
+    assert_eq!(app.day.tasks[0].planned_ymd, 20250902);
+    app.tick(1);
+    assert_eq!(app.day.tasks.len(), 1, "generated exactly once per date");
+}
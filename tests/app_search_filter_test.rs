@@ -0,0 +1,113 @@
+use chute_kun::{app::App, task::Category, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+struct FixedClock(u16);
+impl chute_kun::clock::Clock for FixedClock {
+    fn now_minutes(&self) -> u16 {
+        self.0
+    }
+}
+
+fn key(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn row_text(buf: &ratatui::buffer::Buffer, y: u16) -> String {
+    (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+}
+
+fn sample() -> App {
+    let mut app = App::new();
+    for title in ["Write report", "Lunch", "Review report", "Email"] {
+        app.add_task(title, 10);
+    }
+    app.day.tasks[3].category = Category::WORK;
+    app
+}
+
+#[test]
+fn slash_filters_as_you_type_and_n_jumps_between_hits() {
+    let mut app = sample();
+    key(&mut app, KeyCode::Char('/'));
+    assert!(app.is_search_mode());
+    for c in "REP".chars() {
+        key(&mut app, KeyCode::Char(c));
+        // Typed keys are text, not bindings (`e` would open the estimate editor)
+        assert!(app.is_search_mode());
+    }
+    assert_eq!(app.visible_indices(), [0, 2]);
+    key(&mut app, KeyCode::Enter);
+    assert_eq!(app.search_query(), Some("REP"));
+
+    // `n`/`N` move between hits (wrapping) instead of opening the note editor
+    key(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.selected_index(), 2);
+    assert!(!app.in_input_mode());
+    key(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.selected_index(), 0);
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT));
+    assert_eq!(app.selected_index(), 2);
+
+    // Category name and state match too
+    app.set_search(Some("work".into()));
+    assert_eq!(app.visible_indices(), [3]);
+    assert_eq!(app.selected_index(), 3);
+    app.set_search(Some("planned lunch".into()));
+    assert_eq!(app.visible_indices(), [1]);
+    app.set_search(Some("is:planned lunch".into()));
+    assert_eq!(app.visible_indices(), [1]);
+
+    // Esc clears the search; afterwards `n` edits the note again
+    key(&mut app, KeyCode::Esc);
+    assert_eq!(app.search_query(), None);
+    assert_eq!(app.visible_indices().len(), 4);
+    key(&mut app, KeyCode::Char('n'));
+    assert!(app.is_note_edit());
+}
+
+#[test]
+fn filtered_rows_drive_rendering_and_mouse_selection() {
+    let mut app = sample();
+    app.set_search(Some("report".into()));
+
+    let backend = TestBackend::new(80, 20);
+    let mut terminal = Terminal::new(backend).unwrap();
+    terminal.draw(|f| ui::draw_with_clock(f, &app, &FixedClock(9 * 60))).unwrap();
+    let buf = terminal.backend().buffer().clone();
+    let area = Rect::new(0, 0, 80, 20);
+    let (tabs, _banner, list, _help) = ui::compute_layout(&app, area);
+    assert!(row_text(&buf, tabs.y).contains("/report"), "{}", row_text(&buf, tabs.y));
+    let rows: Vec<String> = (list.y + 1..list.y + 3).map(|y| row_text(&buf, y)).collect();
+    assert!(rows[0].contains("Write report") && rows[1].contains("Review report"), "{:?}", rows);
+    assert!(!row_text(&buf, list.y + 3).contains("Lunch"));
+    // Hit text is highlighted; the rest of the title is not
+    let x = rows[1].find("report").map(|b| rows[1][..b].chars().count()).unwrap() as u16;
+    assert_eq!(buf[(x, list.y + 2)].bg, ratatui::style::Color::Yellow);
+    assert_ne!(buf[(x - 2, list.y + 2)].bg, ratatui::style::Color::Yellow);
+
+    // The second listed row is task index 2
+    let click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 10,
+        row: list.y + 2,
+        modifiers: KeyModifiers::NONE,
+    };
+    app.handle_mouse_event(click, area);
+    assert_eq!(app.selected_index(), 2);
+}
+
+#[test]
+fn state_prefixes_do_not_filter_by_state() {
+    let mut app = sample();
+    app.day.tasks[1].state = chute_kun::task::TaskState::Done;
+    // "d" and "plan" appear in no title, so they match nothing rather than every state
+    app.set_search(Some("d".into()));
+    assert!(app.visible_indices().is_empty());
+    app.set_search(Some("plan".into()));
+    assert!(app.visible_indices().is_empty());
+    app.set_search(Some("is:done".into()));
+    assert_eq!(app.visible_indices(), [1]);
+    app.set_search(Some("done".into()));
+    assert_eq!(app.visible_indices(), [1]);
+}
@@ -0,0 +1,100 @@
+use chute_kun::task::{Session, TaskState};
+use chute_kun::{app::App, ui};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+
+fn done_task_with_session(app: &mut App, start: u16, end: u16) {
+    let idx = app.add_task("A", 30);
+    let t = &mut app.day.tasks[idx];
+    t.sessions = vec![Session { start_min: start, end_min: Some(end) }];
+    t.state = TaskState::Done;
+    t.actual_min = end - start;
+    t.started_at_min = Some(start);
+    t.finished_at_min = Some(end);
+}
+
+fn click(app: &mut App, x: u16, y: u16, area: Rect) {
+    app.handle_mouse_event(
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: x,
+            row: y,
+            modifiers: KeyModifiers::empty(),
+        },
+        area,
+    );
+}
+
+#[test]
+fn edit_insert_split_and_delete_sessions_recompute_actuals() {
+    let mut app = App::new();
+    done_task_with_session(&mut app, 9 * 60, 9 * 60 + 30);
+
+    // Extend the end by 10m and add a missed session right after it
+    app.handle_key(KeyCode::Char('s'));
+    assert!(app.is_session_edit());
+    app.handle_key(KeyCode::Tab);
+    app.handle_key(KeyCode::Right);
+    app.handle_key(KeyCode::Right);
+    app.handle_key(KeyCode::Char('a'));
+    app.handle_key(KeyCode::Enter);
+    assert!(!app.is_session_edit());
+    let t = &app.day.tasks[0];
+    assert_eq!(
+        t.sessions,
+        vec![
+            Session { start_min: 540, end_min: Some(580) },
+            Session { start_min: 580, end_min: Some(595) },
+        ]
+    );
+    assert_eq!(t.actual_min, 55);
+    assert_eq!(t.started_at_min, Some(540));
+    assert_eq!(t.finished_at_min, Some(595));
+
+    // Esc discards edits
+    app.handle_key(KeyCode::Char('s'));
+    app.handle_key(KeyCode::Char('d'));
+    app.handle_key(KeyCode::Esc);
+    assert_eq!(app.day.tasks[0].sessions.len(), 2);
+
+    // Split the first session, then delete its first half
+    app.handle_key(KeyCode::Char('s'));
+    app.handle_key(KeyCode::Char('k'));
+    app.handle_key(KeyCode::Char('s'));
+    app.handle_key(KeyCode::Char('d'));
+    app.handle_key(KeyCode::Enter);
+    let t = &app.day.tasks[0];
+    assert_eq!(
+        t.sessions,
+        vec![
+            Session { start_min: 560, end_min: Some(580) },
+            Session { start_min: 580, end_min: Some(595) },
+        ]
+    );
+    assert_eq!(t.actual_min, 35);
+    assert_eq!(t.started_at_min, Some(560));
+}
+
+#[test]
+fn mouse_selects_end_cell_and_sets_it_from_the_slider() {
+    let mut app = App::new();
+    done_task_with_session(&mut app, 9 * 60, 9 * 60 + 30);
+    app.handle_key(KeyCode::Char('s'));
+
+    let area = Rect { x: 0, y: 0, width: 80, height: 20 };
+    let popup = ui::compute_session_popup_rect(&app, area).expect("session popup");
+    let boxes = ui::session_editor_hitboxes(&app, popup);
+    let (_, _start, end) = boxes.rows[0];
+    click(&mut app, end.x, end.y, area);
+
+    let x = ui::slider_x_for_minutes(boxes.track, 0, 23 * 60 + 59, 5, 10 * 60);
+    click(&mut app, x, boxes.track.y, area);
+    let expected = ui::minutes_from_slider_x(boxes.track, 0, 23 * 60 + 59, 5, x);
+    click(&mut app, boxes.ok.x, boxes.ok.y, area);
+
+    assert!(!app.is_session_edit());
+    let t = &app.day.tasks[0];
+    assert_eq!(t.sessions, vec![Session { start_min: 540, end_min: Some(expected) }]);
+    assert_eq!(t.finished_at_min, Some(expected));
+    assert_eq!(t.actual_min, expected - 540);
+}
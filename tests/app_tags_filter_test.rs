@@ -0,0 +1,68 @@
+use chute_kun::{app::App, config::Config, storage, tags};
+use crossterm::event::KeyCode;
+use tempfile::tempdir;
+
+fn run_command(app: &mut App, cmd: &str) {
+    app.handle_key(KeyCode::Char(':'));
+    for c in cmd.chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+}
+
+#[test]
+fn inline_and_palette_tags_persist_in_snapshot() {
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('i'));
+    for c in "Write report #client-a #Urgent #42".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter); // title -> estimate
+    app.handle_key(KeyCode::Enter); // estimate -> create
+    let t = &app.day.tasks[0];
+    assert_eq!(t.title, "Write report #42");
+    assert_eq!(t.tags, ["client-a", "Urgent"]);
+
+    run_command(&mut app, "tag -urgent +review #client-a");
+    assert_eq!(app.day.tasks[0].tags, ["client-a", "review"]);
+
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("snapshot.toml");
+    storage::save_to_path(&app, &p).unwrap();
+    let loaded = storage::load_from_path(&p, Config::default()).unwrap().unwrap();
+    assert_eq!(loaded.day.tasks[0].tags, ["client-a", "review"]);
+
+    run_command(&mut app, "tag clear");
+    assert!(app.day.tasks[0].tags.is_empty());
+}
+
+#[test]
+fn tag_filter_limits_navigation_and_groups_for_reports() {
+    let mut app = App::new();
+    for (title, tag) in [("A", Some("work")), ("B", None), ("C", Some("Work")), ("D", Some("home"))]
+    {
+        let i = app.add_task(title, 10);
+        if let Some(tag) = tag {
+            app.day.tasks[i].tags.push(tag.to_string());
+        }
+    }
+    app.handle_key(KeyCode::Down); // select B (untagged)
+
+    run_command(&mut app, "filter #work");
+    assert_eq!(app.tag_filter(), Some("work"));
+    assert_eq!(app.visible_indices(), [0, 2]);
+    // Selection snaps to the next listed task, and navigation skips hidden ones
+    assert_eq!(app.selected_index(), 2);
+    app.handle_key(KeyCode::Up);
+    assert_eq!(app.selected_index(), 0);
+    app.handle_key(KeyCode::Up);
+    assert_eq!(app.selected_index(), 0);
+
+    run_command(&mut app, "filter off");
+    assert_eq!(app.visible_indices().len(), 4);
+
+    let groups = tags::group_by_tag(&app.day.tasks);
+    let summary: Vec<(Option<&str>, usize, u32)> =
+        groups.iter().map(|g| (g.tag.as_deref(), g.tasks.len(), g.estimate_min)).collect();
+    assert_eq!(summary, [(Some("home"), 1, 10), (Some("work"), 2, 20), (None, 1, 10)]);
+}
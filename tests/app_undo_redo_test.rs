@@ -0,0 +1,142 @@
+use chute_kun::{
+    app::{App, View},
+    task::{Task, TaskState},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn press(app: &mut App, code: KeyCode, mods: KeyModifiers) {
+    app.handle_key_event(KeyEvent::new(code, mods));
+}
+
+#[test]
+fn undo_and_redo_delete_postpone_and_reorder() {
+    let mut app = App::new();
+    app.add_task("A", 10);
+    app.add_task("B", 20);
+    app.add_task("C", 30);
+
+    // Delete A (with confirmation), then undo
+    press(&mut app, KeyCode::Char('x'), KeyModifiers::NONE);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.day.tasks.len(), 2);
+    press(&mut app, KeyCode::Char('u'), KeyModifiers::NONE);
+    let titles: Vec<_> = app.day.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, vec!["A", "B", "C"]);
+
+    // Redo re-deletes; undo again restores
+    press(&mut app, KeyCode::Char('r'), KeyModifiers::CONTROL);
+    assert_eq!(app.day.tasks.len(), 2);
+    assert!(app.undo());
+    assert_eq!(app.day.tasks.len(), 3);
+
+    // Postpone then reorder; undo both in reverse order
+    press(&mut app, KeyCode::Char('p'), KeyModifiers::NONE);
+    assert_eq!(app.tomorrow_tasks().len(), 1);
+    press(&mut app, KeyCode::Char(']'), KeyModifiers::NONE);
+    let titles: Vec<_> = app.day.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, vec!["C", "B"]);
+    press(&mut app, KeyCode::Char('z'), KeyModifiers::CONTROL);
+    let titles: Vec<_> = app.day.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, vec!["B", "C"]);
+    assert!(app.undo());
+    assert!(app.tomorrow_tasks().is_empty());
+    assert_eq!(app.day.tasks.len(), 3);
+
+    // A new change drops the redo branch
+    assert!(app.can_redo());
+    press(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+    press(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert!(!app.can_redo());
+    assert!(!app.redo());
+}
+
+#[test]
+fn undo_restores_sessions_and_active_pointer_exactly() {
+    let mut app = App::new();
+    app.add_task("A", 10);
+    app.add_task("B", 20);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE); // start A
+    let started = app.day.clone();
+    assert_eq!(started.active_index(), Some(0));
+
+    press(&mut app, KeyCode::Char('j'), KeyModifiers::NONE);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE); // pause A
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE); // start B
+    assert_eq!(app.day.active_index(), Some(1));
+    assert_eq!(app.day.tasks[0].state, TaskState::Paused);
+
+    press(&mut app, KeyCode::Char('f'), KeyModifiers::NONE); // finish B
+    assert_eq!(app.day.tasks[1].state, TaskState::Done);
+    assert_eq!(app.day.active_index(), None);
+
+    assert!(app.undo());
+    assert_eq!(app.day.active_index(), Some(1));
+    assert_eq!(app.day.tasks[1].state, TaskState::Active);
+    assert_eq!(app.day.tasks[1].finished_at_min, None);
+    assert!(app.undo());
+    assert_eq!(app.day.active_index(), None);
+    assert_eq!(app.day.tasks[1].state, TaskState::Planned);
+    assert!(app.undo());
+    assert_eq!(app.day, started);
+    assert_eq!(app.selected_index(), 1, "selection as it was before the undone step");
+    assert!(app.undo());
+    assert!(app.day.tasks.iter().all(|t| t.sessions.is_empty()));
+    assert!(!app.undo(), "add_task is not an input action and is not recorded");
+}
+
+#[test]
+fn undoing_a_rename_keeps_the_time_tracked_since() {
+    let mut app = App::new();
+    app.add_task("Write", 30);
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE); // start
+    app.tick(120);
+    press(&mut app, KeyCode::Char(':'), KeyModifiers::NONE);
+    for c in "rename Write report".chars() {
+        press(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.day.tasks[0].title, "Write report");
+    app.tick(300);
+    let tracked = app.day.tasks[0].actual_min;
+    assert!(tracked >= 7, "{tracked}");
+
+    assert!(app.undo());
+    assert_eq!(app.day.tasks[0].title, "Write");
+    assert_eq!(app.day.tasks[0].state, TaskState::Active);
+    assert_eq!(app.day.tasks[0].actual_min, tracked);
+    assert_eq!(app.day.tasks[0].sessions.len(), 1);
+}
+
+#[test]
+fn past_edits_are_undone_and_redone_by_value() {
+    let mut app = App::new();
+    let mut old = Task::new("Old", 20);
+    old.state = TaskState::Done;
+    old.done_ymd = Some(20250829);
+    let mut older = Task::new("Older", 10);
+    older.state = TaskState::Done;
+    older.done_ymd = Some(20250828);
+    app.apply_snapshot(vec![], vec![], vec![older, old]);
+    press(&mut app, KeyCode::BackTab, KeyModifiers::NONE);
+    assert_eq!(app.view(), View::Past);
+    app.select_index(1);
+    press(&mut app, KeyCode::Char(':'), KeyModifiers::NONE);
+    for c in "rename Old report".chars() {
+        press(&mut app, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    let titles =
+        |app: &App| app.history_tasks().iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+    assert_eq!(titles(&app), ["Older", "Old report"]);
+
+    assert!(app.undo());
+    assert_eq!(titles(&app), ["Older", "Old"]);
+    assert!(app.redo());
+    assert_eq!(titles(&app), ["Older", "Old report"]);
+    // Moving around Past records nothing
+    press(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert!(app.undo());
+    assert_eq!(titles(&app), ["Older", "Old"]);
+    assert!(!app.undo());
+}
@@ -0,0 +1,24 @@
+use chute_kun::storage::Autosave;
+use std::time::{Duration, Instant};
+
+#[test]
+fn autosave_waits_for_quiet_but_not_forever() {
+    let mut a = Autosave::new(Duration::from_secs(2), Duration::from_secs(10));
+    let t0 = Instant::now();
+    assert!(!a.is_due(t0));
+
+    a.touch(t0);
+    assert!(!a.is_due(t0 + Duration::from_secs(1)));
+    assert!(a.is_due(t0 + Duration::from_secs(2)));
+
+    // Continuous changes (e.g., a running task) still save after max_delay
+    for s in 1..=10 {
+        a.touch(t0 + Duration::from_secs(s));
+    }
+    assert!(!a.is_due(t0 + Duration::from_millis(9_500)));
+    assert!(a.is_due(t0 + Duration::from_secs(10)));
+
+    a.saved();
+    assert!(!a.is_pending());
+    assert!(!a.is_due(t0 + Duration::from_secs(60)));
+}
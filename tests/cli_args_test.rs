@@ -0,0 +1,61 @@
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn help_and_version_are_generated_and_unknown_flags_fail() {
+    Command::cargo_bin("chute")
+        .unwrap()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--state <PATH>"))
+        .stdout(predicate::str::contains("--log-level <LEVEL>"));
+    Command::cargo_bin("chute")
+        .unwrap()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
+
+    Command::cargo_bin("chute")
+        .unwrap()
+        .arg("--bogus")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--bogus"));
+    Command::cargo_bin("chute").unwrap().args(["--today", "2025-13-40"]).assert().code(2);
+}
+
+#[test]
+fn config_and_today_options_replace_env_vars() {
+    let dir = tempdir().unwrap();
+    let cfg = dir.path().join("custom.toml");
+    let state = dir.path().join("snapshot.toml");
+
+    Command::cargo_bin("chute")
+        .unwrap()
+        .env_remove("CHUTE_KUN_CONFIG")
+        .arg("--config")
+        .arg(&cfg)
+        .args(["--set-day-start", "0800"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&cfg).unwrap().contains("day_start = \"08:00\""));
+
+    let out = Command::cargo_bin("chute")
+        .unwrap()
+        .env_remove("CHUTE_KUN_TODAY")
+        .args(["add", "Plan trip", "--date", "tomorrow", "--json", "--today", "2025-09-01"])
+        .arg("--config")
+        .arg(&cfg)
+        .arg("--state")
+        .arg(&state)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["task"]["planned_ymd"], 20250902);
+}
@@ -0,0 +1,84 @@
+use assert_cmd::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn chute(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("chute").unwrap();
+    cmd.env("CHUTE_KUN_CONFIG", dir.join("config.toml"));
+    cmd.env("CHUTE_KUN_TODAY", "2025-09-01");
+    cmd.arg("--state").arg(dir.join("snapshot.toml"));
+    cmd.args(args);
+    cmd
+}
+
+fn json_of(cmd: &mut Command) -> serde_json::Value {
+    let out = cmd.output().unwrap();
+    assert!(out.status.success(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+    serde_json::from_slice(&out.stdout).expect("json stdout")
+}
+
+#[test]
+fn add_start_finish_move_and_list_round_trip_through_the_snapshot() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    chute(d, &["add", "Write", "report", "-e", "30"]).assert().success();
+    chute(d, &["add", "Inbox zero"]).assert().success();
+    chute(d, &["add", "Plan trip", "--date", "tomorrow"]).assert().success();
+
+    chute(d, &["start", "report"]).assert().success();
+    chute(d, &["move", "inbox", "1"]).assert().success();
+    chute(d, &["estimate", "2", "+15m"]).assert().success();
+    chute(d, &["at", "1", "10:30"]).assert().success();
+    chute(d, &["finish"]).assert().success();
+
+    let today = json_of(chute(d, &["--json", "list"]).arg("today"));
+    let tasks = today["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["title"], "Inbox zero");
+    assert_eq!(tasks[0]["fixed_start_min"], 630);
+    assert_eq!(tasks[1]["title"], "Write report");
+    assert_eq!(tasks[1]["state"], "Done");
+    assert_eq!(tasks[1]["estimate_min"], 45);
+    assert_eq!(tasks[1]["index"], 2);
+
+    let future = json_of(&mut chute(d, &["--json", "list", "future"]));
+    assert_eq!(future["tasks"][0]["title"], "Plan trip");
+    assert_eq!(future["tasks"][0]["planned_ymd"], 20250902);
+
+    chute(d, &["postpone", "Inbox zero"]).assert().success();
+    chute(d, &["delete", "1"]).assert().success();
+    let today = json_of(&mut chute(d, &["--json", "list"]));
+    assert!(today["tasks"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn failures_use_stable_exit_codes_and_json_errors() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    chute(d, &["add", "Review A"]).assert().success();
+    chute(d, &["add", "Review B"]).assert().success();
+
+    chute(d, &["frobnicate"]).assert().code(2);
+    chute(d, &["start"]).assert().code(2);
+    chute(d, &["start", "9"]).assert().code(3);
+    chute(d, &["start", "review"]).assert().code(4);
+    chute(d, &["pause"]).assert().code(5);
+
+    let out = chute(d, &["--json", "start", "nothing"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(3));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["ok"], false);
+    assert_eq!(v["error"]["kind"], "not_found");
+
+    // Postponing a finished task would drop its completion
+    chute(d, &["finish", "1"]).assert().success();
+    chute(d, &["postpone", "1"]).assert().code(5);
+    // ...but its estimate can still be corrected
+    chute(d, &["estimate", "1", "40"]).assert().success();
+    let v = json_of(chute(d, &["--json", "list"]).arg("today"));
+    assert_eq!(v["tasks"][0]["state"], "Done");
+    assert_eq!(v["tasks"][0]["estimate_min"], 40);
+    let v = json_of(&mut chute(d, &["--json", "postpone", "2"]));
+    assert_eq!((&v["task"]["title"], &v["task"]["index"]), (&"Review B".into(), &1.into()));
+}
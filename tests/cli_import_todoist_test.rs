@@ -0,0 +1,120 @@
+use assert_cmd::prelude::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::sync::mpsc;
+use tempfile::tempdir;
+
+const TASKS: &str = r#"[
+  {"id": "101", "content": "Write report", "labels": ["work", "client-a"],
+   "due": {"date": "2025-09-01", "datetime": "2025-09-01T14:00:00"},
+   "duration": {"amount": 45, "unit": "minute"}},
+  {"id": "102", "content": "Pay rent", "labels": ["est_10m"], "due": {"date": "2025-08-29"}},
+  {"id": "103", "content": "Plan trip", "labels": [], "due": {"date": "2025-09-03"},
+   "duration": {"amount": 1, "unit": "day"}}
+]"#;
+
+/// Serve `TASKS` to `requests` connections, reporting each request line and auth header.
+fn mock_todoist(requests: usize) -> (String, mpsc::Receiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/rest/v2/", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut auth = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(v) = line.strip_prefix("Authorization: ") {
+                    auth = v.trim().to_string();
+                }
+            }
+            tx.send((request.trim().to_string(), auth)).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                TASKS.len(),
+                TASKS
+            )
+            .unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn chute(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::cargo_bin("chute").unwrap();
+    cmd.env("CHUTE_KUN_CONFIG", dir.join("config.toml"));
+    cmd.env("CHUTE_KUN_TODAY", "2025-09-01");
+    cmd.env("TODOIST_API_TOKEN", "secret-token");
+    cmd.arg("--state").arg(dir.join("snapshot.toml"));
+    cmd.args(args);
+    cmd
+}
+
+fn json_of(cmd: &mut Command) -> serde_json::Value {
+    let out = cmd.output().unwrap();
+    assert!(out.status.success(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+    serde_json::from_slice(&out.stdout).expect("json stdout")
+}
+
+#[test]
+fn imports_today_overdue_and_future_tasks_once() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    let (url, requests) = mock_todoist(2);
+    std::fs::write(
+        d.join("config.toml"),
+        format!("[todoist]\nbase_url = \"{url}\"\nfilter = \"today | overdue | 3 days\"\n"),
+    )
+    .unwrap();
+
+    let out = json_of(&mut chute(d, &["--json", "import", "todoist"]));
+    assert_eq!(out["skipped"], 0);
+    assert_eq!(out["added"].as_array().unwrap().len(), 3);
+    let (request, auth) = requests.recv().unwrap();
+    assert_eq!(request, "GET /rest/v2/tasks?filter=today+%7C+overdue+%7C+3+days HTTP/1.1");
+    assert_eq!(auth, "Bearer secret-token");
+
+    let today = json_of(&mut chute(d, &["--json", "list"]));
+    let tasks = today["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["title"], "Write report");
+    assert_eq!(tasks[0]["estimate_min"], 45);
+    assert_eq!(tasks[0]["fixed_start_min"], 14 * 60);
+    assert_eq!(tasks[0]["category"], "Work");
+    assert_eq!(tasks[0]["tags"], serde_json::json!(["client-a"]));
+    assert_eq!(tasks[0]["remote_id"], "todoist:101");
+    // Overdue tasks land in Today; `est_` labels set the estimate
+    assert_eq!(tasks[1]["title"], "Pay rent");
+    assert_eq!(tasks[1]["planned_ymd"], 20250901);
+    assert_eq!(tasks[1]["estimate_min"], 10);
+
+    let future = json_of(&mut chute(d, &["--json", "list", "future"]));
+    assert_eq!(future["tasks"][0]["title"], "Plan trip");
+    assert_eq!(future["tasks"][0]["planned_ymd"], 20250903);
+    assert_eq!(future["tasks"][0]["estimate_min"], 25);
+
+    // Re-importing skips the tasks already present
+    let again = json_of(&mut chute(d, &["--json", "import", "todoist"]));
+    assert_eq!(again["added"].as_array().unwrap().len(), 0);
+    assert_eq!(again["skipped"], 3);
+    assert_eq!(json_of(&mut chute(d, &["--json", "list"]))["tasks"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn missing_token_is_a_usage_error() {
+    let dir = tempdir().unwrap();
+    let out = chute(dir.path(), &["import", "todoist"]).env_remove("TODOIST_API_TOKEN").output();
+    let out = out.unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("TODOIST_API_TOKEN"));
+}
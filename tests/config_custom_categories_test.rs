@@ -0,0 +1,68 @@
+use chute_kun::{app::App, config::Config, storage, task::Category, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use tempfile::tempdir;
+
+const TEAM: &str = r##"
+[categories.general]
+hidden = true
+
+[categories.meetings]
+name = "Meetings"
+color = "cyan"
+
+[categories.deep_work]
+name = "Deep work"
+color = "#224466"
+"##;
+
+#[test]
+fn custom_categories_drive_picker_and_cycle_in_config_order() {
+    let cfg = Config::from_toml_str(TEAM).unwrap();
+    let mut app = App::with_config(cfg);
+    app.add_task("Standup", 15);
+
+    let names: Vec<String> = ui::category_options(&app).into_iter().map(|(n, _, _)| n).collect();
+    assert_eq!(names, ["Work", "Home", "Hobby", "Meetings", "Deep work"]);
+
+    // General is hidden, so cycling starts at the first visible category
+    app.handle_key(KeyCode::Char('c'));
+    assert_eq!(app.day.tasks[0].category, Category::WORK);
+    for _ in 0..3 {
+        app.handle_key(KeyCode::Char('c'));
+    }
+    assert_eq!(app.day.tasks[0].category, Category::new("meetings"));
+    assert_eq!(app.config.category_color(&app.day.tasks[0].category), Color::Cyan);
+
+    // The picker opens on the current category and applies the one below it
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('C'), KeyModifiers::SHIFT));
+    assert_eq!(app.category_pick_index(), 3);
+    app.handle_key(KeyCode::Down);
+    app.handle_key(KeyCode::Enter);
+    assert_eq!(app.day.tasks[0].category.id(), "deep_work");
+    assert_eq!(app.config.category_color(&app.day.tasks[0].category), Color::Rgb(0x22, 0x44, 0x66));
+}
+
+#[test]
+fn legacy_and_unknown_categories_survive_a_round_trip() {
+    let mut app = App::new();
+    app.add_task("Old", 10);
+    app.add_task("Orphan", 10);
+    app.day.tasks[0].category = Category::WORK;
+    app.day.tasks[1].category = Category::new("Gardening");
+
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("snapshot.toml");
+    storage::save_to_path(&app, &p).unwrap();
+    let text = std::fs::read_to_string(&p).unwrap();
+    assert!(text.contains("category = \"Work\""), "{}", text);
+
+    let loaded = storage::load_from_path(&p, Config::default()).unwrap().unwrap();
+    assert_eq!(loaded.day.tasks[0].category, Category::new("work"));
+    assert_eq!(loaded.day.tasks[1].category.id(), "Gardening");
+    assert_eq!(loaded.config.category_name(&loaded.day.tasks[1].category), "Gardening");
+    assert_eq!(loaded.config.category_color(&loaded.day.tasks[1].category), Color::Gray);
+
+    storage::save_to_path(&loaded, &p).unwrap();
+    assert!(std::fs::read_to_string(&p).unwrap().contains("category = \"Gardening\""));
+}
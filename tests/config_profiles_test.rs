@@ -0,0 +1,78 @@
+use assert_cmd::prelude::*;
+use chute_kun::config::{join_key_labels, Config};
+use chute_kun::task::Category;
+use ratatui::style::Color;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn profile_overrides_on_top_of_base_and_never_inherits_state_path() {
+    let base = r#"
+day_start = "08:00"
+state_path = "/tmp/chute-base/snapshot.toml"
+[keys]
+quit = "Q"
+delete = "Shift+x"
+[categories.work]
+name = "Job"
+color = "blue"
+"#;
+    let profile = r#"
+day_start = "10:00"
+[keys]
+quit = "Ctrl+q"
+[categories.work]
+color = "red"
+"#;
+    let cfg = Config::from_toml_layers(base, profile).unwrap();
+    assert_eq!(cfg.day_start_minutes, 10 * 60);
+    assert_eq!(join_key_labels(&cfg.keys.quit), "Ctrl+q");
+    // Unspecified values come from the base
+    assert_eq!(join_key_labels(&cfg.keys.delete), "Shift+x");
+    assert_eq!(cfg.category_name(&Category::WORK), "Job");
+    assert_eq!(cfg.category_color(&Category::WORK), Color::Red);
+    assert_eq!(cfg.state_path, None);
+
+    let own = Config::from_toml_layers(base, "state_path = \"/tmp/chute-work/s.toml\"").unwrap();
+    assert_eq!(own.state_path.unwrap().to_str(), Some("/tmp/chute-work/s.toml"));
+
+    // A broken profile file only drops the overlay; the base settings still apply
+    let (kept, err) = Config::from_toml_layers_or_base(base, "day_start = \"noon\"").unwrap();
+    assert!(err.is_some());
+    assert_eq!(kept.day_start_minutes, 8 * 60);
+    assert_eq!(join_key_labels(&kept.keys.delete), "Shift+x");
+    assert_eq!(kept.state_path, None);
+    let (_, err) = Config::from_toml_layers_or_base(base, "[keys\n").unwrap();
+    assert!(err.is_some());
+}
+
+#[test]
+fn profile_flag_selects_profile_file_and_separate_snapshot() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    let chute = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("chute").unwrap();
+        cmd.env("XDG_DATA_HOME", d.join("data"))
+            .env_remove("CHUTE_KUN_STATE")
+            .env_remove("CHUTE_KUN_PROFILE")
+            .arg("--config")
+            .arg(d.join("config.toml"))
+            .args(args);
+        cmd
+    };
+
+    chute(&["--profile", "work", "--set-day-start", "07:30"]).assert().success();
+    let profile_toml = fs::read_to_string(d.join("profiles/work.toml")).unwrap();
+    assert!(profile_toml.contains("day_start = \"07:30\""), "{}", profile_toml);
+    assert!(!d.join("config.toml").exists());
+
+    chute(&["--profile", "work", "add", "Deploy"]).assert().success();
+    chute(&["add", "Groceries"]).assert().success();
+    let work = fs::read_to_string(d.join("data/chute_kun/profiles/work/snapshot.toml")).unwrap();
+    let base = fs::read_to_string(d.join("data/chute_kun/snapshot.toml")).unwrap();
+    assert!(work.contains("Deploy") && !work.contains("Groceries"));
+    assert!(base.contains("Groceries") && !base.contains("Deploy"));
+
+    chute(&["--profile", "../x", "list"]).assert().code(2);
+}
@@ -0,0 +1,131 @@
+use assert_cmd::prelude::*;
+use chute_kun::{
+    app::App,
+    history::HistoryStore,
+    report,
+    task::{Category, Session, Task, TaskState},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::process::Command;
+use tempfile::tempdir;
+
+fn worked(title: &str, est: u16, sessions: &[(u16, u16)]) -> Task {
+    let mut t = Task::new(title, est);
+    t.state = TaskState::Done;
+    t.sessions =
+        sessions.iter().map(|&(s, e)| Session { start_min: s, end_min: Some(e) }).collect();
+    t.actual_min = t.sessions.iter().map(|s| s.end_min.unwrap() - s.start_min).sum();
+    t
+}
+
+#[test]
+fn report_compares_plan_and_actual() {
+    let mut app = App::new();
+    app.config.day_start_minutes = 9 * 60;
+    let mut write = worked("Write | edit", 30, &[(540, 585)]);
+    write.category = Category::WORK;
+    write.tags = vec!["acme".into()];
+    let mut mail = worked("Mail", 15, &[(585, 595)]);
+    mail.tags = vec!["admin".into(), "acme".into()];
+    let mut call = worked("Call", 15, &[(600, 620)]);
+    call.interrupt = true;
+    let tasks = vec![write, mail, call, Task::new("Review", 20)];
+
+    let text = report::render(&app, 20250903, &tasks, report::DEFAULT_TEMPLATE);
+    let expected = "\
+# Daily report 2025-09-03 (Wed)
+
+- Work: 09:00 - 10:20
+- ESD at day start: 10:05, actual end: 10:20 (+15m)
+- Total: estimate 80m, actual 75m (-5m)
+
+## Plan vs actual
+
+| # | Task | Category | Est | Act | Delta | State |
+|---:|---|---|---:|---:|---:|---|
+| 1 | Write \\| edit | Work | 30m | 45m | +15m | done |
+| 2 | Mail | General | 15m | 10m | -5m | done |
+| 3 | Call (interrupt) | General | 15m | 20m | +5m | done |
+| 4 | Review | General | 20m | 0m | - | planned |
+
+## By category
+
+| Category | Est | Act | Delta |
+|---|---:|---:|---:|
+| General | 50m | 30m | -20m |
+| Work | 30m | 45m | +15m |
+
+## By tag
+
+| Tag | Tasks | Est | Act | Delta |
+|---|---:|---:|---:|---:|
+| #acme | 2 | 45m | 55m | +10m |
+| #admin | 1 | 15m | 10m | -5m |
+| (untagged) | 2 | 35m | 20m | -15m |
+
+## Interruptions
+
+- 10:00 Call (20m)
+
+Total: 1 (20m)
+";
+    assert_eq!(text, expected);
+
+    // Custom templates: placeholders may be padded; unknown ones are left alone
+    let custom = report::render(&app, 20250903, &tasks, "{{ date }}: {{actual_total}} {{nope}}");
+    assert_eq!(custom, "2025-09-03: 75m {{nope}}");
+}
+
+#[test]
+fn interrupts_are_marked_and_reported_from_the_palette_and_cli() {
+    let dir = tempdir().unwrap();
+    let state = dir.path().join("snapshot.toml");
+    let mut app = chute_kun::storage::new_app_at_path(&state, Default::default());
+    let key = |app: &mut App, code| app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    let type_line = |app: &mut App, line: &str| {
+        key(app, KeyCode::Char(':'));
+        for c in line.chars() {
+            key(app, KeyCode::Char(c));
+        }
+        key(app, KeyCode::Enter);
+    };
+    type_line(&mut app, "add Plan 30m");
+    type_line(&mut app, "interrupt Phone 10m");
+    assert_eq!(app.day.tasks.iter().map(|t| t.interrupt).collect::<Vec<_>>(), [false, true]);
+
+    type_line(&mut app, "report");
+    let date = chute_kun::date::format_ymd(chute_kun::date::today_ymd());
+    let written =
+        std::fs::read_to_string(dir.path().join("reports").join(format!("{date}.md"))).unwrap();
+    assert!(written.contains("| 2 | Phone (interrupt) | General | 10m | 0m | - | planned |"));
+
+    // A past day from the CLI, with a template file
+    let d = dir.path();
+    HistoryStore::for_state_path(&state)
+        .save_day(
+            20250901,
+            &[{
+                let mut t = worked("Review", 20, &[(540, 565)]);
+                t.done_ymd = Some(20250901);
+                t
+            }],
+        )
+        .unwrap();
+    std::fs::write(d.join("tpl.md"), "{{date}} {{first_start}}-{{last_finish}}\n{{categories}}\n")
+        .unwrap();
+    let out = Command::cargo_bin("chute")
+        .unwrap()
+        .env("CHUTE_KUN_CONFIG", d.join("config.toml"))
+        .env("CHUTE_KUN_TODAY", "2025-09-03")
+        .arg("--state")
+        .arg(&state)
+        .args(["report", "day", "2025-09-01", "--template"])
+        .arg(d.join("tpl.md"))
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "2025-09-01 09:00-09:25\n| Category | Est | Act | Delta |\n|---|---:|---:|---:|\n| General | 20m | 25m | +5m |\n"
+    );
+}
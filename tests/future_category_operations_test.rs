@@ -14,18 +14,18 @@ fn future_cycle_and_picker_work() {
                                         // Future ビューへ
     app.handle_key(KeyCode::Tab);
     assert_eq!(app.tomorrow_tasks().len(), 1);
-    assert!(matches!(app.tomorrow_tasks()[0].category, Category::General));
+    assert_eq!(app.tomorrow_tasks()[0].category, Category::GENERAL);
 
     // 1) 'c' でカテゴリが循環（General -> Work）
     app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
-    assert!(matches!(app.tomorrow_tasks()[0].category, Category::Work));
+    assert_eq!(app.tomorrow_tasks()[0].category, Category::WORK);
 
     // 2) Shift+'c' でピッカーを開き、Down -> Enter で Home に設定
     app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::SHIFT));
     assert!(app.is_category_picker(), "picker should open on Shift+c in Future");
     app.handle_key(KeyCode::Down); // select Home (index 2) from Work's index(1)
     app.handle_key(KeyCode::Enter);
-    assert!(matches!(app.tomorrow_tasks()[0].category, Category::Home));
+    assert_eq!(app.tomorrow_tasks()[0].category, Category::HOME);
 
     // 描画パスも問題ないことを確認
     terminal.draw(|f| ui::draw(f, &app)).unwrap();
@@ -0,0 +1,43 @@
+use chute_kun::{
+    headless::{self, Command, ErrorKind, ListWhich},
+    journal::{self, Journal},
+    storage::{self, Autosave, StateLock},
+};
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+fn add(title: &str) -> Command {
+    Command::Add { title: title.into(), estimate_min: Some(10), ymd: None }
+}
+
+#[test]
+fn headless_changes_wait_for_the_tui_to_close() {
+    let dir = tempdir().unwrap();
+    let state = dir.path().join("snapshot.toml");
+    let journal_path = journal::journal_path_for(&state);
+
+    // TUI side: lock, journal and a pending autosave
+    let lock = StateLock::try_acquire(&state).unwrap().expect("first lock");
+    let mut app = storage::new_app_at_path(&state, Default::default());
+    let mut journal = Journal::open(journal_path.clone(), &app);
+    let mut autosave = Autosave::new(Duration::ZERO, Duration::ZERO);
+    app.add_task("From TUI", 25);
+    assert!(journal.record(&mut app).unwrap());
+    autosave.touch(Instant::now());
+
+    // A changing command is refused and leaves the journal alone; reading still works
+    let err = headless::run_at_path(&state, Default::default(), &add("From script")).unwrap_err();
+    assert_eq!((err.kind, err.kind.exit_code()), (ErrorKind::Locked, 6));
+    assert!(journal_path.exists());
+    headless::run_at_path(&state, Default::default(), &Command::List(ListWhich::Today)).unwrap();
+
+    assert!(autosave.is_due(Instant::now()));
+    journal.checkpoint(&mut app, &state).unwrap();
+    drop(lock);
+
+    // Once the TUI is gone the command applies on top of what it saved
+    headless::run_at_path(&state, Default::default(), &add("From script")).unwrap();
+    let saved = storage::load_from_path(&state, Default::default()).unwrap().unwrap();
+    let titles: Vec<&str> = saved.day.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["From TUI", "From script"]);
+}
@@ -0,0 +1,100 @@
+use chute_kun::{config::Config, history::HistoryStore, storage, task::Task};
+use crossterm::event::KeyCode;
+use tempfile::tempdir;
+
+const LEGACY: &str = r#"
+version = 1
+
+[[today]]
+title = "Today"
+estimate_min = 10
+actual_min = 0
+state = "Planned"
+planned_ymd = 20250831
+
+[[past]]
+title = "Old A"
+estimate_min = 20
+actual_min = 25
+state = "Done"
+planned_ymd = 20250829
+done_ymd = 20250829
+
+[[past]]
+title = "Old B"
+estimate_min = 30
+actual_min = 30
+state = "Done"
+planned_ymd = 20250830
+done_ymd = 20250830
+"#;
+
+#[test]
+fn legacy_past_is_migrated_to_day_files_and_loaded_lazily() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-08-31");
+    let dir = tempdir().expect("tempdir");
+    let snap = dir.path().join("snapshot.toml");
+    std::fs::write(&snap, LEGACY).unwrap();
+
+    let mut app = storage::load_from_path(&snap, Config::default()).unwrap().unwrap();
+    let store = HistoryStore::for_state_path(&snap);
+    assert_eq!(store.list_days().unwrap(), vec![20250829, 20250830]);
+    assert!(dir.path().join("history/2025/2025-08-29.toml").exists());
+    // Nothing read until the Past view is opened
+    assert!(app.history_tasks().is_empty());
+    app.handle_key(KeyCode::BackTab);
+    let titles: Vec<_> = app.history_tasks().iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, vec!["Old A", "Old B"]);
+
+    storage::save_to_path(&app, &snap).unwrap();
+    let saved = std::fs::read_to_string(&snap).unwrap();
+    assert!(!saved.contains("[[past]]"), "past moved out of the snapshot: {saved}");
+    // Migrating the same legacy list again (e.g. after a crash before save) adds nothing
+    let legacy: chute_kun::storage::SnapshotV1 = toml::from_str(LEGACY).unwrap();
+    assert_eq!(store.migrate(legacy.past).unwrap(), 0);
+    assert_eq!(store.load_day(20250829).unwrap().len(), 1);
+}
+
+#[test]
+fn duplicate_legacy_entries_are_all_migrated_once() {
+    let dir = tempdir().expect("tempdir");
+    let store = HistoryStore::for_state_path(&dir.path().join("snapshot.toml"));
+    let mut standup = Task::new("Standup", 15);
+    standup.done_ymd = Some(20250829);
+    let legacy = vec![standup.clone(), standup.clone(), standup];
+
+    assert_eq!(store.migrate(legacy[..2].to_vec()).unwrap(), 2, "identical tasks both kept");
+    // A re-run only adds the copies the day file is still missing
+    assert_eq!(store.migrate(legacy.clone()).unwrap(), 1);
+    assert_eq!(store.migrate(legacy).unwrap(), 0);
+    assert_eq!(store.load_day(20250829).unwrap().len(), 3);
+}
+
+#[test]
+fn sweep_appends_to_its_day_file_only() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-08-31");
+    let dir = tempdir().expect("tempdir");
+    let snap = dir.path().join("snapshot.toml");
+    let store = HistoryStore::for_state_path(&snap);
+    let mut earlier = Task::new("Earlier", 15);
+    earlier.done_ymd = Some(20250830);
+    store.save_day(20250830, &[earlier]).unwrap();
+    let mut other = Task::new("Other day", 5);
+    other.done_ymd = Some(20250801);
+    store.save_day(20250801, &[other.clone()]).unwrap();
+
+    let mut app = storage::new_app_at_path(&snap, Config::default());
+    let mut done = Task::new("Yesterday", 10);
+    done.state = chute_kun::task::TaskState::Done;
+    done.done_ymd = Some(20250830);
+    app.apply_snapshot(vec![done], vec![], vec![]);
+    assert_eq!(app.history_dirty_days().iter().copied().collect::<Vec<_>>(), vec![20250830]);
+
+    storage::save_to_path(&app, &snap).unwrap();
+    let day: Vec<_> = store.load_day(20250830).unwrap().into_iter().map(|t| t.title).collect();
+    assert_eq!(day, vec!["Earlier", "Yesterday"]);
+    assert_eq!(store.load_day(20250801).unwrap(), vec![other]);
+    let range = store.load_range(20250815, 20250831).unwrap();
+    assert_eq!(range.len(), 1);
+    assert_eq!(range[0].0, 20250830);
+}
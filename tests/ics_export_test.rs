@@ -0,0 +1,123 @@
+use chute_kun::{
+    app::App,
+    ics::{self, Part},
+    task::{Session, Task},
+};
+
+/// Unfolded VEVENTs as `PROP:value` lines, without the volatile DTSTAMP.
+fn events(text: &str) -> Vec<Vec<String>> {
+    let unfolded = text.replace("\r\n ", "");
+    let mut out = Vec::new();
+    let mut cur: Option<Vec<String>> = None;
+    for line in unfolded.split("\r\n") {
+        match line {
+            "BEGIN:VEVENT" => cur = Some(Vec::new()),
+            "END:VEVENT" => out.extend(cur.take()),
+            l if l.starts_with("DTSTAMP:") => {}
+            l => {
+                if let Some(ev) = cur.as_mut() {
+                    ev.push(l.to_string());
+                }
+            }
+        }
+    }
+    out
+}
+
+fn prop<'a>(ev: &'a [String], name: &str) -> &'a str {
+    let prefix = format!("{name}:");
+    ev.iter().find_map(|l| l.strip_prefix(&prefix)).unwrap_or_else(|| panic!("no {name}: {ev:?}"))
+}
+
+#[test]
+fn plan_blocks_and_sessions_become_events_with_stable_uids() {
+    let mut app = App::new();
+    app.config.day_start_minutes = 9 * 60;
+    app.add_task("Write report, v2", 30);
+    app.add_task("Lunch", 60);
+    app.day.tasks[1].fixed_start_min = Some(12 * 60);
+    app.day.tasks[1].category = chute_kun::task::Category::HOME;
+    app.add_task("Review", 20);
+    app.day.tasks[0].sessions.push(Session { start_min: 545, end_min: Some(570) });
+    app.day.tasks[0].sessions.push(Session { start_min: 600, end_min: None });
+    let today = chute_kun::date::today_ymd();
+    let day = |offset: i32| chute_kun::date::add_days_to_ymd(today, offset);
+    let mut future = Task::new("Plan trip", 45);
+    future.planned_ymd = day(2);
+    app.add_planned_task(future);
+    let mut past = Task::new("Old work", 10);
+    past.sessions.push(Session { start_min: 23 * 60 + 50, end_min: Some(10) });
+    let past = vec![(day(-1), vec![past])];
+
+    let out = ics::export(&app, &past, today, 615, Part::All);
+    assert!(out.text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(out.text.ends_with("END:VCALENDAR\r\n"));
+    assert!(out.text.lines().all(|l| l.trim_end_matches('\r').len() <= 75));
+    let evs = events(&out.text);
+    assert_eq!(out.events, evs.len());
+    assert_eq!(evs.len(), 4 + 3);
+
+    // Plan: same schedule as the list (09:00, fixed 12:00, then 13:00), then Future
+    let plan: Vec<(&str, &str, &str)> = evs[..4]
+        .iter()
+        .map(|e| (prop(e, "SUMMARY"), prop(e, "DTSTART"), prop(e, "DTEND")))
+        .collect();
+    assert_eq!(
+        plan,
+        [
+            ("Write report\\, v2", &*format!("{today}T090000"), &*format!("{today}T093000")),
+            ("Lunch", &format!("{today}T120000"), &format!("{today}T130000")),
+            ("Review", &format!("{today}T130000"), &format!("{today}T132000")),
+            ("Plan trip", &format!("{}T090000", day(2)), &format!("{}T094500", day(2))),
+        ]
+    );
+    assert_eq!(prop(&evs[0], "CATEGORIES"), "General,Plan");
+    assert_eq!(prop(&evs[1], "CATEGORIES"), "Home,Plan");
+
+    // Actual: the past session crosses midnight; the running one ends now
+    assert_eq!(prop(&evs[4], "SUMMARY"), "Old work");
+    assert_eq!(prop(&evs[4], "DTEND"), format!("{today}T001000"));
+    assert_eq!(prop(&evs[5], "DTSTART"), format!("{today}T090500"));
+    assert_eq!(prop(&evs[6], "DTEND"), format!("{today}T101500"));
+    assert_eq!(prop(&evs[6], "CATEGORIES"), "General,Actual");
+
+    // Re-exporting after a reorder keeps every UID
+    let uids = |text: &str| {
+        let mut v: Vec<String> = events(text).iter().map(|e| prop(e, "UID").to_string()).collect();
+        v.sort();
+        v
+    };
+    let before = uids(&out.text);
+    assert_eq!(before.iter().collect::<std::collections::HashSet<_>>().len(), 7);
+    app.day.tasks.swap(0, 2);
+    assert_eq!(uids(&ics::export(&app, &past, today, 615, Part::All).text), before);
+
+    // Separate calendars for plan and actual
+    let plan_only = ics::export(&app, &past, today, 615, Part::Plan);
+    assert!(plan_only.text.contains("X-WR-CALNAME:chute-kun Plan\r\n"));
+    assert_eq!(plan_only.events, 4);
+    assert_eq!(ics::export(&app, &past, today, 615, Part::Actual).events, 3);
+}
+
+#[test]
+fn palette_export_writes_next_to_the_snapshot() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("snapshot.toml");
+    let mut app = chute_kun::storage::new_app_at_path(&state, Default::default());
+    app.add_task("Focus", 25);
+    let key = |app: &mut App, code| app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    key(&mut app, KeyCode::Char(':'));
+    for c in "export ics plan".chars() {
+        key(&mut app, KeyCode::Char(c));
+    }
+    key(&mut app, KeyCode::Enter);
+    let written = std::fs::read_to_string(dir.path().join("chute.ics")).unwrap();
+    assert_eq!(events(&written).len(), 1);
+    match app.command_feedback() {
+        Some(chute_kun::app::CommandFeedback::Done(msg)) => {
+            assert!(msg.starts_with("exported 1 events to "), "{msg}")
+        }
+        other => panic!("expected a confirmation, got {other:?}"),
+    }
+}
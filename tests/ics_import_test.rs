@@ -0,0 +1,185 @@
+use assert_cmd::prelude::*;
+use chute_kun::ics;
+use std::process::Command;
+use tempfile::tempdir;
+
+// 2025-09-03 is a Wednesday
+const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:standup\r
+DTSTART:20250903T100000\r
+DTEND:20250903T101500\r
+SUMMARY:Standup\\, team\r
+  A\r
+CATEGORIES:work\r
+BEGIN:VALARM\r
+DESCRIPTION:not an event\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:sync\r
+DTSTART;TZID=Asia/Tokyo:20250901T140000\r
+DURATION:PT1H\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE\r
+SUMMARY:Sync\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review\r
+DTSTART:20250827T150000\r
+DURATION:PT45M\r
+RRULE:FREQ=WEEKLY;UNTIL=20251231T000000Z\r
+EXDATE:20250903T150000,20250910T150000\r
+SUMMARY:Review (skipped this week)\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:gym\r
+DTSTART:20250901T070000\r
+DTEND:20250901T080000\r
+RRULE:FREQ=DAILY;INTERVAL=2\r
+SUMMARY:Gym\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:once\r
+DTSTART:20250902T090000\r
+DTEND:20250902T093000\r
+RRULE:FREQ=DAILY;COUNT=1\r
+SUMMARY:Only yesterday\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:lunch\r
+DTSTART:20250901T120000\r
+DTEND:20250901T130000\r
+RRULE:FREQ=DAILY\r
+SUMMARY:Lunch\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:lunch\r
+RECURRENCE-ID:20250903T120000\r
+DTSTART:20250903T163000\r
+DTEND:20250903T170000\r
+SUMMARY:Late lunch\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday\r
+DTSTART;VALUE=DATE:20250903\r
+SUMMARY:All day\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:cancelled\r
+DTSTART:20250903T110000\r
+STATUS:CANCELLED\r
+SUMMARY:Cancelled\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:monthly\r
+DTSTART:20250803T110000\r
+RRULE:FREQ=MONTHLY\r
+SUMMARY:Monthly\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn events_of_the_day_honour_rrule_exdate_and_overrides() {
+    let got: Vec<(String, String, Option<u16>, Option<u16>)> =
+        ics::appointments(CALENDAR, 20250903)
+            .into_iter()
+            .map(|t| (t.id, t.title, t.due_min, t.estimate_min))
+            .collect();
+    assert_eq!(
+        got,
+        [
+            ("gym/20250903".to_string(), "Gym".to_string(), Some(7 * 60), Some(60)),
+            ("standup/20250903".into(), "Standup, team A".into(), Some(10 * 60), Some(15)),
+            ("sync/20250903".into(), "Sync".into(), Some(14 * 60), Some(60)),
+            ("lunch/20250903".into(), "Late lunch".into(), Some(16 * 60 + 30), Some(30)),
+        ]
+    );
+    assert_eq!(ics::appointments(CALENDAR, 20250903)[1].labels, ["work"]);
+    // The next day lunch is back at noon and gym rests
+    let titles: Vec<String> =
+        ics::appointments(CALENDAR, 20250904).into_iter().map(|t| t.title).collect();
+    assert_eq!(titles, ["Lunch"]);
+}
+
+#[test]
+fn out_of_range_times_and_durations_are_rejected() {
+    let calendar = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:late\r
+DTSTART:20250903T250000\r
+SUMMARY:Hour 25\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:odd\r
+DTSTART:20250903T097500\r
+SUMMARY:Minute 75\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:long\r
+DTSTART:20250903T090000\r
+DURATION:P99999999999W\r
+SUMMARY:Forever\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:weeks\r
+DTSTART:20250903T100000\r
+DURATION:P500000W\r
+SUMMARY:Many weeks\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    // Bad start times skip the event; an overflowing duration leaves the estimate unset
+    let got: Vec<(String, Option<u16>)> = ics::appointments(calendar, 20250903)
+        .into_iter()
+        .map(|t| (t.title, t.estimate_min))
+        .collect();
+    assert_eq!(got, [("Forever".to_string(), None), ("Many weeks".to_string(), None)]);
+}
+
+#[test]
+fn import_ics_adds_fixed_start_tasks_once_in_time_order() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    std::fs::write(d.join("work.ics"), CALENDAR).unwrap();
+    std::fs::write(d.join("config.toml"), "day_start = \"09:00\"\n").unwrap();
+    let chute = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("chute").unwrap();
+        cmd.env("CHUTE_KUN_CONFIG", d.join("config.toml"))
+            .env("CHUTE_KUN_TODAY", "2025-09-03")
+            .arg("--state")
+            .arg(d.join("snapshot.toml"))
+            .args(args);
+        cmd
+    };
+    let json = |args: &[&str]| -> serde_json::Value {
+        let out = chute(args).output().unwrap();
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+    chute(&["add", "Write", "120m"]).assert().success();
+    chute(&["add", "Mail", "10m"]).assert().success();
+
+    let file = d.join("work.ics");
+    let out = json(&["--json", "import", "ics", file.to_str().unwrap()]);
+    assert_eq!(out["added"].as_array().unwrap().len(), 4);
+    let again = json(&["--json", "import", "ics", file.to_str().unwrap()]);
+    assert_eq!((again["added"].as_array().unwrap().len(), &again["skipped"]), (0, &4.into()));
+
+    let tasks = json(&["--json", "list"])["tasks"].as_array().unwrap().clone();
+    let order: Vec<&str> = tasks.iter().map(|t| t["title"].as_str().unwrap()).collect();
+    // Gym (07:00) leads and holds 09:00-10:00, so Standup goes before Write
+    assert_eq!(order, ["Gym", "Standup, team A", "Write", "Mail", "Sync", "Late lunch"]);
+    assert_eq!(tasks[1]["fixed_start_min"], 600);
+    assert_eq!(tasks[1]["estimate_min"], 15);
+    assert_eq!(tasks[1]["category"], "Work");
+    assert_eq!(tasks[1]["remote_id"], "ics:standup/20250903");
+
+    // Without a file, `[ics] paths` is required
+    let out = chute(&["import", "ics"]).output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let config = format!("[ics]\npaths = [{:?}]\n", file.to_str().unwrap());
+    std::fs::write(d.join("config.toml"), config).unwrap();
+    assert_eq!(json(&["--json", "import", "ics"])["skipped"], 4);
+}
@@ -0,0 +1,107 @@
+use chute_kun::journal::{self, Journal, JournalEvent};
+use chute_kun::{app::App, config::Config, history::HistoryStore, storage, task::TaskState};
+use crossterm::event::KeyCode;
+use tempfile::tempdir;
+
+#[test]
+fn unsaved_changes_are_replayed_from_the_journal() {
+    std::env::set_var("CHUTE_KUN_TODAY", "2025-09-01");
+    let dir = tempdir().expect("tempdir");
+    let snap = dir.path().join("snapshot.toml");
+    let jpath = journal::journal_path_for(&snap);
+
+    let mut app = storage::new_app_at_path(&snap, Config::default());
+    let mut j = Journal::open(jpath.clone(), &app);
+    app.add_task("A", 30);
+    app.add_task("B", 10);
+    assert!(j.record(&mut app).unwrap());
+    app.handle_key(KeyCode::Enter); // start A
+    assert!(j.record(&mut app).unwrap());
+    app.handle_key(KeyCode::Char(']')); // move A below B
+    assert!(j.record(&mut app).unwrap());
+    app.finish_active();
+    assert!(j.record(&mut app).unwrap());
+    assert!(!j.record(&mut app).unwrap(), "no change, no entry");
+
+    let events: Vec<_> =
+        journal::read_entries(&jpath).unwrap().into_iter().map(|e| e.event).collect();
+    assert_eq!(
+        events,
+        vec![JournalEvent::Add, JournalEvent::Start, JournalEvent::Reorder, JournalEvent::Finish]
+    );
+
+    // "Crash": no snapshot was ever written, yet loading recovers everything
+    let mut recovered = storage::load_from_path(&snap, Config::default()).unwrap().unwrap();
+    let titles: Vec<_> = recovered.day.tasks.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, vec!["B", "A"]);
+    assert_eq!(recovered.day.tasks[1].state, TaskState::Done);
+    assert_eq!(recovered.journal_seq(), 4);
+
+    // A checkpoint folds the journal into the snapshot and truncates it
+    let leftover = std::fs::read_to_string(&jpath).unwrap();
+    let mut j = Journal::open(jpath.clone(), &recovered);
+    j.checkpoint(&mut recovered, &snap).unwrap();
+    assert!(!jpath.exists());
+    assert!(std::fs::read_to_string(&snap).unwrap().contains("journal_seq = 4"));
+
+    // Crash between snapshot rename and truncation: stale entries are not applied twice
+    std::fs::write(&jpath, leftover).unwrap();
+    let again = storage::load_from_path(&snap, Config::default()).unwrap().unwrap();
+    assert_eq!(again.day.tasks.len(), 2);
+}
+
+#[test]
+fn torn_last_line_is_ignored_and_atomic_write_leaves_no_temp() {
+    let dir = tempdir().expect("tempdir");
+    let jpath = dir.path().join("snapshot.journal");
+    std::fs::write(&jpath, "{\"seq\":1,\"ts\":0,\"event\":\"add\",\"ops\":[]}\n{\"seq\":2,\"ts")
+        .unwrap();
+    let entries = journal::read_entries(&jpath).unwrap();
+    assert_eq!(entries.len(), 1);
+
+    let p = dir.path().join("snapshot.toml");
+    storage::write_atomic(&p, b"version = 1\n").unwrap();
+    storage::write_atomic(&p, b"version = 1\n# second\n").unwrap();
+    assert_eq!(std::fs::read_to_string(&p).unwrap(), "version = 1\n# second\n");
+    let names: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert!(names.iter().all(|n| !n.ends_with(".tmp")), "{names:?}");
+}
+
+#[test]
+fn past_edits_and_routine_changes_are_replayed() {
+    let dir = tempdir().expect("tempdir");
+    let snap = dir.path().join("snapshot.toml");
+    let jpath = journal::journal_path_for(&snap);
+    let mut done = chute_kun::task::Task::new("Old report", 20);
+    done.state = TaskState::Done;
+    done.done_ymd = Some(20250829);
+    HistoryStore::for_state_path(&snap).save_day(20250829, &[done]).unwrap();
+
+    let mut app = storage::new_app_at_path(&snap, Config::default());
+    let mut j = Journal::open(jpath.clone(), &app);
+    app.add_task("Stretch", 10);
+    assert!(j.record(&mut app).unwrap());
+    let type_line = |app: &mut App, line: &str| {
+        app.handle_key(KeyCode::Char(':'));
+        for c in line.chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+    };
+    type_line(&mut app, "routine daily");
+    assert!(j.record(&mut app).unwrap());
+    type_line(&mut app, "view past");
+    assert!(!j.record(&mut app).unwrap(), "loading Past is not a change");
+    type_line(&mut app, "rename Final report");
+    assert!(j.record(&mut app).unwrap());
+
+    // "Crash" before any checkpoint
+    let recovered = storage::load_from_path(&snap, Config::default()).unwrap().unwrap();
+    assert_eq!(recovered.routines().len(), 1);
+    assert_eq!(recovered.day.tasks[0].routine_id, Some(recovered.routines()[0].id));
+    assert_eq!(recovered.history_tasks_for_day(20250829)[0].title, "Final report");
+    assert!(recovered.history_dirty_days().contains(&20250829));
+}
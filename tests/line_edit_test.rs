@@ -0,0 +1,85 @@
+use chute_kun::{app::App, line_edit::LineEditor, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+#[test]
+fn editor_keys_and_cjk_cursor_width() {
+    let mut ed = LineEditor::new("資料 作成 today");
+    assert_eq!(ed.cursor(), 11);
+    let ctrl = |c| (KeyCode::Char(c), KeyModifiers::CONTROL);
+
+    let (code, mods) = ctrl('w');
+    assert!(ed.handle_key(code, mods));
+    assert_eq!(ed.text(), "資料 作成 ");
+    ed.handle_key(KeyCode::Left, KeyModifiers::ALT);
+    assert_eq!(ed.cursor(), 3);
+    // Two wide chars before the cursor plus a space
+    assert_eq!(ed.cursor_width(), 5);
+    ed.handle_key(KeyCode::Char('X'), KeyModifiers::NONE);
+    ed.handle_key(KeyCode::Delete, KeyModifiers::NONE);
+    assert_eq!(ed.text(), "資料 X成 ");
+
+    let (code, mods) = ctrl('k');
+    ed.handle_key(code, mods);
+    assert_eq!(ed.text(), "資料 X");
+    let (code, mods) = ctrl('a');
+    ed.handle_key(code, mods);
+    assert_eq!(ed.cursor(), 0);
+    ed.handle_key(KeyCode::Char('f'), KeyModifiers::ALT);
+    assert_eq!(ed.cursor(), 2);
+    let (code, mods) = ctrl('u');
+    ed.handle_key(code, mods);
+    assert_eq!((ed.text(), ed.cursor()), (" X", 0));
+    // Enter/Esc/Up are left to the caller
+    assert!(!ed.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+}
+
+#[test]
+fn inputs_recall_their_own_history_and_render_the_cursor() {
+    let mut app = App::new();
+    app.add_task("A", 20);
+    let key = |app: &mut App, code, mods| app.handle_key_event(KeyEvent::new(code, mods));
+    let run = |app: &mut App, cmd: &str| {
+        app.handle_key(KeyCode::Char(':'));
+        for c in cmd.chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+    };
+    run(&mut app, "est +5m");
+    run(&mut app, "est 40m");
+    assert_eq!(app.day.tasks[0].estimate_min, 40);
+
+    // Title history is separate from command history
+    key(&mut app, KeyCode::Char('i'), KeyModifiers::NONE);
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(app.input_buffer(), Some(""));
+    key(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+
+    key(&mut app, KeyCode::Char(':'), KeyModifiers::NONE);
+    key(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(app.input_buffer(), Some("est 40m"));
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(app.input_buffer(), Some("est +5m"));
+    key(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Down, KeyModifiers::NONE);
+    assert_eq!(app.input_buffer(), Some("e"), "draft comes back after the newest entry");
+    key(&mut app, KeyCode::Up, KeyModifiers::NONE);
+    // Edit the recalled line in the middle: est 40m -> est 45m
+    key(&mut app, KeyCode::Left, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+    key(&mut app, KeyCode::Char('5'), KeyModifiers::NONE);
+    key(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(app.day.tasks[0].estimate_min, 45);
+
+    // Cursor after wide chars lands two cells per char
+    key(&mut app, KeyCode::Char('i'), KeyModifiers::NONE);
+    app.handle_paste("日本語");
+    key(&mut app, KeyCode::Left, KeyModifiers::NONE);
+    let mut terminal = Terminal::new(TestBackend::new(60, 14)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let popup = ui::compute_input_popup_rect(&app, Rect::new(0, 0, 60, 14)).unwrap();
+    let pos = terminal.get_cursor_position().unwrap();
+    assert_eq!((pos.x, pos.y), (popup.x + 1 + "Title: ".len() as u16 + 4, popup.y + 1));
+}
@@ -23,11 +23,11 @@ fn past_cycle_and_picker_work() {
     app.apply_snapshot(vec![], vec![], vec![sample_done("P")]);
     app.handle_key(KeyCode::BackTab); // Today -> Past
     assert_eq!(app.history_tasks().len(), 1);
-    assert!(matches!(app.history_tasks()[0].category, Category::General));
+    assert_eq!(app.history_tasks()[0].category, Category::GENERAL);
 
     // 1) 'c' でカテゴリ循環（General -> Work）
     app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
-    assert!(matches!(app.history_tasks()[0].category, Category::Work));
+    assert_eq!(app.history_tasks()[0].category, Category::WORK);
 
     // 2) Shift+'c' でピッカーを開き、Down -> Down -> Enter で Hobby に設定
     app.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::SHIFT));
@@ -35,7 +35,7 @@ fn past_cycle_and_picker_work() {
     app.handle_key(KeyCode::Down); // Home
     app.handle_key(KeyCode::Down); // Hobby
     app.handle_key(KeyCode::Enter);
-    assert!(matches!(app.history_tasks()[0].category, Category::Hobby));
+    assert_eq!(app.history_tasks()[0].category, Category::HOBBY);
 
     terminal.draw(|f| ui::draw(f, &app)).unwrap();
 }
@@ -0,0 +1,77 @@
+use chute_kun::{
+    app::App,
+    config::Config,
+    date::{add_days_to_ymd, today_ymd},
+    headless::{self, Command},
+    quick_add::QuickAdd,
+    task::Category,
+};
+use crossterm::event::KeyCode;
+
+#[test]
+fn parses_estimate_category_time_date_and_tags() {
+    let cfg = Config::default();
+    let monday = 20251020;
+    let q = QuickAdd::parse("Write report 1h30m @Work ^9:30 >fri #client-a 3 pages", &cfg, monday);
+    assert_eq!(q.title, "Write report 3 pages");
+    assert_eq!(q.estimate_min, Some(90));
+    assert_eq!(q.category, Some(Category::WORK));
+    assert_eq!(q.fixed_start_min, Some(9 * 60 + 30));
+    assert_eq!(q.planned_ymd, Some(20251024));
+    assert_eq!(q.tags, ["client-a"]);
+
+    // Unknown categories and malformed words stay in the title; the last field wins
+    let q = QuickAdd::parse("Mail @bob ^25:00 >mon 10m 2h", &cfg, monday);
+    assert_eq!(q.title, "Mail @bob ^25:00");
+    assert_eq!(q.estimate_min, Some(120));
+    assert_eq!(q.planned_ymd, Some(20251027), "a weekday means the next one");
+    assert_eq!(QuickAdd::parse("Run >3d", &cfg, monday).planned_ymd, Some(20251023));
+}
+
+#[test]
+fn out_of_range_dates_and_durations_stay_in_the_title() {
+    let cfg = Config::default();
+    let q = QuickAdd::parse("x >99999999d", &cfg, 20251020);
+    assert_eq!(q.title, "x >99999999d");
+    assert_eq!(q.planned_ymd, None);
+    let q = QuickAdd::parse("x >4294967295d 99999999h", &cfg, 20251020);
+    assert_eq!(q.title, "x >4294967295d 99999999h");
+    assert_eq!((q.planned_ymd, q.estimate_min), (None, None));
+}
+
+#[test]
+fn title_input_and_cli_add_create_the_same_task() {
+    let line = "Write report 45m @work ^14:00 >tomorrow #client-a";
+    let mut app = App::new();
+    app.handle_key(KeyCode::Char('i'));
+    for c in line.chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+    assert!(!app.in_input_mode(), "an estimate in the title skips the estimate popup");
+    let tui = app.tomorrow_tasks()[0].clone();
+    assert_eq!(tui.title, "Write report");
+    assert_eq!(
+        (tui.estimate_min, tui.fixed_start_min, tui.planned_ymd),
+        (45, Some(14 * 60), add_days_to_ymd(today_ymd(), 1))
+    );
+    assert_eq!(tui.category, Category::WORK);
+    assert_eq!(tui.tags, ["client-a"]);
+
+    let mut cli = App::new();
+    let cmd = Command::Add { title: line.to_string(), estimate_min: None, ymd: None };
+    headless::run(&mut cli, &cmd).unwrap();
+    assert_eq!(cli.tomorrow_tasks(), &vec![tui]);
+
+    // Without an estimate the popup still asks, keeping the other fields
+    app.handle_key(KeyCode::Char('i'));
+    for c in "Review @home".chars() {
+        app.handle_key(KeyCode::Char(c));
+    }
+    app.handle_key(KeyCode::Enter);
+    assert!(app.is_new_task_estimate());
+    assert_eq!(app.new_task_title(), Some("Review"));
+    app.handle_key(KeyCode::Enter);
+    let t = app.day.tasks.last().unwrap();
+    assert_eq!((t.title.as_str(), t.estimate_min, &t.category), ("Review", 25, &Category::HOME));
+}
@@ -0,0 +1,36 @@
+use chute_kun::{config::Config, routine::EstimatePolicy};
+
+#[test]
+fn parse_policies_with_default_window() {
+    assert_eq!(EstimatePolicy::parse("fixed"), Some(EstimatePolicy::Fixed));
+    assert_eq!(EstimatePolicy::parse("last"), Some(EstimatePolicy::Last));
+    assert_eq!(EstimatePolicy::parse("mean"), Some(EstimatePolicy::Mean { window: 5 }));
+    assert_eq!(EstimatePolicy::parse("median:3"), Some(EstimatePolicy::Median { window: 3 }));
+    assert_eq!(
+        EstimatePolicy::parse("P80:10"),
+        Some(EstimatePolicy::Percentile { p: 80, window: 10 })
+    );
+    assert_eq!(EstimatePolicy::parse("p0"), None);
+    assert_eq!(EstimatePolicy::parse("mean:0"), None);
+    assert_eq!(EstimatePolicy::parse("mode"), None);
+}
+
+#[test]
+fn estimates_use_only_the_recent_window() {
+    let hist = [100, 10, 20, 30, 40];
+    assert_eq!(EstimatePolicy::Fixed.estimate(&hist), None);
+    assert_eq!(EstimatePolicy::Last.estimate(&hist), Some(40));
+    assert_eq!(EstimatePolicy::Mean { window: 4 }.estimate(&hist), Some(25));
+    assert_eq!(EstimatePolicy::Median { window: 3 }.estimate(&hist), Some(30));
+    assert_eq!(EstimatePolicy::Percentile { p: 80, window: 4 }.estimate(&hist), Some(40));
+    assert_eq!(EstimatePolicy::Percentile { p: 50, window: 5 }.estimate(&hist), Some(30));
+    assert_eq!(EstimatePolicy::Mean { window: 5 }.estimate(&[]), None);
+}
+
+#[test]
+fn config_routine_estimate_key() {
+    let cfg = Config::from_toml_str("routine_estimate = \"p90:8\"\n").expect("parse");
+    assert_eq!(cfg.routine_estimate, EstimatePolicy::Percentile { p: 90, window: 8 });
+    assert!(Config::from_toml_str("routine_estimate = \"sometimes\"\n").is_err());
+    assert_eq!(Config::default().routine_estimate, EstimatePolicy::Mean { window: 5 });
+}
@@ -0,0 +1,61 @@
+use chute_kun::routine::{Recurrence, Routine, Weekday};
+use chute_kun::task::Task;
+
+fn routine(rec: Recurrence, anchor: u32) -> Routine {
+    Routine::from_task(1, &Task::new("Morning review", 15), rec, anchor)
+}
+
+#[test]
+fn parse_compact_rules() {
+    assert_eq!(Recurrence::parse("daily"), Some(Recurrence::Daily));
+    assert_eq!(Recurrence::parse("weekdays"), Some(Recurrence::Weekdays));
+    assert_eq!(
+        Recurrence::parse("fri,mon"),
+        Some(Recurrence::Weekly { days: vec![Weekday::Mon, Weekday::Fri] })
+    );
+    assert_eq!(
+        Recurrence::parse("weekly wed"),
+        Some(Recurrence::Weekly { days: vec![Weekday::Wed] })
+    );
+    assert_eq!(Recurrence::parse("every 3d"), Some(Recurrence::EveryNDays { n: 3 }));
+    assert_eq!(Recurrence::parse("monthly 31"), Some(Recurrence::Monthly { day: 31 }));
+    assert_eq!(Recurrence::parse("monthly"), None);
+    assert_eq!(Recurrence::parse("every 0"), None);
+}
+
+#[test]
+fn occurrence_rules_match_expected_dates() {
+    // 2025-09-01 is a Monday
+    let weekdays = routine(Recurrence::Weekdays, 20250101);
+    assert!(weekdays.is_due(20250901));
+    assert!(!weekdays.is_due(20250906)); // Saturday
+
+    let every3 = routine(Recurrence::EveryNDays { n: 3 }, 20250901);
+    assert!(every3.is_due(20250901));
+    assert!(!every3.is_due(20250902));
+    assert!(every3.is_due(20250904));
+    assert!(!every3.is_due(20250829), "never fires before the anchor");
+
+    // Day 31 falls on the last day of shorter months
+    let monthly = routine(Recurrence::Monthly { day: 31 }, 20250101);
+    assert!(monthly.is_due(20250228));
+    assert!(!monthly.is_due(20250227));
+    assert!(monthly.is_due(20250331));
+
+    // Invalid dates never fire (and never panic)
+    assert!(!Recurrence::EveryNDays { n: 3 }.occurs_on(20250901, 20250100));
+    assert!(!weekdays.is_due(20250230));
+    assert!(!Recurrence::Weekly { days: vec![Weekday::Mon] }.occurs_on(20250000, 20250101));
+}
+
+#[test]
+fn last_generated_date_blocks_duplicates() {
+    let mut r = routine(Recurrence::Daily, 20250101);
+    r.last_generated_ymd = Some(20250901);
+    assert!(!r.is_due(20250901));
+    assert!(r.is_due(20250902));
+    let t = r.instantiate(20250902);
+    assert_eq!(t.routine_id, Some(1));
+    assert_eq!(t.planned_ymd, 20250902);
+    assert_eq!(t.estimate_min, 15);
+}
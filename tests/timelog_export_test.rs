@@ -0,0 +1,150 @@
+use assert_cmd::prelude::*;
+use chute_kun::{
+    history::HistoryStore,
+    task::{Category, Session, Task, TaskState},
+    timelog::{self, Query, Rows},
+};
+use std::process::Command;
+use tempfile::tempdir;
+
+fn done(title: &str, ymd: u32, sessions: &[(u16, u16)]) -> Task {
+    let mut t = Task::new(title, 30);
+    t.state = TaskState::Done;
+    t.planned_ymd = ymd;
+    t.done_ymd = Some(ymd);
+    t.sessions =
+        sessions.iter().map(|&(s, e)| Session { start_min: s, end_min: Some(e) }).collect();
+    t.actual_min =
+        t.sessions.iter().map(|s| (s.end_min.unwrap() + 1440 - s.start_min) % 1440).sum();
+    t
+}
+
+#[test]
+fn sessions_and_tasks_span_past_today_and_future() {
+    let dir = tempdir().unwrap();
+    let state = dir.path().join("snapshot.toml");
+    let today = chute_kun::date::today_ymd();
+    let day = |offset: i32| chute_kun::date::add_days_to_ymd(today, offset);
+    let store = HistoryStore::for_state_path(&state);
+    let mut call = done("Client call, \"Acme\"", day(-2), &[(23 * 60 + 50, 17), (600, 610)]);
+    call.category = Category::WORK;
+    call.tags = vec!["acme".into(), "billable".into()];
+    store.save_day(day(-2), &[call]).unwrap();
+    store.save_day(day(-10), &[done("Too old", day(-10), &[(600, 630)])]).unwrap();
+
+    let mut app = chute_kun::storage::new_app_at_path(&state, Default::default());
+    app.add_task("Write", 45);
+    app.day.tasks[0].sessions = vec![
+        Session { start_min: 600, end_min: None },
+        Session { start_min: 540, end_min: Some(560) },
+    ];
+    let mut trip = Task::new("Plan trip", 20);
+    trip.planned_ymd = day(1);
+    app.add_planned_task(trip);
+
+    let query =
+        Query { from: Some(day(-2)), to: Some(day(1)), rows: Rows::Sessions, round_min: None };
+    let (from, to) = query.range(today);
+    let days = timelog::collect(&app, today, from, to).unwrap();
+    assert_eq!(days.iter().map(|(d, _)| *d).collect::<Vec<_>>(), [day(-2), today, day(1)]);
+
+    let csv = timelog::table(&app, &days, 607, &query).to_csv();
+    let ymd = |d: u32| chute_kun::date::format_ymd(d);
+    let expected = [
+        "date,task,category,start,end,duration_min,tags".to_string(),
+        format!("{},\"Client call, \"\"Acme\"\"\",Work,10:00,10:10,10,acme billable", ymd(day(-2))),
+        format!("{},\"Client call, \"\"Acme\"\"\",Work,23:50,00:17,27,acme billable", ymd(day(-2))),
+        format!("{},Write,General,09:00,09:20,20,", ymd(today)),
+        format!("{},Write,General,10:00,,7,", ymd(today)),
+    ];
+    assert_eq!(csv, expected.join("\r\n") + "\r\n");
+
+    // Rounded to 5 minutes: 27 -> 25 and the running 7 -> 5
+    let rounded = Query { round_min: Some(5), ..query };
+    let json = timelog::table(&app, &days, 607, &rounded).to_json();
+    let mins: Vec<i64> =
+        json.as_array().unwrap().iter().map(|r| r["duration_min"].as_i64().unwrap()).collect();
+    assert_eq!(mins, [10, 25, 20, 5]);
+    assert_eq!(json[3]["end"], serde_json::Value::Null);
+
+    // One row per task, Future included
+    let tasks = Query { rows: Rows::Tasks, ..rounded };
+    let json = timelog::table(&app, &days, 607, &tasks).to_json();
+    let rows = json.as_array().unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["actual_min"], 35);
+    assert_eq!(rows[0]["done_date"], ymd(day(-2)));
+    assert_eq!(
+        (&rows[0]["tags"], &rows[1]["tags"]),
+        (&"acme billable".into(), &serde_json::Value::Null)
+    );
+    assert_eq!((&rows[2]["task"], &rows[2]["state"]), (&"Plan trip".into(), &"planned".into()));
+    assert_eq!(rows[2]["planned_date"], ymd(day(1)));
+}
+
+#[test]
+fn export_csv_and_json_from_the_cli() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    let state = d.join("snapshot.toml");
+    let chute = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("chute").unwrap();
+        cmd.env("CHUTE_KUN_CONFIG", d.join("config.toml"))
+            .env("CHUTE_KUN_TODAY", "2025-09-03")
+            .arg("--state")
+            .arg(&state)
+            .args(args);
+        cmd
+    };
+    chute(&["add", "Write", "30m"]).assert().success();
+    chute(&["add", "Trip", "20m", "--date", "2025-09-05"]).assert().success();
+    HistoryStore::for_state_path(&state)
+        .save_day(20250901, &[done("Review", 20250901, &[(540, 575)])])
+        .unwrap();
+
+    let out = chute(&[
+        "--json",
+        "export",
+        "--format",
+        "json",
+        "--rows",
+        "tasks",
+        "--from",
+        "2025-09-01",
+        "--to",
+        "2025-09-04",
+    ])
+    .output()
+    .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!((&v["format"], &v["rows"]), (&"json".into(), &2.into()));
+    let titles: Vec<&str> =
+        v["content"].as_array().unwrap().iter().map(|r| r["task"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["Review", "Write"]);
+
+    // --from alone runs through today
+    let file = d.join("log.csv");
+    chute(&[
+        "export",
+        "--format",
+        "csv",
+        "--from",
+        "2025-09-01",
+        "--round",
+        "15",
+        "-o",
+        file.to_str().unwrap(),
+    ])
+    .assert()
+    .success();
+    assert_eq!(
+        std::fs::read_to_string(&file).unwrap(),
+        "date,task,category,start,end,duration_min,tags\r\n2025-09-01,Review,General,09:00,09:35,30,\r\n"
+    );
+
+    let out = chute(&["export", "--format", "csv", "--from", "2025-09-04", "--to", "2025-09-01"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -0,0 +1,168 @@
+use assert_cmd::prelude::*;
+use chute_kun::{
+    app::App,
+    outbox::Outbox,
+    source::{self, RemoteTask},
+    todoist::{self, SyncWorker, TodoistConfig, TodoistOp, TodoistSource, Tracker},
+    ui,
+};
+use ratatui::{backend::TestBackend, Terminal};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// Answer one connection per response with `(status, body)`, reporting
+/// `METHOD path body` for each request.
+fn mock(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for (stream, (status, body)) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut len = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    len = v.trim().parse().unwrap();
+                }
+            }
+            let mut buf = vec![0; len];
+            reader.read_exact(&mut buf).unwrap();
+            let target = request.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+            tx.send(format!("{target} {}", String::from_utf8_lossy(&buf)).trim().to_string())
+                .unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn remote(id: &str, title: &str) -> RemoteTask {
+    RemoteTask { id: id.into(), title: title.into(), ..Default::default() }
+}
+
+#[test]
+fn finish_and_postpone_queue_calls_that_survive_a_failed_delivery() {
+    let mut app = App::new();
+    source::import(&mut app, "todoist", vec![remote("7", "Write report"), remote("8", "Mail")]);
+    app.add_task("Local only", 10);
+    let mut tracker = Tracker::open(&app);
+
+    app.day.tasks[0].sessions.push(chute_kun::task::Session { start_min: 540, end_min: None });
+    app.select_index(0);
+    app.finish_selected();
+    app.select_index(1);
+    app.postpone_selected();
+    app.select_index(1);
+    app.finish_selected(); // not imported: no call
+
+    let ops = tracker.observe(&app);
+    assert_eq!(ops.len(), 3, "{ops:?}");
+    match &ops[0] {
+        TodoistOp::Comment { task_id, content } => {
+            assert_eq!(task_id, "7");
+            assert!(content.starts_with("tc-log | Write report | act:"), "{content}");
+            assert!(content.contains("| est:25m | state:Done | date:"), "{content}");
+            assert!(content.contains("| sessions:09:00-"), "{content}");
+        }
+        other => panic!("expected a comment, got {other:?}"),
+    }
+    assert_eq!(ops[1], TodoistOp::Close { task_id: "7".into() });
+    let tomorrow = chute_kun::date::add_days_to_ymd(chute_kun::date::today_ymd(), 1);
+    assert_eq!(ops[2], TodoistOp::Due { task_id: "8".into(), ymd: tomorrow });
+    assert!(tracker.observe(&app).is_empty());
+
+    // Offline: the calls stay on disk and the header shows the problem
+    let dir = tempdir().unwrap();
+    let path = todoist::queue_path_for(&dir.path().join("snapshot.toml"));
+    let mut queue = Outbox::open(path.clone()).unwrap();
+    queue.push(ops).unwrap();
+    let (url, _down) = mock(vec![(503, "down")]);
+    let source = TodoistSource::new(&TodoistConfig { base_url: url, ..Default::default() }, "t");
+    let status = todoist::deliver(&Mutex::new(queue), Some(&source));
+    assert_eq!(status.pending, 3);
+    app.set_sync_notice("todoist", status.notice());
+    let mut terminal = Terminal::new(TestBackend::new(120, 10)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let top: String = (0..120).map(|x| terminal.backend().buffer()[(x, 0)].symbol()).collect();
+    assert!(top.contains("Sync todoist: POST /comments returned 503: down (3 queued)"), "{top}");
+
+    // Back online: a worker started from the file delivers them in order
+    let (url, requests) = mock(vec![(200, "{}"), (204, ""), (204, "")]);
+    let source = TodoistSource::new(&TodoistConfig { base_url: url, ..Default::default() }, "t");
+    let worker = SyncWorker::spawn(Outbox::open(path.clone()).unwrap(), Some(source));
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let status = loop {
+        if let Some(s) = worker.try_status() {
+            break s;
+        }
+        assert!(Instant::now() < deadline, "worker did not report");
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    assert_eq!((status.pending, status.error), (0, None));
+    let sent: Vec<String> = requests.try_iter().collect();
+    assert!(sent[0].starts_with(r#"POST /comments {"content":"tc-log | Write report"#));
+    assert_eq!(sent[1], "POST /tasks/7/close");
+    assert_eq!(
+        sent[2],
+        format!(r#"POST /tasks/8 {{"due_date":"{}"}}"#, chute_kun::date::format_ymd(tomorrow))
+    );
+    assert!(!path.exists(), "an emptied queue removes its file");
+}
+
+#[test]
+fn headless_finish_closes_the_remote_task() {
+    let dir = tempdir().unwrap();
+    let d = dir.path();
+    let tasks = r#"[{"id": "42", "content": "Call bank", "labels": []},
+                    {"id": "43", "content": "Pay rent", "labels": []}]"#;
+    let (url, requests) = mock(vec![(200, tasks), (204, "")]);
+    std::fs::write(
+        d.join("config.toml"),
+        format!("[todoist]\nbase_url = \"{url}\"\ncomment_on_finish = false\n"),
+    )
+    .unwrap();
+    let chute = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("chute").unwrap();
+        cmd.env("CHUTE_KUN_CONFIG", d.join("config.toml"))
+            .env("CHUTE_KUN_TODAY", "2025-09-01")
+            .env("TODOIST_API_TOKEN", "t")
+            .arg("--state")
+            .arg(d.join("snapshot.toml"))
+            .args(args);
+        cmd
+    };
+    chute(&["import", "todoist"]).assert().success();
+    chute(&["finish", "Call bank"]).assert().success();
+    let sent: Vec<String> = requests.iter().take(2).collect();
+    assert_eq!(sent[1], "POST /tasks/42/close");
+    assert!(!todoist::queue_path_for(&d.join("snapshot.toml")).exists());
+
+    // Finished tasks stay done
+    chute(&["postpone", "Call bank"]).assert().code(5);
+
+    // The server is gone now: postponing is queued and reported
+    let out = chute(&["postpone", "Pay rent"]).output().unwrap();
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("todoist: POST /tasks/43 failed"), "{stdout}");
+    assert!(stdout.contains("(1 queued)"), "{stdout}");
+    let queue = Outbox::<TodoistOp>::open(todoist::queue_path_for(&d.join("snapshot.toml")));
+    assert_eq!(queue.unwrap().pending(), [TodoistOp::Due { task_id: "43".into(), ymd: 20250902 }]);
+}
@@ -0,0 +1,206 @@
+use chute_kun::{
+    app::App,
+    task::Session,
+    toggl::{self, Sidecar, SyncWorker, TogglClient, TogglConfig},
+};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// Answer one connection per `(status, headers, body)`, reporting
+/// `METHOD path body` and the Authorization header of each request.
+fn mock(
+    responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, mpsc::Receiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api/v9", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for (stream, (status, headers, body)) in listener.incoming().zip(responses) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let (mut len, mut auth) = (0, String::new());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    len = v.trim().parse().unwrap();
+                }
+                if let Some(v) = line.strip_prefix("Authorization: ") {
+                    auth = v.trim().to_string();
+                }
+            }
+            let mut buf = vec![0; len];
+            reader.read_exact(&mut buf).unwrap();
+            let target = request.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+            let line = format!("{target} {}", String::from_utf8_lossy(&buf)).trim().to_string();
+            tx.send((line, auth)).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 {status} X\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+    (url, rx)
+}
+
+fn client(url: &str) -> TogglClient {
+    let mut cfg = TogglConfig::new("tok");
+    cfg.base_url = url.to_string();
+    cfg.tags = vec!["chute".into()];
+    cfg.pace = Duration::ZERO;
+    TogglClient::new(cfg)
+}
+
+fn json(line: &str) -> serde_json::Value {
+    serde_json::from_str(&line[line.find('{').unwrap()..]).unwrap()
+}
+
+#[test]
+fn start_pause_and_reorder_map_sessions_to_entries() {
+    let dir = tempdir().unwrap();
+    let sidecar = toggl::sidecar_path_for(&dir.path().join("snapshot.toml"));
+    assert!(sidecar.ends_with("sync/toggl.json"));
+    let mut app = App::new();
+    app.add_task("Write report", 30);
+    app.add_task("Mail", 10);
+    app.day.tasks[0].sessions.push(Session { start_min: 540, end_min: None });
+    let (url, requests) = mock(vec![
+        (200, "", r#"{"default_workspace_id": 77}"#),
+        (200, "", r#"{"id": 9001}"#),
+        (200, "", r#"{"id": 9001}"#),
+    ]);
+    let mut c = client(&url);
+    let ymd = 20250901;
+
+    // Start: the workspace is looked up once, then a running entry is created
+    let st = toggl::push(&mut c, &sidecar, &toggl::local_sessions(&app.day.tasks, ymd)).unwrap();
+    assert_eq!((st.pending, st.error), (0, None));
+    let (me, auth) = requests.recv().unwrap();
+    assert_eq!(me, "GET /api/v9/me");
+    assert_eq!(auth, "Basic dG9rOmFwaV90b2tlbg=="); // tok:api_token
+    let (create, _) = requests.recv().unwrap();
+    assert!(create.starts_with("POST /api/v9/workspaces/77/time_entries {"), "{create}");
+    let body = json(&create);
+    assert_eq!(body["description"], "Write report");
+    assert_eq!(body["duration"], -1);
+    assert_eq!(body["created_with"], "chute-kun");
+    assert_eq!(body["tags"], serde_json::json!(["chute"]));
+    assert!(body["start"].as_str().unwrap().starts_with("2025-09-01T09:00:00"));
+
+    // Pause: the same entry gets its stop; nothing else is sent
+    app.day.tasks[0].sessions[0].end_min = Some(565);
+    let sessions = toggl::local_sessions(&app.day.tasks, ymd);
+    toggl::push(&mut c, &sidecar, &sessions).unwrap();
+    let (patch, _) = requests.recv().unwrap();
+    assert!(patch.starts_with("PATCH /api/v9/workspaces/77/time_entries/9001 {"), "{patch}");
+    assert_eq!(json(&patch)["duration"], 25 * 60);
+    assert!(json(&patch)["stop"].as_str().unwrap().starts_with("2025-09-01T09:25:00"));
+
+    // Reordering moves the record instead of creating a duplicate
+    app.day.tasks.swap(0, 1);
+    let sessions = toggl::local_sessions(&app.day.tasks, ymd);
+    let st = toggl::push(&mut c, &sidecar, &sessions).unwrap();
+    assert_eq!((st.pending, st.error), (0, None));
+    let saved = Sidecar::load(&sidecar).unwrap();
+    assert_eq!(saved.workspace_id, Some(77));
+    assert_eq!(saved.entries.len(), 1);
+    assert_eq!((saved.entries[0].task_index, saved.entries[0].entry_id), (1, 9001));
+    assert!(saved.plan(&sessions).is_empty());
+}
+
+#[test]
+fn offline_sessions_are_backfilled_after_429_backoff() {
+    let dir = tempdir().unwrap();
+    let sidecar = dir.path().join("sync").join("toggl.json");
+    let mut app = App::new();
+    app.add_task("Plan", 20);
+    app.day.tasks[0].sessions.push(Session { start_min: 480, end_min: Some(500) });
+    app.day.tasks[0].sessions.push(Session { start_min: 510, end_min: None });
+    let sessions = toggl::local_sessions(&app.day.tasks, 20250901);
+
+    // Offline: nothing is recorded and both sessions stay pending
+    let (url, _down) = mock(vec![(503, "", "down")]);
+    let mut c = client(&url);
+    Sidecar { workspace_id: Some(5), entries: vec![] }.save(&sidecar).unwrap();
+    let st = toggl::push(&mut c, &sidecar, &sessions).unwrap();
+    assert_eq!(st.pending, 2);
+    assert!(st
+        .notice()
+        .unwrap()
+        .starts_with("toggl: POST /workspaces/5/time_entries returned 503"));
+
+    // Back online but rate limited: the worker waits for Retry-After and backfills both
+    let (url, requests) = mock(vec![
+        (429, "Retry-After: 0\r\n", "slow down"),
+        (200, "", r#"{"id": 1}"#),
+        (200, "", r#"{"id": 2}"#),
+    ]);
+    let mut cfg = TogglConfig::new("tok");
+    cfg.base_url = url;
+    cfg.pace = Duration::ZERO;
+    let mut worker = SyncWorker::spawn(cfg, sidecar.clone());
+    worker.observe(sessions.clone());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut last = None;
+    while last.as_ref().map_or(true, |s: &toggl::SyncStatus| s.error.is_some()) {
+        assert!(Instant::now() < deadline, "worker did not finish: {last:?}");
+        last = worker.try_status().or(last);
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    let sent: Vec<String> = requests.try_iter().map(|(l, _)| l).collect();
+    assert_eq!(sent.len(), 3, "{sent:?}");
+    assert_eq!(json(&sent[1])["duration"], 20 * 60);
+    assert_eq!(json(&sent[2])["duration"], -1);
+    let saved = Sidecar::load(&sidecar).unwrap();
+    assert_eq!(saved.entries.iter().map(|r| r.entry_id).collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn renaming_after_a_push_updates_the_entry() {
+    let dir = tempdir().unwrap();
+    let sidecar = dir.path().join("sync").join("toggl.json");
+    let mut app = App::new();
+    app.add_task("Draft", 30);
+    app.day.tasks[0].sessions.push(Session { start_min: 540, end_min: None });
+    let (url, requests) = mock(vec![
+        (200, "", r#"{"default_workspace_id": 77}"#),
+        (200, "", r#"{"id": 9001}"#),
+        (200, "", r#"{"id": 9001}"#),
+        (200, "", r#"{"id": 9001}"#),
+    ]);
+    let mut c = client(&url);
+    let ymd = 20250901;
+    toggl::push(&mut c, &sidecar, &toggl::local_sessions(&app.day.tasks, ymd)).unwrap();
+    requests.recv().unwrap();
+    requests.recv().unwrap();
+
+    // Renaming the running task patches its description
+    app.day.tasks[0].title = "Write report".into();
+    toggl::push(&mut c, &sidecar, &toggl::local_sessions(&app.day.tasks, ymd)).unwrap();
+    let (patch, _) = requests.recv().unwrap();
+    assert!(patch.starts_with("PATCH /api/v9/workspaces/77/time_entries/9001 {"), "{patch}");
+    assert_eq!(json(&patch)["description"], "Write report");
+    assert_eq!(json(&patch)["duration"], -1);
+
+    // Renamed again after stopping: still the same entry
+    app.day.tasks[0].title = "Write the report".into();
+    app.day.tasks[0].sessions[0].end_min = Some(570);
+    let sessions = toggl::local_sessions(&app.day.tasks, ymd);
+    toggl::push(&mut c, &sidecar, &sessions).unwrap();
+    let (patch, _) = requests.recv().unwrap();
+    assert!(patch.starts_with("PATCH /api/v9/workspaces/77/time_entries/9001 {"), "{patch}");
+    assert_eq!(json(&patch)["description"], "Write the report");
+    let saved = Sidecar::load(&sidecar).unwrap();
+    assert_eq!(saved.entries.len(), 1);
+    assert!(saved.plan(&sessions).is_empty());
+}
@@ -27,8 +27,8 @@ fn bottom_gauge_shows_category_segments_over_24h() {
     let e2 = 21 * 60 + 15; // 21:15
     app.add_task("Morning Work", 60);
     app.add_task("Late Home", 30);
-    app.day.tasks[0].category = Category::Work; // Blue
-    app.day.tasks[1].category = Category::Home; // Yellow
+    app.day.tasks[0].category = Category::WORK; // Blue
+    app.day.tasks[1].category = Category::HOME; // Yellow
     if let Some(t) = app.day.tasks.get_mut(0) {
         t.sessions.push(Session { start_min: s1, end_min: Some(e1) });
     }
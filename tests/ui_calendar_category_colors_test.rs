@@ -48,8 +48,8 @@ fn calendar_plan_lane_uses_category_colors() {
     let base = app.config.day_start_minutes;
     app.add_task("Work Block", 60);
     app.add_task("Home Block", 30);
-    app.day.tasks[0].category = Category::Work; // Blue
-    app.day.tasks[1].category = Category::Home; // Yellow
+    app.day.tasks[0].category = Category::WORK; // Blue
+    app.day.tasks[1].category = Category::HOME; // Yellow
 
     // Switch to Calendar
     app.toggle_display_mode();
@@ -89,7 +89,7 @@ fn calendar_actual_lane_uses_category_colors() {
     let base = app.config.day_start_minutes;
     app.add_task("Alpha", 60);
     app.add_task("Bravo", 60);
-    app.day.tasks[1].category = Category::Hobby; // Magenta
+    app.day.tasks[1].category = Category::HOBBY; // Magenta
 
     // Add one closed actual session for the second task
     if let Some(t) = app.day.tasks.get_mut(1) {
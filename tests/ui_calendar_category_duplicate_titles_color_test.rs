@@ -25,8 +25,8 @@ fn calendar_plan_lane_uses_per_range_category_even_with_duplicate_titles() {
     // Two tasks with the same title but different categories
     app.add_task("Same", 40);
     app.add_task("Same", 30);
-    app.day.tasks[0].category = Category::Work; // Blue
-    app.day.tasks[1].category = Category::Home; // Yellow
+    app.day.tasks[0].category = Category::WORK; // Blue
+    app.day.tasks[1].category = Category::HOME; // Yellow
 
     // Switch to Calendar and draw later so Now row does not interfere
     app.toggle_display_mode();
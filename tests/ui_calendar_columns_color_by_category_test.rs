@@ -14,8 +14,8 @@ fn columns_use_each_task_category_color() {
 
     app.add_task("Alpha", 30);
     app.add_task("Bravo", 30);
-    app.day.tasks[0].category = Category::Work; // Blue
-    app.day.tasks[1].category = Category::Home; // Yellow
+    app.day.tasks[0].category = Category::WORK; // Blue
+    app.day.tasks[1].category = Category::HOME; // Yellow
                                                 // Overlap
     if let Some(t) = app.day.tasks.get_mut(0) {
         t.sessions.push(Session { start_min: base + 10, end_min: Some(base + 25) });
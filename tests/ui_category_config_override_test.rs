@@ -17,7 +17,7 @@ color = "cyan"
 
     // Add one Work task and render list
     app.add_task("Deal", 15);
-    app.day.tasks[0].category = Category::Work;
+    app.day.tasks[0].category = Category::WORK;
 
     let backend = TestBackend::new(80, 10);
     let mut terminal = Terminal::new(backend).unwrap();
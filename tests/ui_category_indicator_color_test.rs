@@ -20,9 +20,9 @@ fn category_indicator_renders_expected_colors() {
     app.add_task("B", 15);
     app.add_task("C", 10);
     // Assign categories directly for the test
-    app.day.tasks[0].category = Category::Work;
-    app.day.tasks[1].category = Category::Home;
-    app.day.tasks[2].category = Category::Hobby;
+    app.day.tasks[0].category = Category::WORK;
+    app.day.tasks[1].category = Category::HOME;
+    app.day.tasks[2].category = Category::HOBBY;
 
     terminal.draw(|f| ui::draw(f, &app)).unwrap();
     let buf = terminal.backend().buffer().clone();
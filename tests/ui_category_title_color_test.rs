@@ -20,9 +20,9 @@ fn title_is_colored_by_category_and_done_is_gray() {
     app.add_task("Alpha", 20);
     app.add_task("Bravo", 15);
     app.add_task("Charlie", 10);
-    app.day.tasks[0].category = Category::Work;
-    app.day.tasks[1].category = Category::Home;
-    app.day.tasks[2].category = Category::Hobby;
+    app.day.tasks[0].category = Category::WORK;
+    app.day.tasks[1].category = Category::HOME;
+    app.day.tasks[2].category = Category::HOBBY;
 
     // Mark the third task Done via key flow to stay realistic
     // Move selection to 3rd row and finish (f)
@@ -0,0 +1,75 @@
+use chute_kun::{app::App, clock::Clock, config::Config, storage, task::Session, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+use tempfile::tempdir;
+
+struct FixedClock(u16);
+impl Clock for FixedClock {
+    fn now_minutes(&self) -> u16 {
+        self.0
+    }
+}
+
+fn buf_contains(buf: &ratatui::buffer::Buffer, needle: &str) -> bool {
+    (0..buf.area.height).any(|y| {
+        let s: String = (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect();
+        s.contains(needle)
+    })
+}
+
+fn type_str(app: &mut App, s: &str) {
+    for c in s.chars() {
+        app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+}
+
+#[test]
+fn note_editor_keeps_line_breaks_and_note_persists() {
+    let mut app = App::new();
+    app.add_task("Write report", 30);
+
+    app.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+    assert!(app.is_note_edit());
+    type_str(&mut app, "met Bob");
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT));
+    type_str(&mut app, "follow up");
+    app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(!app.is_note_edit());
+    assert_eq!(app.day.tasks[0].notes, "met Bob\nfollow up");
+
+    // Esc discards a further edit
+    app.handle_key(KeyCode::Char('n'));
+    type_str(&mut app, "!!");
+    app.handle_key(KeyCode::Esc);
+    assert_eq!(app.day.tasks[0].notes, "met Bob\nfollow up");
+
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("snapshot.toml");
+    storage::save_to_path(&app, &p).unwrap();
+    let loaded = storage::load_from_path(&p, Config::default()).unwrap().unwrap();
+    assert_eq!(loaded.day.tasks[0].notes, "met Bob\nfollow up");
+}
+
+#[test]
+fn detail_pane_shows_meta_sessions_and_notes_for_selected_task() {
+    let mut app = App::new();
+    app.add_task("Write report", 30);
+    {
+        let t = &mut app.day.tasks[0];
+        t.sessions = vec![Session { start_min: 9 * 60, end_min: Some(9 * 60 + 25) }];
+        t.actual_min = 25;
+        t.notes = "met Bob".to_string();
+    }
+    app.handle_key(KeyCode::Char('d'));
+    assert!(app.detail_open());
+
+    for (w, h) in [(120, 30), (80, 30)] {
+        let mut terminal = Terminal::new(TestBackend::new(w, h)).unwrap();
+        terminal.draw(|f| ui::draw_with_clock(f, &app, &FixedClock(10 * 60))).unwrap();
+        let buf = terminal.backend().buffer().clone();
+        assert!(buf_contains(&buf, "Detail"), "{}x{}", w, h);
+        assert!(buf_contains(&buf, "30m / 25m (-5m)"), "{}x{}", w, h);
+        assert!(buf_contains(&buf, "09:00–09:25"), "{}x{}", w, h);
+        assert!(buf_contains(&buf, "met Bob"), "{}x{}", w, h);
+    }
+}